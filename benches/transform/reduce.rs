@@ -7,7 +7,7 @@ use criterion::{
 };
 use indexmap::IndexMap;
 use vector::transforms::reduce::{Reduce, ReduceConfig};
-use vector_lib::transform::Transform;
+use vector_lib::transform::MultiOutputTaskTransform;
 
 use crate::common::{consume, FixedLogStream};
 
@@ -50,6 +50,7 @@ fn reduce(c: &mut Criterion) {
                 ends_when: None,
                 starts_when: None,
                 max_events: None,
+                ..Default::default()
             },
         },
     ] {
@@ -58,14 +59,12 @@ fn reduce(c: &mut Criterion) {
             b.to_async(tokio::runtime::Runtime::new().unwrap())
                 .iter_batched(
                     || {
-                        let reduce = Transform::event_task(
-                            Reduce::new(&param.reduce_config, &Default::default()).unwrap(),
-                        )
-                        .into_task();
+                        let reduce =
+                            Reduce::new(&param.reduce_config, &Default::default()).unwrap();
                         (Box::new(reduce), Box::pin(param.input.clone()))
                     },
                     |(reduce, input)| async {
-                        let output = reduce.transform_events(input);
+                        let output = reduce.transform(input);
                         consume(output)
                     },
                     BatchSize::SmallInput,
@@ -34,6 +34,31 @@ impl Discriminant {
             .collect();
         Self { values }
     }
+
+    /// Treats fields set to an empty string as if they were missing, so that both identify the
+    /// same subset of events.
+    #[must_use]
+    pub fn with_empty_as_missing(mut self) -> Self {
+        for value in &mut self.values {
+            if matches!(value, Some(Value::Bytes(bytes)) if bytes.is_empty()) {
+                *value = None;
+            }
+        }
+        self
+    }
+
+    /// Converts the values of all fields, other than nulls, to strings, so that values of
+    /// different types with the same string form, such as `1` and `"1"`, identify the same subset
+    /// of events.
+    #[must_use]
+    pub fn with_values_as_strings(mut self) -> Self {
+        for value in self.values.iter_mut().flatten() {
+            if !matches!(value, Value::Bytes(_) | Value::Null) {
+                *value = Value::from(value.to_string_lossy().into_owned());
+            }
+        }
+        self
+    }
 }
 
 impl PartialEq for Discriminant {
@@ -120,7 +145,12 @@ impl Hash for Discriminant {
 }
 
 // Hashes value for discriminant purposes.
+//
+// The type of each value is hashed along with it, so that values of different types that hash
+// the same bytes, such as `null` and `false`, can't make discriminants with nulls in different
+// positions hash the same.
 fn hash_value<H: Hasher>(hasher: &mut H, value: &Value) {
+    std::mem::discriminant(value).hash(hasher);
     match value {
         // Trivial.
         Value::Bytes(val) => val.hash(hasher),
@@ -142,12 +172,14 @@ fn hash_f64<H: Hasher>(hasher: &mut H, value: f64) {
 }
 
 fn hash_array<H: Hasher>(hasher: &mut H, array: &[Value]) {
+    hasher.write_usize(array.len());
     for val in array {
         hash_value(hasher, val);
     }
 }
 
 fn hash_map<H: Hasher>(hasher: &mut H, map: &ObjectMap) {
+    hasher.write_usize(map.len());
     for (key, val) in map {
         hasher.write(key.as_bytes());
         hash_value(hasher, val);
@@ -205,6 +237,61 @@ mod tests {
         assert_ne!(hash(discriminant_1), hash(discriminant_2));
     }
 
+    #[test]
+    fn empty_as_missing() {
+        let mut event_1 = LogEvent::default();
+        event_1.insert("hostname", "");
+        let event_2 = LogEvent::default();
+
+        let discriminant_fields = vec!["hostname".to_string()];
+
+        let discriminant_1 = Discriminant::from_log_event(&event_1, &discriminant_fields);
+        let discriminant_2 = Discriminant::from_log_event(&event_2, &discriminant_fields);
+        assert_ne!(discriminant_1, discriminant_2);
+
+        let discriminant_1 = discriminant_1.with_empty_as_missing();
+        let discriminant_2 = discriminant_2.with_empty_as_missing();
+        assert_eq!(discriminant_1, discriminant_2);
+        assert_eq!(hash(discriminant_1), hash(discriminant_2));
+    }
+
+    #[test]
+    fn values_as_strings() {
+        let mut event_1 = LogEvent::default();
+        event_1.insert("hostname", 1);
+        let mut event_2 = LogEvent::default();
+        event_2.insert("hostname", "1");
+
+        let discriminant_fields = vec!["hostname".to_string()];
+
+        let discriminant_1 = Discriminant::from_log_event(&event_1, &discriminant_fields);
+        let discriminant_2 = Discriminant::from_log_event(&event_2, &discriminant_fields);
+        assert_ne!(discriminant_1, discriminant_2);
+
+        let discriminant_1 = discriminant_1.with_values_as_strings();
+        let discriminant_2 = discriminant_2.with_values_as_strings();
+        assert_eq!(discriminant_1, discriminant_2);
+        assert_eq!(hash(discriminant_1), hash(discriminant_2));
+    }
+
+    #[test]
+    fn null_positions() {
+        let mut event_1 = LogEvent::default();
+        event_1.insert("host", Value::Null);
+        event_1.insert("region", false);
+        let mut event_2 = LogEvent::default();
+        event_2.insert("host", false);
+        event_2.insert("region", Value::Null);
+
+        let discriminant_fields = vec!["host".to_string(), "region".to_string()];
+
+        let discriminant_1 = Discriminant::from_log_event(&event_1, &discriminant_fields);
+        let discriminant_2 = Discriminant::from_log_event(&event_2, &discriminant_fields);
+
+        assert_ne!(discriminant_1, discriminant_2);
+        assert_ne!(hash(discriminant_1), hash(discriminant_2));
+    }
+
     #[test]
     fn field_order() {
         let mut event_1 = LogEvent::default();
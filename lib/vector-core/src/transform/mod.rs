@@ -29,6 +29,7 @@ pub enum Transform {
     Function(Box<dyn FunctionTransform>),
     Synchronous(Box<dyn SyncTransform>),
     Task(Box<dyn TaskTransform<EventArray>>),
+    MultiOutputTask(Box<dyn MultiOutputTaskTransform>),
 }
 
 impl Transform {
@@ -79,6 +80,15 @@ impl Transform {
         Transform::Task(Box::new(WrapEventTask(v)))
     }
 
+    /// Create a new task transform that can write to multiple outputs.
+    ///
+    /// Like [`SyncTransform`], the outputs must be known in advance and returned via
+    /// `TransformConfig::outputs`. Attempting to send to any output not registered in advance is
+    /// considered a bug and will cause a panic.
+    pub fn multi_output_task(v: impl MultiOutputTaskTransform + 'static) -> Self {
+        Transform::MultiOutputTask(Box::new(v))
+    }
+
     /// Transmute the inner transform into a task transform.
     ///
     /// # Panics
@@ -137,6 +147,18 @@ pub trait TaskTransform<T: EventContainer + 'static>: Send + 'static {
     }
 }
 
+/// Broader than [`TaskTransform`], this trait allows task transforms to write to multiple
+/// outputs.
+///
+/// Each event produced is paired with the name of the output it is sent to, where `None` is the
+/// default output.
+pub trait MultiOutputTaskTransform: Send + 'static {
+    fn transform(
+        self: Box<Self>,
+        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = (Option<String>, Event)> + Send>>;
+}
+
 /// Broader than the simple [`FunctionTransform`], this trait allows transforms to write to
 /// multiple outputs. Those outputs must be known in advanced and returned via
 /// `TransformConfig::outputs`. Attempting to send to any output not registered in advance is
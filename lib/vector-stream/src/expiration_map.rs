@@ -18,7 +18,7 @@ impl<T> Emitter<T> {
 
 /// Similar to `stream.filter_map(..).flatten(..)` but also allows checking for expired events
 /// and flushing when the input stream ends.
-pub fn map_with_expiration<S, T, M, E, F>(
+pub fn map_with_expiration<S, T, U, M, E, F>(
     initial_state: S,
     input: impl Stream<Item = T> + 'static,
     expiration_interval: Duration,
@@ -28,11 +28,11 @@ pub fn map_with_expiration<S, T, M, E, F>(
     mut expiration_fn: E,
     // called once at the end of the input stream
     mut flush_fn: F,
-) -> impl Stream<Item = T>
+) -> impl Stream<Item = U>
 where
-    M: FnMut(&mut S, T, &mut Emitter<T>),
-    E: FnMut(&mut S, &mut Emitter<T>),
-    F: FnMut(&mut S, &mut Emitter<T>),
+    M: FnMut(&mut S, T, &mut Emitter<U>),
+    E: FnMut(&mut S, &mut Emitter<U>),
+    F: FnMut(&mut S, &mut Emitter<U>),
 {
     let mut state = initial_state;
     let mut flush_stream = tokio::time::interval(expiration_interval);
@@ -40,7 +40,7 @@ where
     Box::pin(stream! {
         futures_util::pin_mut!(input);
               loop {
-                let mut emitter = Emitter::<T>::new();
+                let mut emitter = Emitter::<U>::new();
                 let done = tokio::select! {
                     _ = flush_stream.tick() => {
                         expiration_fn(&mut state, &mut emitter);
@@ -117,7 +117,7 @@ mod sematext_metrics;
 mod socket;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 mod splunk_hec;
-#[cfg(feature = "sinks-statsd")]
+#[cfg(any(feature = "sinks-console", feature = "sinks-statsd"))]
 mod statsd_sink;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 mod tag_cardinality_limit;
@@ -255,7 +255,7 @@ pub(crate) use self::sample::*;
 pub(crate) use self::sematext_metrics::*;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 pub(crate) use self::splunk_hec::*;
-#[cfg(feature = "sinks-statsd")]
+#[cfg(any(feature = "sinks-console", feature = "sinks-statsd"))]
 pub(crate) use self::statsd_sink::*;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub(crate) use self::tag_cardinality_limit::*;
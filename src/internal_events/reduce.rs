@@ -1,5 +1,9 @@
-use metrics::counter;
-use vector_lib::internal_event::InternalEvent;
+use std::{path::Path, time::Duration};
+
+use metrics::{counter, histogram};
+use vector_lib::internal_event::{
+    error_stage, error_type, ComponentEventsDropped, InternalEvent, INTENTIONAL,
+};
 
 #[derive(Debug)]
 pub struct ReduceStaleEventFlushed;
@@ -9,3 +13,126 @@ impl InternalEvent for ReduceStaleEventFlushed {
         counter!("stale_events_flushed_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct ReduceGroupEvicted {
+    pub total_bytes: usize,
+    pub max_total_bytes: usize,
+}
+
+impl InternalEvent for ReduceGroupEvicted {
+    fn emit(self) {
+        debug!(
+            message = "Total size of groups exceeds the configured limit; flushing oldest group.",
+            total_bytes = self.total_bytes,
+            max_total_bytes = self.max_total_bytes,
+            internal_log_rate_limit = true,
+        );
+        counter!("groups_evicted_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ReduceFieldDropped<'a> {
+    pub field: &'a str,
+    pub max_fields: usize,
+}
+
+impl InternalEvent for ReduceFieldDropped<'_> {
+    fn emit(self) {
+        debug!(
+            message = "Group has reached the maximum number of fields; dropping field.",
+            field = %self.field,
+            max_fields = self.max_fields,
+            internal_log_rate_limit = true,
+        );
+        counter!("fields_dropped_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ReduceSlowTransactionCompleted {
+    pub duration: Duration,
+    pub threshold: Duration,
+}
+
+impl InternalEvent for ReduceSlowTransactionCompleted {
+    fn emit(self) {
+        debug!(
+            message = "Transaction completed after exceeding the slow threshold.",
+            duration_ms = self.duration.as_millis() as u64,
+            threshold_ms = self.threshold.as_millis() as u64,
+            internal_log_rate_limit = true,
+        );
+        counter!("slow_transactions_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct ReduceGroupLookup {
+    pub duration: Duration,
+}
+
+impl InternalEvent for ReduceGroupLookup {
+    fn emit(self) {
+        histogram!("group_lookup_duration_seconds", self.duration);
+    }
+}
+
+#[derive(Debug)]
+pub struct ReduceOpenGroupAge {
+    pub age: Duration,
+}
+
+impl InternalEvent for ReduceOpenGroupAge {
+    fn emit(self) {
+        histogram!("open_group_age_seconds", self.age);
+    }
+}
+
+#[derive(Debug)]
+pub struct ReduceGroupsFlushed {
+    pub count: usize,
+}
+
+impl InternalEvent for ReduceGroupsFlushed {
+    fn emit(self) {
+        histogram!("groups_flushed_per_tick", self.count as f64);
+    }
+}
+
+#[derive(Debug)]
+pub struct ReducePausedEventDropped;
+
+impl InternalEvent for ReducePausedEventDropped {
+    fn emit(self) {
+        emit!(ComponentEventsDropped::<INTENTIONAL> {
+            count: 1,
+            reason: "Event received while its group was paused.",
+        });
+    }
+}
+
+#[derive(Debug)]
+pub struct ReduceSpillError<'a> {
+    pub error: std::io::Error,
+    pub path: &'a Path,
+}
+
+impl InternalEvent for ReduceSpillError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Failed to write spilled field to file.",
+            path = ?self.path,
+            error = %self.error,
+            error_type = error_type::IO_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::IO_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
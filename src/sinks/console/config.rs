@@ -1,18 +1,42 @@
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::{
+    io::IsTerminal,
+    num::{NonZeroU32, NonZeroU64, NonZeroUsize},
+    path::Path,
+    time::Duration,
+};
+
 use futures::{future, FutureExt};
-use tokio::io;
+use tokio::{io, net::TcpStream};
 use vector_lib::codecs::{
-    encoding::{Framer, FramingConfig},
-    JsonSerializerConfig,
+    encoding::{Framer, FramingConfig, SerializerConfig},
+    JsonSerializerConfig, TextSerializerConfig,
 };
 use vector_lib::configurable::configurable_component;
+use vrl::path::parse_target_path;
 
 use crate::{
     codecs::{Encoder, EncodingConfigWithFraming, SinkType},
+    conditions::AnyCondition,
     config::{AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext},
-    sinks::{console::sink::WriterSink, Healthcheck, VectorSink},
+    sinks::{
+        console::{
+            output::{ConsoleOutput, TcpOutput, TemplatedFiles},
+            sink::{
+                DiffTracker, IntervalStats, ProgressDots, RateLimiter, RingBuffer, TableFormatter,
+                WriterSink,
+            },
+        },
+        util::statsd::StatsdEncoder,
+        Healthcheck, VectorSink,
+    },
+    template::Template,
 };
+#[cfg(unix)]
+use crate::{event::Value, sinks::console::output::SyslogOutput};
 
-/// The [standard stream][standard_streams] to write to.
+/// The destination to write output to, such as a [standard stream][standard_streams].
 ///
 /// [standard_streams]: https://en.wikipedia.org/wiki/Standard_streams
 #[configurable_component]
@@ -30,6 +54,333 @@ pub enum Target {
     ///
     /// [stderr]: https://en.wikipedia.org/wiki/Standard_streams#Standard_error_(stderr)
     Stderr,
+
+    /// Write output to files.
+    File {
+        /// The path of the file to write each event to.
+        ///
+        /// The path is rendered for each event, so events can be written to different files.
+        #[configurable(metadata(docs::examples = "/tmp/vector-%Y-%m-%d.log"))]
+        #[configurable(metadata(docs::examples = "/logs/{{ tenant }}.log"))]
+        path: Template,
+
+        /// The maximum number of files to keep open at once.
+        ///
+        /// When more files are written to, the least recently written file is flushed and
+        /// closed.
+        #[serde(default = "default_max_open_files")]
+        max_open_files: NonZeroUsize,
+
+        /// Whether or not to write a UTF-8 byte order mark (BOM) at the start of each file.
+        ///
+        /// Some Windows tools need the BOM to read the file as UTF-8. It is only written to files
+        /// that are empty when they are opened, and never for binary encodings.
+        #[serde(default)]
+        write_bom: bool,
+
+        #[configurable(derived)]
+        #[serde(default)]
+        compression: Compression,
+
+        /// The number of bytes after which to start a new segment of a file.
+        ///
+        /// When set, each segment is written to its own file, with the time it was started
+        /// inserted before the extension of the path, such as `out-20240101T120000000000000.log`.
+        /// The limit applies to the bytes written before compression. A segment is only rotated
+        /// between records, so it can exceed the limit by up to one record.
+        #[configurable(metadata(docs::type_unit = "bytes"))]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rotate_after_bytes: Option<NonZeroUsize>,
+    },
+
+    /// Write output to a TCP connection.
+    ///
+    /// The connection is opened on the first record, and reopened if writing to it fails.
+    Tcp {
+        /// The address to connect to, including the port.
+        ///
+        /// The host can be a hostname or an IP address.
+        #[configurable(metadata(docs::examples = "127.0.0.1:9000"))]
+        #[configurable(metadata(docs::examples = "localhost:9000"))]
+        address: String,
+    },
+
+    /// Write output to the system logger, such as journald or a syslog daemon.
+    ///
+    /// Each record is sent as its own message, so binary encodings cannot be used. Only available
+    /// on Unix.
+    #[cfg(unix)]
+    Syslog {
+        /// The path of the Unix socket the system logger listens on.
+        #[serde(default = "default_syslog_path")]
+        path: PathBuf,
+
+        #[configurable(derived)]
+        #[serde(default)]
+        facility: SyslogFacility,
+
+        /// The field containing the severity of each event.
+        ///
+        /// The field can hold the name of a severity, such as `err` or `warning`, or its number,
+        /// from `0` for `emerg` to `7` for `debug`. Events without a valid severity are sent with
+        /// `default_severity`.
+        #[configurable(metadata(docs::examples = "level"))]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        severity_field: Option<String>,
+
+        /// The severity of events without a valid severity.
+        #[serde(default)]
+        default_severity: SyslogSeverity,
+    },
+}
+
+/// The syslog facility to send messages with.
+#[cfg(unix)]
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyslogFacility {
+    /// Messages from user-level programs.
+    #[default]
+    User,
+
+    /// Messages from system daemons.
+    Daemon,
+
+    /// Reserved for local use.
+    Local0,
+
+    /// Reserved for local use.
+    Local1,
+
+    /// Reserved for local use.
+    Local2,
+
+    /// Reserved for local use.
+    Local3,
+
+    /// Reserved for local use.
+    Local4,
+
+    /// Reserved for local use.
+    Local5,
+
+    /// Reserved for local use.
+    Local6,
+
+    /// Reserved for local use.
+    Local7,
+}
+
+#[cfg(unix)]
+impl SyslogFacility {
+    /// Returns the numerical code of the facility.
+    pub const fn code(self) -> u8 {
+        match self {
+            SyslogFacility::User => 1,
+            SyslogFacility::Daemon => 3,
+            SyslogFacility::Local0 => 16,
+            SyslogFacility::Local1 => 17,
+            SyslogFacility::Local2 => 18,
+            SyslogFacility::Local3 => 19,
+            SyslogFacility::Local4 => 20,
+            SyslogFacility::Local5 => 21,
+            SyslogFacility::Local6 => 22,
+            SyslogFacility::Local7 => 23,
+        }
+    }
+}
+
+/// The severity of a syslog message.
+#[cfg(unix)]
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyslogSeverity {
+    /// The system is unusable.
+    Emerg,
+
+    /// Action must be taken immediately.
+    Alert,
+
+    /// Critical conditions.
+    Crit,
+
+    /// Error conditions.
+    Err,
+
+    /// Warning conditions.
+    Warning,
+
+    /// Normal but significant conditions.
+    Notice,
+
+    /// Informational messages.
+    #[default]
+    Info,
+
+    /// Debug-level messages.
+    Debug,
+}
+
+#[cfg(unix)]
+impl SyslogSeverity {
+    const ALL: [SyslogSeverity; 8] = [
+        SyslogSeverity::Emerg,
+        SyslogSeverity::Alert,
+        SyslogSeverity::Crit,
+        SyslogSeverity::Err,
+        SyslogSeverity::Warning,
+        SyslogSeverity::Notice,
+        SyslogSeverity::Info,
+        SyslogSeverity::Debug,
+    ];
+
+    /// Returns the numerical code of the severity.
+    pub const fn code(self) -> u8 {
+        self as u8
+    }
+
+    /// Parses a severity from its name or numerical code.
+    ///
+    /// Names are matched case-insensitively, and the common `error` and `warn` spellings are also
+    /// accepted.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Integer(code) => usize::try_from(*code)
+                .ok()
+                .and_then(|code| Self::ALL.get(code).copied()),
+            Value::Bytes(name) => match String::from_utf8_lossy(name).to_lowercase().as_str() {
+                "emerg" => Some(SyslogSeverity::Emerg),
+                "alert" => Some(SyslogSeverity::Alert),
+                "crit" => Some(SyslogSeverity::Crit),
+                "err" | "error" => Some(SyslogSeverity::Err),
+                "warning" | "warn" => Some(SyslogSeverity::Warning),
+                "notice" => Some(SyslogSeverity::Notice),
+                "info" => Some(SyslogSeverity::Info),
+                "debug" => Some(SyslogSeverity::Debug),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn default_syslog_path() -> PathBuf {
+    PathBuf::from("/dev/log")
+}
+
+/// Dual encoding configuration.
+///
+/// When set, each event is written twice to the same output, first encoded with the `first` codec
+/// and then with the `second` one, such as a human readable line followed by a machine readable
+/// one. This replaces the codec of `encoding`; its framing and field options still apply.
+#[configurable_component]
+#[derive(Clone, Copy, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DualEncodingConfig {
+    /// The codec to encode each event with first.
+    #[configurable(metadata(docs::examples = "text"))]
+    pub first: DualEncodingCodec,
+
+    /// The codec to encode each event with second.
+    #[configurable(metadata(docs::examples = "json"))]
+    pub second: DualEncodingCodec,
+}
+
+/// A codec to encode each event with when `dual_encoding` is set.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DualEncodingCodec {
+    /// Encode the `message` field of log events as plain text.
+    Text,
+
+    /// Encode events as [JSON][json].
+    ///
+    /// [json]: https://www.json.org/
+    Json,
+
+    /// Encode log events as [logfmt][logfmt].
+    ///
+    /// [logfmt]: https://brandur.org/logfmt
+    Logfmt,
+}
+
+impl DualEncodingCodec {
+    fn serializer_config(self) -> SerializerConfig {
+        match self {
+            Self::Text => TextSerializerConfig::default().into(),
+            Self::Json => JsonSerializerConfig::default().into(),
+            Self::Logfmt => SerializerConfig::Logfmt,
+        }
+    }
+}
+
+/// The compression to apply to files.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    /// [Gzip][gzip] compression.
+    ///
+    /// A `.gz` suffix is added to the path of each file. Each segment of a rotated file is a
+    /// complete gzip stream.
+    ///
+    /// [gzip]: https://www.gzip.org/
+    Gzip,
+
+    /// No compression.
+    #[default]
+    None,
+}
+
+const fn default_max_open_files() -> NonZeroUsize {
+    NonZeroUsize::MIN.saturating_add(63)
+}
+
+impl Target {
+    fn is_terminal(&self) -> bool {
+        match self {
+            Target::Stdout => std::io::stdout().is_terminal(),
+            Target::Stderr => std::io::stderr().is_terminal(),
+            Target::File { .. } | Target::Tcp { .. } => false,
+            #[cfg(unix)]
+            Target::Syslog { .. } => false,
+        }
+    }
+
+    /// Checks that the target can be written to.
+    ///
+    /// The target is often given through an environment variable, so it is only known once the
+    /// configuration has been interpolated.
+    fn validate(&self) -> crate::Result<()> {
+        match self {
+            Target::Stdout | Target::Stderr => Ok(()),
+            #[cfg(unix)]
+            Target::Syslog { .. } => Ok(()),
+            Target::File { path, .. } => {
+                if path.is_empty() {
+                    return Err("`target.file.path` must not be empty".into());
+                }
+                if !path.is_dynamic() && Path::new(path.get_ref()).is_dir() {
+                    return Err(format!(
+                        "`target.file.path` must not be a directory, found: `{}`",
+                        path.get_ref()
+                    )
+                    .into());
+                }
+                Ok(())
+            }
+            Target::Tcp { address } => {
+                if address.is_empty() {
+                    return Err("`target.tcp.address` must not be empty".into());
+                }
+                Ok(())
+            }
+        }
+    }
 }
 
 /// Configuration for the `console` sink.
@@ -37,7 +388,8 @@ pub enum Target {
     "console",
     "Display observability events in the console, which can be useful for debugging purposes."
 ))]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
 #[serde(deny_unknown_fields)]
 pub struct ConsoleSinkConfig {
     #[configurable(derived)]
@@ -45,8 +397,173 @@ pub struct ConsoleSinkConfig {
     pub target: Target,
 
     #[serde(flatten)]
+    #[derivative(Default(
+        value = "(None::<FramingConfig>, JsonSerializerConfig::default()).into()"
+    ))]
     pub encoding: EncodingConfigWithFraming,
 
+    /// Whether or not to escape control characters in text-based output.
+    ///
+    /// When enabled, ASCII control characters other than newlines and tabs, such as those that
+    /// begin terminal escape sequences, are written in escaped form (for example, `\x1b`). This
+    /// prevents untrusted events from manipulating the terminal.
+    ///
+    /// If not set, control characters are escaped only when the target is a terminal. Binary
+    /// encodings are never modified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sanitize_control_chars: Option<bool>,
+
+    /// Whether or not to prefix each record with the ID of the component the event came from.
+    ///
+    /// The ID is written in brackets, such as `[my_transform] `, or as `[unknown] ` when the
+    /// event does not record the component it came from. Not written for binary encodings.
+    #[serde(default)]
+    pub prefix_component_id: bool,
+
+    /// Whether or not to prefix each record with its line number.
+    ///
+    /// Records are numbered from `1`, in the order they are written, and the number is followed by
+    /// `: `, such as `1: `. The number is written first, before any other prefix, such as the one
+    /// of `prefix_component_id`. Not written for binary encodings.
+    #[serde(default)]
+    pub line_numbers: bool,
+
+    /// A separator to write between consecutive records of different event types.
+    ///
+    /// When set, the separator is written on its own line whenever an event is of a different
+    /// type (log, metric, or trace) than the previous one, which makes streams mixing event types
+    /// easier to read. Not written for binary encodings.
+    #[configurable(metadata(docs::examples = "---"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_separator: Option<String>,
+
+    /// Whether or not to write a footer summarizing the events written when the sink stops.
+    ///
+    /// The footer contains the number of events written, by type, and the total number of bytes
+    /// written for them. With a templated file path, the footer is written to the file of the last
+    /// event, and is not written if no event was. Not written for binary encodings.
+    #[serde(default)]
+    pub emit_footer: bool,
+
+    /// The interval, in milliseconds, at which to write a line of statistics about the events
+    /// seen.
+    ///
+    /// Each line reports the number of events and bytes written per second, and the number of
+    /// events of each type, since the previous line. Unlike `emit_footer`, these lines are written
+    /// periodically while the sink runs. With a templated file path, each line is written to the
+    /// file of the last event, and no line is written before the first event. Not written for binary
+    /// encodings.
+    #[configurable(metadata(docs::examples = 10000, docs::type_unit = "milliseconds"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats_interval_ms: Option<NonZeroU64>,
+
+    /// Whether or not to write only the fields of each log event that changed from the previous
+    /// log event of the same group.
+    ///
+    /// Events are grouped by the value of `diff_key_field`, which is always written. The first
+    /// event of each group is written in full.
+    #[serde(default)]
+    pub diff_only: bool,
+
+    /// The field to group events by when `diff_only` is enabled.
+    ///
+    /// If not set, all events belong to a single group.
+    #[configurable(metadata(docs::examples = "host"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff_key_field: Option<String>,
+
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ring: Option<RingConfig>,
+
+    /// A condition that events must match to be written.
+    ///
+    /// Events that do not match are acknowledged without being written, which is useful to only
+    /// print the events of interest, such as errors, while debugging.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub print_when: Option<AnyCondition>,
+
+    /// Whether or not to write diagnostics about events that could not be written to STDERR.
+    ///
+    /// When enabled, a JSON record describing the problem is written to STDERR for each event
+    /// that fails to encode or that no output could be selected for, and the sink carries on with
+    /// the next event. Otherwise, a failure to encode an event stops the sink.
+    #[serde(default)]
+    pub diagnostics_to_stderr: bool,
+
+    /// Whether or not to write a `.` to STDERR for each event, as a compact progress indicator.
+    ///
+    /// A new line is started every 80 events. The records of the events are still written to
+    /// the target, unless it is STDERR, in which case only the dots are written.
+    #[serde(default)]
+    pub progress_dots: bool,
+
+    /// A field to nest the fields of each log event under before encoding.
+    ///
+    /// Some consumers require each record to be an object with a single top-level field, such as
+    /// `{"event": {...}}`. Other event types are written unchanged. Cannot be used with binary
+    /// encodings.
+    #[configurable(metadata(docs::examples = "event"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrap_field: Option<String>,
+
+    /// Fields of log events whose values are replaced with `***` before encoding.
+    ///
+    /// Only fields present in an event are replaced, so that sensitive values such as passwords
+    /// and tokens can be kept out of shared output without adding fields to other events.
+    #[configurable(metadata(docs::examples = "password", docs::examples = "user.token"))]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redact_fields: Vec<String>,
+
+    /// Whether or not to write metric events as [StatsD][statsd] lines.
+    ///
+    /// When enabled, each metric event is written as one or more lines such as
+    /// `name:value|type|#tags`, regardless of the configured encoding. Other event types are
+    /// encoded as usual. Metrics that StatsD cannot represent are dropped.
+    ///
+    /// [statsd]: https://github.com/statsd/statsd/blob/master/docs/metric_types.md
+    #[serde(default)]
+    pub statsd_metrics: bool,
+
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dual_encoding: Option<DualEncodingConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<TableConfig>,
+
+    /// Whether or not to write log events in the streaming form of `jq --stream`.
+    ///
+    /// Each leaf value of an event is written as a `[path, value]` pair on its own line, and the
+    /// last element of each non-empty array or object is followed by a closing `[path]` event.
+    /// This allows very large events to be processed incrementally, such as with
+    /// `jq -c --stream`. This replaces the codec of `encoding` for log events; other event types
+    /// are encoded as usual. Cannot be used with binary encodings.
+    #[serde(default)]
+    pub json_stream: bool,
+
+    /// The depth at which log events written as JSON start being indented.
+    ///
+    /// Objects and arrays nested at least this deep are pretty-printed, one field per line, while
+    /// shallower ones stay compact. The top-level object is at depth `0`, so a value of `1` keeps
+    /// the fields of an event on one line and indents the objects they contain. This replaces the
+    /// codec of `encoding` for log events; other event types are encoded as usual. Cannot be used
+    /// together with `json_stream`, or with binary encodings.
+    #[configurable(metadata(docs::examples = 1))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pretty_depth: Option<usize>,
+
+    /// The maximum number of records to write each second.
+    ///
+    /// Records over the limit are dropped, but still acknowledged. Before the next record written,
+    /// and when the sink stops, a `dropped N records` line reports how many were dropped since the
+    /// last report, except for binary encodings. This keeps a high volume of events from
+    /// overwhelming the terminal.
+    #[configurable(metadata(docs::examples = 100))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_records_per_second: Option<NonZeroU32>,
+
     #[configurable(derived)]
     #[serde(
         default,
@@ -56,18 +573,185 @@ pub struct ConsoleSinkConfig {
     pub acknowledgements: AcknowledgementsConfig,
 }
 
+/// Ring buffer configuration.
+///
+/// When configured, only the most recent events are held in memory and nothing is written until
+/// a dump is triggered, either by an event matching `dump_when` or, on Unix, by sending `SIGUSR1`
+/// to Vector. Cannot be used with a templated file path.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RingConfig {
+    /// The maximum number of events to hold.
+    pub capacity: NonZeroUsize,
+
+    /// A condition that triggers writing out all held events.
+    ///
+    /// The event matching the condition is written last.
+    pub dump_when: Option<AnyCondition>,
+}
+
+/// Table configuration.
+///
+/// When configured, each log event is written as a row of fixed-width columns, one for each of
+/// the given fields, separated by a space, instead of being encoded. Values longer than their
+/// column are truncated, and shorter ones are padded with spaces. Missing fields are written as
+/// blank columns. Other event types are encoded as usual. Cannot be used with binary encodings.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TableConfig {
+    /// The fields to write as columns, in order.
+    #[configurable(metadata(docs::examples = "host", docs::examples = "message"))]
+    pub columns: Vec<String>,
+
+    /// The width of each column, in characters.
+    ///
+    /// Must have the same number of entries as `columns`.
+    #[configurable(metadata(docs::examples = 16, docs::examples = 60))]
+    pub widths: Vec<usize>,
+
+    /// Whether or not to write a header row with the names of the columns before the first row.
+    #[serde(default)]
+    pub header: bool,
+}
+
+impl TableConfig {
+    fn build(&self) -> crate::Result<TableFormatter> {
+        if self.columns.len() != self.widths.len() {
+            return Err("`table.widths` must have one entry for each of `table.columns`".into());
+        }
+        let columns = self
+            .columns
+            .iter()
+            .cloned()
+            .zip(self.widths.iter().copied())
+            .collect();
+        Ok(TableFormatter::new(columns, self.header))
+    }
+}
+
 const fn default_target() -> Target {
     Target::Stdout
 }
 
 impl GenerateConfig for ConsoleSinkConfig {
     fn generate_config() -> toml::Value {
-        toml::Value::try_from(Self {
-            target: Target::Stdout,
-            encoding: (None::<FramingConfig>, JsonSerializerConfig::default()).into(),
-            acknowledgements: Default::default(),
-        })
-        .unwrap()
+        toml::Value::try_from(Self::default()).unwrap()
+    }
+}
+
+impl ConsoleSinkConfig {
+    /// Whether events are encoded in a binary format, whose framing would be corrupted by text
+    /// decorations.
+    fn is_binary(&self) -> bool {
+        self.dual_encoding.is_none()
+            && matches!(
+                self.encoding.config().1,
+                SerializerConfig::Avro { .. }
+                    | SerializerConfig::Native
+                    | SerializerConfig::Protobuf(_)
+            )
+    }
+
+    /// Builds the sink writing encoded events to the given output.
+    fn writer_sink<O: ConsoleOutput>(&self, output: O) -> crate::Result<WriterSink<O>> {
+        let transformer = self.encoding.transformer();
+        let (framer, serializer) = self.encoding.build(SinkType::StreamBased)?;
+        let (encoder, dual_encoder) = match self.dual_encoding {
+            Some(DualEncodingConfig { first, second }) => (
+                Encoder::<Framer>::new(framer.clone(), first.serializer_config().build()?),
+                Some(Encoder::<Framer>::new(
+                    framer,
+                    second.serializer_config().build()?,
+                )),
+            ),
+            None => (Encoder::<Framer>::new(framer, serializer), None),
+        };
+
+        let is_binary = self.is_binary();
+        let sanitize_control_chars = !is_binary
+            && self
+                .sanitize_control_chars
+                .unwrap_or_else(|| self.target.is_terminal());
+        // Text decorations would corrupt the framing of binary encodings.
+        let prefix_component_id = !is_binary && self.prefix_component_id;
+        let line_numbers = !is_binary && self.line_numbers;
+        let type_separator = self.type_separator.clone().filter(|_| !is_binary);
+        let emit_footer = !is_binary && self.emit_footer;
+        let stats = self
+            .stats_interval_ms
+            .filter(|_| !is_binary)
+            .map(|interval| IntervalStats::new(Duration::from_millis(interval.get())));
+
+        let ring = match &self.ring {
+            Some(ring) => {
+                let dump_when = ring
+                    .dump_when
+                    .as_ref()
+                    .map(|condition| condition.build(&Default::default()))
+                    .transpose()?;
+                Some(RingBuffer::new(ring.capacity.get(), dump_when))
+            }
+            None => None,
+        };
+
+        let print_when = self
+            .print_when
+            .as_ref()
+            .map(|condition| condition.build(&Default::default()))
+            .transpose()?;
+
+        let diagnostics = self
+            .diagnostics_to_stderr
+            .then(|| Box::new(io::stderr()) as Box<dyn ConsoleOutput>);
+
+        let progress = self.progress_dots.then(|| {
+            ProgressDots::new(
+                Box::new(io::stderr()),
+                matches!(self.target, Target::Stderr),
+            )
+        });
+
+        let statsd = self.statsd_metrics.then(|| StatsdEncoder::new(None));
+
+        let table = self.table.as_ref().map(TableConfig::build).transpose()?;
+        let redact_fields = self
+            .redact_fields
+            .iter()
+            .map(|field| {
+                parse_target_path(field).map_err(|error| {
+                    format!("invalid `redact_fields` entry {:?}: {}", field, error)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let rate_limit = self
+            .max_records_per_second
+            .map(|max| RateLimiter::new(max).write_notes(!is_binary));
+
+        Ok(WriterSink::new(output, transformer, encoder)
+            .sanitize_control_chars(sanitize_control_chars)
+            .prefix_component_id(prefix_component_id)
+            .type_separator(type_separator)
+            .emit_footer(emit_footer)
+            .diff(
+                self.diff_only
+                    .then(|| DiffTracker::new(self.diff_key_field.clone())),
+            )
+            .ring(ring)
+            .print_when(print_when)
+            .diagnostics(diagnostics)
+            .wrap_field(self.wrap_field.clone())
+            .redact_fields(redact_fields)
+            .statsd(statsd)
+            .dual_encoder(dual_encoder)
+            .table(table)
+            .rate_limit(rate_limit)
+            .progress(progress)
+            .json_stream(self.json_stream)
+            .pretty_depth(self.pretty_depth)
+            .line_numbers(line_numbers)
+            .stats(stats))
     }
 }
 
@@ -75,24 +759,75 @@ impl GenerateConfig for ConsoleSinkConfig {
 #[typetag::serde(name = "console")]
 impl SinkConfig for ConsoleSinkConfig {
     async fn build(&self, _cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
-        let transformer = self.encoding.transformer();
-        let (framer, serializer) = self.encoding.build(SinkType::StreamBased)?;
-        let encoder = Encoder::<Framer>::new(framer, serializer);
-
-        let sink: VectorSink = match self.target {
-            Target::Stdout => VectorSink::from_event_streamsink(WriterSink {
-                output: io::stdout(),
-                transformer,
-                encoder,
-            }),
-            Target::Stderr => VectorSink::from_event_streamsink(WriterSink {
-                output: io::stderr(),
-                transformer,
-                encoder,
-            }),
+        self.target.validate()?;
+        if self.ring.is_some()
+            && matches!(&self.target, Target::File { path, .. } if path.is_dynamic())
+        {
+            // A dump would write every held event to the file of the last one.
+            return Err("`ring` cannot be used with a templated `target.file.path`".into());
+        }
+        if self.json_stream && self.pretty_depth.is_some() {
+            return Err("`json_stream` and `pretty_depth` cannot both be set".into());
+        }
+        if self.is_binary() {
+            // These options replace or reshape the output of the codec, which would corrupt the
+            // framing of binary encodings.
+            let text_options = [
+                ("json_stream", self.json_stream),
+                ("pretty_depth", self.pretty_depth.is_some()),
+                ("table", self.table.is_some()),
+                ("wrap_field", self.wrap_field.is_some()),
+            ];
+            if let Some((option, _)) = text_options.iter().find(|(_, set)| *set) {
+                return Err(format!("`{}` cannot be used with a binary encoding", option).into());
+            }
+            // Syslog messages are split on newlines, which can appear anywhere in binary frames.
+            #[cfg(unix)]
+            if matches!(self.target, Target::Syslog { .. }) {
+                return Err("the `syslog` target cannot be used with a binary encoding".into());
+            }
+        }
+
+        let sink = match &self.target {
+            Target::Stdout => VectorSink::from_event_streamsink(self.writer_sink(io::stdout())?),
+            Target::Stderr => VectorSink::from_event_streamsink(self.writer_sink(io::stderr())?),
+            Target::File {
+                path,
+                max_open_files,
+                write_bom,
+                compression,
+                rotate_after_bytes,
+            } => VectorSink::from_event_streamsink(
+                self.writer_sink(
+                    TemplatedFiles::new(path.clone(), *max_open_files)
+                        .write_bom(*write_bom && !self.is_binary())
+                        .compression(*compression)
+                        .rotate_after_bytes(*rotate_after_bytes),
+                )?,
+            ),
+            Target::Tcp { address } => VectorSink::from_event_streamsink(
+                self.writer_sink(TcpOutput::new(address.clone()))?,
+            ),
+            #[cfg(unix)]
+            Target::Syslog {
+                path,
+                facility,
+                severity_field,
+                default_severity,
+            } => VectorSink::from_event_streamsink(self.writer_sink(SyslogOutput::new(
+                path.clone(),
+                *facility,
+                severity_field.clone(),
+                *default_severity,
+            ))?),
+        };
+
+        let healthcheck = match &self.target {
+            Target::Tcp { address } => tcp_healthcheck(address.clone()).boxed(),
+            _ => future::ok(()).boxed(),
         };
 
-        Ok((sink, future::ok(()).boxed()))
+        Ok((sink, healthcheck))
     }
 
     fn input(&self) -> Input {
@@ -104,12 +839,262 @@ impl SinkConfig for ConsoleSinkConfig {
     }
 }
 
+/// Checks that a connection can be opened to the address of a TCP target.
+async fn tcp_healthcheck(address: String) -> crate::Result<()> {
+    TcpStream::connect(address.as_str()).await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
+    use bytes::BytesMut;
+    use futures::stream;
+    use tokio::io::AsyncReadExt;
+    use tokio_util::codec::{Decoder, LengthDelimitedCodec};
+    use vector_lib::codecs::{decoding::format::Deserializer, NativeDeserializerConfig};
+    use vector_lib::config::LogNamespace;
+
     use super::*;
+    use crate::{
+        config::interpolate,
+        event::{Event, LogEvent, Metric, MetricKind, MetricValue},
+        test_util::temp_dir,
+    };
 
     #[test]
     fn generate_config() {
         crate::test_util::test_generate_config::<ConsoleSinkConfig>();
     }
+
+    fn resolve(target: &str) -> ConsoleSinkConfig {
+        let (config, _) = interpolate(
+            "target = ${CONSOLE_TARGET}\nencoding.codec = \"json\"",
+            &HashMap::from([("CONSOLE_TARGET".to_string(), target.to_string())]),
+            true,
+        )
+        .unwrap();
+        toml::from_str(&config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn resolves_target_from_environment() {
+        let config = resolve(r#""stderr""#);
+        assert!(matches!(config.target, Target::Stderr));
+        assert!(config.build(SinkContext::default()).await.is_ok());
+
+        let directory = temp_dir();
+        let config = resolve(&format!(
+            r#"{{ file = {{ path = "{}/out.log" }} }}"#,
+            directory.display()
+        ));
+        assert!(matches!(config.target, Target::File { .. }));
+        assert!(config.build(SinkContext::default()).await.is_ok());
+
+        std::fs::create_dir_all(&directory).unwrap();
+        let config = resolve(&format!(
+            r#"{{ file = {{ path = "{}" }} }}"#,
+            directory.display()
+        ));
+        assert!(config.build(SinkContext::default()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_ring_with_templated_file_path() {
+        let config: ConsoleSinkConfig = toml::from_str(&format!(
+            r#"
+target.file.path = "{}/{{{{ tenant }}}}.log"
+encoding.codec = "json"
+ring.capacity = 10
+"#,
+            temp_dir().display()
+        ))
+        .unwrap();
+        assert!(config.build(SinkContext::default()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn dual_encoding_takes_two_codecs() {
+        let config: ConsoleSinkConfig = toml::from_str(
+            r#"
+encoding.codec = "json"
+dual_encoding = { first = "text", second = "json" }
+"#,
+        )
+        .unwrap();
+        assert!(config.build(SinkContext::default()).await.is_ok());
+
+        assert!(toml::from_str::<ConsoleSinkConfig>(
+            r#"
+encoding.codec = "json"
+dual_encoding = { first = "text" }
+"#,
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_text_options_with_binary_encoding() {
+        for option in [
+            "json_stream = true",
+            "pretty_depth = 1",
+            "table = { columns = [\"message\"], widths = [10] }",
+            "wrap_field = \"event\"",
+        ] {
+            let config: ConsoleSinkConfig =
+                toml::from_str(&format!("encoding.codec = \"native\"\n{}", option)).unwrap();
+            assert!(config.build(SinkContext::default()).await.is_err());
+        }
+
+        #[cfg(unix)]
+        {
+            let config: ConsoleSinkConfig =
+                toml::from_str("target.syslog = {}\nencoding.codec = \"native\"").unwrap();
+            assert!(config.build(SinkContext::default()).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn native_encoding_round_trips() {
+        let path = temp_dir().join("out.bin");
+        let config: ConsoleSinkConfig = toml::from_str(&format!(
+            r#"
+target.file.path = "{}"
+target.file.write_bom = true
+encoding.codec = "native"
+prefix_component_id = true
+type_separator = "---"
+emit_footer = true
+"#,
+            path.display()
+        ))
+        .unwrap();
+
+        let events = vec![
+            Event::Log(LogEvent::from("foo")),
+            Event::Metric(Metric::new(
+                "counter",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            )),
+            Event::Log(LogEvent::from("bar")),
+        ];
+
+        let (sink, _) = config.build(SinkContext::default()).await.unwrap();
+        sink.run(stream::iter(events)).await.unwrap();
+
+        let mut bytes = BytesMut::from(std::fs::read(&path).unwrap().as_slice());
+        let mut codec = LengthDelimitedCodec::new();
+        let mut decoded = Vec::new();
+        while let Some(frame) = codec.decode(&mut bytes).unwrap() {
+            decoded.extend(
+                NativeDeserializerConfig
+                    .build()
+                    .parse(frame.freeze(), LogNamespace::Legacy)
+                    .unwrap(),
+            );
+        }
+        assert!(bytes.is_empty());
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(decoded[0].as_log()["message"], "foo".into());
+        assert_eq!(decoded[1].as_metric().name(), "counter");
+        assert_eq!(
+            decoded[1].as_metric().value(),
+            &MetricValue::Counter { value: 1.0 }
+        );
+        assert_eq!(decoded[2].as_log()["message"], "bar".into());
+    }
+
+    #[tokio::test]
+    async fn streams_to_tcp() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let config: ConsoleSinkConfig = toml::from_str(&format!(
+            r#"
+target.tcp.address = "{}"
+encoding.codec = "text"
+"#,
+            address
+        ))
+        .unwrap();
+
+        let events = vec![
+            Event::Log(LogEvent::from("foo")),
+            Event::Log(LogEvent::from("bar")),
+        ];
+
+        let (sink, healthcheck) = config.build(SinkContext::default()).await.unwrap();
+        healthcheck.await.unwrap();
+        // The healthcheck opens a connection of its own.
+        listener.accept().await.unwrap();
+
+        sink.run(stream::iter(events)).await.unwrap();
+
+        let (mut connection, _) = listener.accept().await.unwrap();
+        let mut written = String::new();
+        connection.read_to_string(&mut written).await.unwrap();
+        assert_eq!(written, "foo\nbar\n");
+    }
+
+    #[tokio::test]
+    async fn tcp_healthcheck_fails_without_listener() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+
+        let config: ConsoleSinkConfig = toml::from_str(&format!(
+            r#"
+target.tcp.address = "{}"
+encoding.codec = "text"
+"#,
+            address
+        ))
+        .unwrap();
+
+        let (_, healthcheck) = config.build(SinkContext::default()).await.unwrap();
+        assert!(healthcheck.await.is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn sends_to_syslog_with_severity() {
+        let directory = temp_dir();
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("log.sock");
+        let socket = tokio::net::UnixDatagram::bind(&path).unwrap();
+
+        let config: ConsoleSinkConfig = toml::from_str(&format!(
+            r#"
+target.syslog.path = "{}"
+target.syslog.facility = "local0"
+target.syslog.severity_field = "level"
+encoding.codec = "text"
+"#,
+            path.display()
+        ))
+        .unwrap();
+
+        let mut error = LogEvent::from("failed");
+        error.insert("level", "err");
+        let mut debug = LogEvent::from("details");
+        debug.insert("level", 7);
+        let events = vec![
+            Event::Log(error),
+            Event::Log(debug),
+            Event::Log(LogEvent::from("done")),
+        ];
+
+        let (sink, _) = config.build(SinkContext::default()).await.unwrap();
+        sink.run(stream::iter(events)).await.unwrap();
+
+        let mut messages = Vec::new();
+        let mut buf = [0; 1024];
+        for _ in 0..3 {
+            let len = socket.recv(&mut buf).await.unwrap();
+            messages.push(String::from_utf8_lossy(&buf[..len]).into_owned());
+        }
+        assert_eq!(messages, vec!["<131>failed", "<135>details", "<134>done"]);
+    }
 }
@@ -1,4 +1,5 @@
 mod config;
+mod output;
 mod sink;
 
 pub use config::{ConsoleSinkConfig, Target};
@@ -0,0 +1,409 @@
+use std::{
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
+
+use async_compression::tokio::write::GzipEncoder;
+use async_trait::async_trait;
+use chrono::Utc;
+use lru::LruCache;
+#[cfg(unix)]
+use tokio::net::UnixDatagram;
+use tokio::{
+    fs::{self, File},
+    io::{self, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use super::config::Compression;
+#[cfg(unix)]
+use super::config::{SyslogFacility, SyslogSeverity};
+use crate::{event::Event, internal_events::TemplateRenderingError, template::Template};
+
+/// A destination that encoded events are written to.
+#[async_trait]
+pub trait ConsoleOutput: Send + Sync + Unpin {
+    /// Selects the destination of the event about to be written.
+    ///
+    /// Returns `false` if the event cannot be written and should be dropped.
+    fn route(&mut self, _event: &Event) -> bool {
+        true
+    }
+
+    /// Whether a destination has been selected, so that records not written for an event, such as
+    /// the footer, can be written.
+    fn has_destination(&self) -> bool {
+        true
+    }
+
+    async fn write_all(&mut self, bytes: &[u8]) -> io::Result<()>;
+
+    async fn flush(&mut self) -> io::Result<()>;
+
+    /// Flushes and closes the output when the sink stops.
+    async fn close(&mut self) -> io::Result<()> {
+        self.flush().await
+    }
+}
+
+#[async_trait]
+impl<T> ConsoleOutput for T
+where
+    T: io::AsyncWrite + Send + Sync + Unpin,
+{
+    async fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+        AsyncWriteExt::write_all(self, bytes).await
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        AsyncWriteExt::flush(self).await
+    }
+}
+
+/// Writes each event to the file at the path rendered from its template.
+///
+/// At most `max_open_files` files are kept open. When more are needed, the least recently
+/// written file is flushed and closed.
+pub struct TemplatedFiles {
+    path: Template,
+    files: LruCache<String, OutFile>,
+    current: Option<String>,
+    write_bom: bool,
+    compression: Compression,
+    rotate_after_bytes: Option<usize>,
+}
+
+/// The UTF-8 byte order mark.
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+impl TemplatedFiles {
+    pub fn new(path: Template, max_open_files: NonZeroUsize) -> Self {
+        Self {
+            path,
+            files: LruCache::new(max_open_files),
+            current: None,
+            write_bom: false,
+            compression: Compression::None,
+            rotate_after_bytes: None,
+        }
+    }
+
+    /// Sets whether a UTF-8 byte order mark is written at the start of each new file.
+    pub const fn write_bom(mut self, write_bom: bool) -> Self {
+        self.write_bom = write_bom;
+        self
+    }
+
+    /// Sets the compression applied to each file.
+    pub const fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets the number of bytes after which a new segment of a file is started.
+    pub fn rotate_after_bytes(mut self, rotate_after_bytes: Option<NonZeroUsize>) -> Self {
+        self.rotate_after_bytes = rotate_after_bytes.map(NonZeroUsize::get);
+        self
+    }
+
+    /// Returns the path of the file to open for the rendered path.
+    ///
+    /// When rotating, each segment is written to its own file, with the time it was opened
+    /// inserted before the extension, such as `out-20240101T120000000000000.log`.
+    fn file_path(&self, path: &str) -> PathBuf {
+        let mut path = PathBuf::from(path);
+        if self.rotate_after_bytes.is_some() {
+            let stem = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let timestamp = Utc::now().format("%Y%m%dT%H%M%S%9f");
+            let file_name = match path.extension() {
+                Some(extension) => {
+                    format!("{}-{}.{}", stem, timestamp, extension.to_string_lossy())
+                }
+                None => format!("{}-{}", stem, timestamp),
+            };
+            path.set_file_name(file_name);
+        }
+        if self.compression == Compression::Gzip {
+            let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+            file_name.push(".gz");
+            path.set_file_name(file_name);
+        }
+        path
+    }
+
+    async fn open(&self, path: &str) -> io::Result<OutFile> {
+        let file = open_file(self.file_path(path)).await?;
+        let is_empty = file.metadata().await?.len() == 0;
+        let mut file = OutFile::new(file, self.compression);
+        if self.write_bom && is_empty {
+            file.write_all(BOM).await?;
+            file.written = 0;
+        }
+        Ok(file)
+    }
+}
+
+#[async_trait]
+impl ConsoleOutput for TemplatedFiles {
+    fn route(&mut self, event: &Event) -> bool {
+        match self.path.render_string(event) {
+            Ok(path) => {
+                self.current = Some(path);
+                true
+            }
+            Err(error) => {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some("path"),
+                    drop_event: true,
+                });
+                false
+            }
+        }
+    }
+
+    fn has_destination(&self) -> bool {
+        self.current.is_some()
+    }
+
+    async fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let Some(path) = self.current.as_ref() else {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "no file path was rendered",
+            ));
+        };
+
+        // Segments are only rotated between writes, so a write spanning several records, such as
+        // a dump of the ring buffer, is kept whole in one segment.
+        let rotate = match (self.rotate_after_bytes, self.files.peek(path)) {
+            (Some(max), Some(file)) => file.written > 0 && file.written + bytes.len() > max,
+            _ => false,
+        };
+        if rotate {
+            if let Some(mut file) = self.files.pop(path) {
+                file.close().await?;
+            }
+        }
+
+        if !self.files.contains(path) {
+            let file = self.open(path).await?;
+            if let Some((_, mut evicted)) = self.files.push(path.clone(), file) {
+                evicted.close().await?;
+            }
+        }
+        let file = self
+            .files
+            .get_mut(path)
+            .expect("file should have been opened");
+        file.write_all(bytes).await
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        for (_, file) in self.files.iter_mut() {
+            file.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> io::Result<()> {
+        while let Some((_, mut file)) = self.files.pop_lru() {
+            file.close().await?;
+        }
+        Ok(())
+    }
+}
+
+/// Sends each record to the system logger through its Unix socket, such as `/dev/log`.
+///
+/// Each line written is sent as its own message, with a priority made of the facility and the
+/// severity of the event it was written for.
+#[cfg(unix)]
+pub struct SyslogOutput {
+    path: PathBuf,
+    socket: Option<UnixDatagram>,
+    facility: SyslogFacility,
+    severity_field: Option<String>,
+    default_severity: SyslogSeverity,
+    severity: SyslogSeverity,
+}
+
+#[cfg(unix)]
+impl SyslogOutput {
+    pub const fn new(
+        path: PathBuf,
+        facility: SyslogFacility,
+        severity_field: Option<String>,
+        default_severity: SyslogSeverity,
+    ) -> Self {
+        Self {
+            path,
+            socket: None,
+            facility,
+            severity_field,
+            default_severity,
+            severity: default_severity,
+        }
+    }
+}
+
+#[cfg(unix)]
+#[async_trait]
+impl ConsoleOutput for SyslogOutput {
+    fn route(&mut self, event: &Event) -> bool {
+        self.severity = self
+            .severity_field
+            .as_ref()
+            .zip(event.maybe_as_log())
+            .and_then(|(field, log)| log.parse_path_and_get_value(field.as_str()).ok().flatten())
+            .and_then(SyslogSeverity::from_value)
+            .unwrap_or(self.default_severity);
+        true
+    }
+
+    async fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if self.socket.is_none() {
+            self.socket = Some(UnixDatagram::unbound()?);
+        }
+        let socket = self
+            .socket
+            .as_ref()
+            .expect("socket should have been created");
+
+        let priority = self.facility.code() * 8 + self.severity.code();
+        for line in bytes.split(|byte| *byte == b'\n') {
+            if line.is_empty() {
+                continue;
+            }
+            let mut message = format!("<{}>", priority).into_bytes();
+            message.extend_from_slice(line);
+            socket.send_to(&message, &self.path).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Streams records to a TCP endpoint.
+///
+/// The connection is opened on the first write. When a write fails, the connection is reopened
+/// and the write is retried once, so that the sink carries on if the endpoint restarts.
+pub struct TcpOutput {
+    address: String,
+    stream: Option<TcpStream>,
+}
+
+impl TcpOutput {
+    pub const fn new(address: String) -> Self {
+        Self {
+            address,
+            stream: None,
+        }
+    }
+
+    async fn stream(&mut self) -> io::Result<&mut TcpStream> {
+        if self.stream.is_none() {
+            self.stream = Some(TcpStream::connect(self.address.as_str()).await?);
+        }
+        Ok(self
+            .stream
+            .as_mut()
+            .expect("stream should have been connected"))
+    }
+}
+
+#[async_trait]
+impl ConsoleOutput for TcpOutput {
+    async fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let result = AsyncWriteExt::write_all(self.stream().await?, bytes).await;
+        if let Err(error) = result {
+            warn!(
+                message = "Error writing to TCP connection; reconnecting.",
+                address = %self.address,
+                %error,
+            );
+            self.stream = None;
+            AsyncWriteExt::write_all(self.stream().await?, bytes).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        match self.stream.as_mut() {
+            Some(stream) => AsyncWriteExt::flush(stream).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn close(&mut self) -> io::Result<()> {
+        match self.stream.take() {
+            Some(mut stream) => stream.shutdown().await,
+            None => Ok(()),
+        }
+    }
+}
+
+/// An open file, or segment of a file when rotating.
+struct OutFile {
+    writer: OutFileWriter,
+    // The number of uncompressed bytes written to this file since it was opened.
+    written: usize,
+}
+
+enum OutFileWriter {
+    Regular(File),
+    Gzip(GzipEncoder<File>),
+}
+
+impl OutFile {
+    fn new(file: File, compression: Compression) -> Self {
+        let writer = match compression {
+            Compression::None => OutFileWriter::Regular(file),
+            Compression::Gzip => OutFileWriter::Gzip(GzipEncoder::new(file)),
+        };
+        Self { writer, written: 0 }
+    }
+
+    async fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+        match &mut self.writer {
+            OutFileWriter::Regular(file) => file.write_all(bytes).await?,
+            OutFileWriter::Gzip(gzip) => gzip.write_all(bytes).await?,
+        }
+        self.written += bytes.len();
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        match &mut self.writer {
+            OutFileWriter::Regular(file) => file.flush().await,
+            OutFileWriter::Gzip(gzip) => gzip.flush().await,
+        }
+    }
+
+    /// Flushes all data, finishing the gzip stream when compressing.
+    async fn close(&mut self) -> io::Result<()> {
+        match &mut self.writer {
+            OutFileWriter::Regular(file) => file.shutdown().await,
+            OutFileWriter::Gzip(gzip) => gzip.shutdown().await,
+        }
+    }
+}
+
+async fn open_file(path: impl AsRef<Path>) -> io::Result<File> {
+    if let Some(parent) = path.as_ref().parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+}
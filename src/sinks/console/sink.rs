@@ -1,79 +1,1021 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Write as _,
+    num::NonZeroU32,
+    time::{Duration, Instant},
+};
+
 use async_trait::async_trait;
-use bytes::BytesMut;
-use futures::{stream::BoxStream, StreamExt};
-use tokio::{io, io::AsyncWriteExt};
+use bytes::{BufMut, BytesMut};
+use chrono::Utc;
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use tokio_stream::wrappers::IntervalStream;
 use tokio_util::codec::Encoder as _;
 use vector_lib::codecs::encoding::Framer;
 use vector_lib::{
     internal_event::{
         ByteSize, BytesSent, CountByteSize, EventsSent, InternalEventHandle as _, Output, Protocol,
     },
+    json_size::JsonSize,
     EstimatedJsonEncodedSizeOf,
 };
 
+use vrl::{event_path, path::OwnedTargetPath};
+
 use crate::{
     codecs::{Encoder, Transformer},
-    event::{Event, EventStatus, Finalizable},
-    sinks::util::StreamSink,
+    conditions::Condition,
+    event::{Event, EventStatus, Finalizable, LogEvent, ObjectMap, Value},
+    sinks::{
+        console::output::ConsoleOutput,
+        util::{statsd::StatsdEncoder, StreamSink},
+    },
 };
 
 pub struct WriterSink<T> {
-    pub output: T,
-    pub transformer: Transformer,
-    pub encoder: Encoder<Framer>,
+    output: T,
+    transformer: Transformer,
+    encoder: Encoder<Framer>,
+    sanitize_control_chars: bool,
+    prefix_component_id: bool,
+    type_separator: Option<String>,
+    emit_footer: bool,
+    diff: Option<DiffTracker>,
+    ring: Option<RingBuffer>,
+    print_when: Option<Condition>,
+    diagnostics: Option<Box<dyn ConsoleOutput>>,
+    wrap_field: Option<String>,
+    redact_fields: Vec<OwnedTargetPath>,
+    statsd: Option<StatsdEncoder>,
+    dual_encoder: Option<Encoder<Framer>>,
+    table: Option<TableFormatter>,
+    rate_limit: Option<RateLimiter>,
+    progress: Option<ProgressDots>,
+    json_stream: bool,
+    pretty_depth: Option<usize>,
+    line_numbers: bool,
+    stats: Option<IntervalStats>,
+}
+
+/// The component ID written for events that do not record the component they came from.
+const UNKNOWN_COMPONENT_ID: &str = "unknown";
+
+/// Holds the most recently encoded events until a dump is triggered.
+pub struct RingBuffer {
+    capacity: usize,
+    dump_when: Option<Condition>,
+    entries: VecDeque<(BytesMut, JsonSize)>,
+}
+
+impl RingBuffer {
+    pub fn new(capacity: usize, dump_when: Option<Condition>) -> Self {
+        Self {
+            capacity,
+            dump_when,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    fn push(&mut self, bytes: BytesMut, byte_size: JsonSize) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((bytes, byte_size));
+    }
+
+    /// Takes all buffered events, returning their concatenated bytes along with the count and
+    /// size of the events.
+    fn drain(&mut self) -> (BytesMut, CountByteSize) {
+        let mut bytes = BytesMut::new();
+        let mut byte_size = JsonSize::zero();
+        let count = self.entries.len();
+        for (entry, entry_size) in self.entries.drain(..) {
+            bytes.extend_from_slice(&entry);
+            byte_size += entry_size;
+        }
+        (bytes, CountByteSize(count, byte_size))
+    }
+}
+
+impl<T> WriterSink<T> {
+    pub fn new(output: T, transformer: Transformer, encoder: Encoder<Framer>) -> Self {
+        Self {
+            output,
+            transformer,
+            encoder,
+            sanitize_control_chars: false,
+            prefix_component_id: false,
+            type_separator: None,
+            emit_footer: false,
+            diff: None,
+            ring: None,
+            print_when: None,
+            diagnostics: None,
+            wrap_field: None,
+            redact_fields: Vec::new(),
+            statsd: None,
+            dual_encoder: None,
+            table: None,
+            rate_limit: None,
+            progress: None,
+            json_stream: false,
+            pretty_depth: None,
+            line_numbers: false,
+            stats: None,
+        }
+    }
+
+    /// Sets whether control characters are escaped before being written.
+    pub const fn sanitize_control_chars(mut self, sanitize_control_chars: bool) -> Self {
+        self.sanitize_control_chars = sanitize_control_chars;
+        self
+    }
+
+    /// Sets whether each record is prefixed with the ID of the component the event came from.
+    pub const fn prefix_component_id(mut self, prefix_component_id: bool) -> Self {
+        self.prefix_component_id = prefix_component_id;
+        self
+    }
+
+    /// Sets the separator written between consecutive records of different event types.
+    pub fn type_separator(mut self, type_separator: Option<String>) -> Self {
+        self.type_separator = type_separator;
+        self
+    }
+
+    /// Sets whether a summary footer is written when the sink stops.
+    pub const fn emit_footer(mut self, emit_footer: bool) -> Self {
+        self.emit_footer = emit_footer;
+        self
+    }
+
+    /// Sets the tracker used to write only the fields that changed from the previous event.
+    pub fn diff(mut self, diff: Option<DiffTracker>) -> Self {
+        self.diff = diff;
+        self
+    }
+
+    /// Sets the buffer holding events until a dump is triggered.
+    pub fn ring(mut self, ring: Option<RingBuffer>) -> Self {
+        self.ring = ring;
+        self
+    }
+
+    /// Sets the condition events must match to be written.
+    pub fn print_when(mut self, print_when: Option<Condition>) -> Self {
+        self.print_when = print_when;
+        self
+    }
+
+    /// Sets the output problems with events are reported to.
+    pub fn diagnostics(mut self, diagnostics: Option<Box<dyn ConsoleOutput>>) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    /// Sets the field each event is wrapped in before being encoded.
+    pub fn wrap_field(mut self, wrap_field: Option<String>) -> Self {
+        self.wrap_field = wrap_field;
+        self
+    }
+
+    /// Sets the fields whose values are redacted before being encoded.
+    pub fn redact_fields(mut self, redact_fields: Vec<OwnedTargetPath>) -> Self {
+        self.redact_fields = redact_fields;
+        self
+    }
+
+    /// Sets the encoder metrics are written with instead of the codec.
+    pub fn statsd(mut self, statsd: Option<StatsdEncoder>) -> Self {
+        self.statsd = statsd;
+        self
+    }
+
+    /// Sets a second encoder each event is also written with.
+    pub fn dual_encoder(mut self, dual_encoder: Option<Encoder<Framer>>) -> Self {
+        self.dual_encoder = dual_encoder;
+        self
+    }
+
+    /// Sets the formatter events are written as table rows with.
+    pub fn table(mut self, table: Option<TableFormatter>) -> Self {
+        self.table = table;
+        self
+    }
+
+    /// Sets the limit on the number of events written per second.
+    pub fn rate_limit(mut self, rate_limit: Option<RateLimiter>) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
+
+    /// Sets the output a dot is written to for each event.
+    pub fn progress(mut self, progress: Option<ProgressDots>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Sets whether log events are written in the streaming form of `jq --stream`.
+    pub const fn json_stream(mut self, json_stream: bool) -> Self {
+        self.json_stream = json_stream;
+        self
+    }
+
+    /// Sets the depth from which nested objects and arrays of log events are pretty-printed.
+    pub fn pretty_depth(mut self, pretty_depth: Option<usize>) -> Self {
+        self.pretty_depth = pretty_depth;
+        self
+    }
+
+    /// Sets whether each record is prefixed with its line number.
+    pub const fn line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    /// Sets the statistics written periodically while the sink runs.
+    pub fn stats(mut self, stats: Option<IntervalStats>) -> Self {
+        self.stats = stats;
+        self
+    }
+}
+
+impl<T> WriterSink<T>
+where
+    T: ConsoleOutput,
+{
+    async fn write(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        self.output.write_all(bytes).await.map_err(|error| {
+            // Error when writing to stdout/stderr is likely irrecoverable,
+            // so stop the sink.
+            error!(message = "Error writing to output. Stopping sink.", %error);
+        })
+    }
+
+    /// Writes a JSON record describing a problem with an event to the diagnostics output, if any.
+    ///
+    /// Returns `false` if there is no diagnostics output, in which case the caller should handle
+    /// the problem itself.
+    async fn diagnose(&mut self, kind: &str, message: &str) -> bool {
+        let Some(diagnostics) = self.diagnostics.as_mut() else {
+            return false;
+        };
+
+        let record = serde_json::json!({
+            "timestamp": Utc::now().to_rfc3339(),
+            "kind": kind,
+            "message": message,
+        });
+        let mut bytes = record.to_string().into_bytes();
+        bytes.push(b'\n');
+        if let Err(error) = async {
+            diagnostics.write_all(&bytes).await?;
+            diagnostics.flush().await
+        }
+        .await
+        {
+            error!(message = "Error writing diagnostics.", %error, internal_log_rate_limit = true);
+        }
+        true
+    }
+
+    /// Writes a progress dot for an event, if enabled.
+    async fn tick_progress(&mut self) {
+        if let Some(progress) = self.progress.as_mut() {
+            if let Err(error) = progress.tick().await {
+                error!(message = "Error writing progress.", %error, internal_log_rate_limit = true);
+            }
+        }
+    }
 }
 
 #[async_trait]
 impl<T> StreamSink<Event> for WriterSink<T>
 where
-    T: io::AsyncWrite + Send + Sync + Unpin,
+    T: ConsoleOutput,
 {
     async fn run(mut self: Box<Self>, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
         let bytes_sent = register!(BytesSent::from(Protocol("console".into(),)));
         let events_sent = register!(EventsSent::from(Output(None)));
-        while let Some(mut event) = input.next().await {
+        let mut dump_signals = if self.ring.is_some() {
+            dump_signals()
+        } else {
+            stream::pending().boxed()
+        };
+
+        let mut stats_ticks = match self.stats.as_mut() {
+            Some(stats) => stats.start(),
+            None => stream::pending().boxed(),
+        };
+
+        let mut previous_type = None;
+        let mut summary = Summary::default();
+        let mut line_number: u64 = 0;
+
+        loop {
+            let mut event = tokio::select! {
+                event = input.next() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+                Some(()) = dump_signals.next() => {
+                    // Nothing is written for an empty ring, which has no file to go to when the
+                    // path is rendered from the events.
+                    if let Some(ring) = self.ring.as_mut().filter(|ring| !ring.entries.is_empty()) {
+                        let (bytes, count) = ring.drain();
+                        self.write(&bytes).await?;
+                        events_sent.emit(count);
+                        bytes_sent.emit(ByteSize(bytes.len()));
+                    }
+                    continue;
+                }
+                Some(()) = stats_ticks.next() => {
+                    if let Some(stats) = self.stats.as_mut() {
+                        let line = stats.take_line();
+                        // With a templated file path, there is no file to write to until an event
+                        // is routed.
+                        if self.output.has_destination() {
+                            self.write(line.as_bytes()).await?;
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            self.tick_progress().await;
+            if self
+                .progress
+                .as_ref()
+                .is_some_and(|progress| progress.replaces_records)
+            {
+                event
+                    .take_finalizers()
+                    .update_status(EventStatus::Delivered);
+                continue;
+            }
+
+            if let Some(condition) = self.print_when.as_ref() {
+                let (print, checked) = condition.check(event);
+                event = checked;
+                if !print {
+                    // Events that are not printed are still acknowledged, so sources don't stall.
+                    event
+                        .take_finalizers()
+                        .update_status(EventStatus::Delivered);
+                    continue;
+                }
+            }
+
+            if let Some(rate_limit) = self.rate_limit.as_mut() {
+                if !rate_limit.allow() {
+                    // Dropped events are still acknowledged, so sources don't stall.
+                    event
+                        .take_finalizers()
+                        .update_status(EventStatus::Delivered);
+                    continue;
+                }
+                if let Some(note) = rate_limit.take_note() {
+                    self.write(note.as_bytes()).await?;
+                }
+            }
+
+            if !self.output.route(&event) {
+                event.take_finalizers().update_status(EventStatus::Rejected);
+                self.diagnose(
+                    "event_skipped",
+                    "No output could be selected for the event.",
+                )
+                .await;
+                continue;
+            }
+
+            let dump = match self.ring.as_ref().and_then(|ring| ring.dump_when.as_ref()) {
+                Some(condition) => {
+                    let (dump, checked) = condition.check(event);
+                    event = checked;
+                    dump
+                }
+                None => false,
+            };
+
+            if let (Some(diff), Event::Log(log)) = (self.diff.as_mut(), &mut event) {
+                diff.diff(log);
+            }
+
             let event_byte_size = event.estimated_json_encoded_size_of();
+            let event_type = std::mem::discriminant(&event);
+            let separator = self
+                .type_separator
+                .as_ref()
+                .filter(|_| previous_type.is_some_and(|previous| previous != event_type));
+            previous_type = Some(event_type);
+            summary.count_event(&event);
+            if let Some(stats) = self.stats.as_mut() {
+                stats.summary.count_event(&event);
+            }
+            let mut prefix = self
+                .prefix_component_id
+                .then(|| component_id_prefix(&event));
+            if let Event::Log(log) = &mut event {
+                redact_log(log, &self.redact_fields);
+            }
             self.transformer.transform(&mut event);
+            if let (Some(field), Event::Log(log)) = (self.wrap_field.as_ref(), &mut event) {
+                wrap_log(log, field);
+            }
 
             let finalizers = event.take_finalizers();
             let mut bytes = BytesMut::new();
-            self.encoder.encode(event, &mut bytes).map_err(|_| {
+            let encoded = match (&mut self.statsd, &mut self.table, event) {
+                (Some(statsd), _, Event::Metric(metric)) => {
+                    // Encoding a metric as statsd lines never fails.
+                    let _ = statsd.encode(&metric, &mut bytes);
+                    Ok(())
+                }
+                (_, Some(table), Event::Log(log)) => {
+                    table.write_row(&log, &mut bytes);
+                    Ok(())
+                }
+                (_, _, Event::Log(log)) if self.json_stream => {
+                    write_json_stream(log.value(), &mut bytes).map_err(|error| {
+                        vector_lib::codecs::encoding::Error::SerializingError(error.into())
+                    })
+                }
+                (_, _, Event::Log(log)) if self.pretty_depth.is_some() => {
+                    let depth = self.pretty_depth.unwrap_or_default();
+                    write_pretty_from_depth(log.value(), depth, &mut bytes).map_err(|error| {
+                        vector_lib::codecs::encoding::Error::SerializingError(error.into())
+                    })
+                }
+                (_, _, event) => match self.dual_encoder.as_mut() {
+                    Some(dual_encoder) => self
+                        .encoder
+                        .encode(event.clone(), &mut bytes)
+                        .and_then(|()| dual_encoder.encode(event, &mut bytes)),
+                    None => self.encoder.encode(event, &mut bytes),
+                },
+            };
+            if let Err(error) = encoded {
                 // Error is handled by `Encoder`.
                 finalizers.update_status(EventStatus::Errored);
-            })?;
-
-            match self.output.write_all(&bytes).await {
-                Err(error) => {
-                    // Error when writing to stdout/stderr is likely irrecoverable,
-                    // so stop the sink.
-                    error!(message = "Error writing to output. Stopping sink.", %error);
-                    finalizers.update_status(EventStatus::Errored);
-                    return Err(());
+                if self.diagnose("encode_failed", &error.to_string()).await {
+                    continue;
                 }
-                Ok(()) => {
-                    finalizers.update_status(EventStatus::Delivered);
+                return Err(());
+            }
+            if self.line_numbers {
+                // The line number comes first, before any other prefix.
+                line_number += 1;
+                let number = format!("{}: ", line_number);
+                prefix = Some(number + prefix.as_deref().unwrap_or_default());
+            }
+            if let Some(prefix) = prefix {
+                let mut prefixed = BytesMut::with_capacity(prefix.len() + bytes.len());
+                prefixed.extend_from_slice(prefix.as_bytes());
+                prefixed.extend_from_slice(&bytes);
+                bytes = prefixed;
+            }
+            if self.sanitize_control_chars {
+                bytes = escape_control_chars(&bytes);
+            }
+            if let Some(separator) = separator {
+                let mut separated = BytesMut::with_capacity(separator.len() + 1 + bytes.len());
+                separated.extend_from_slice(separator.as_bytes());
+                separated.put_u8(b'\n');
+                separated.extend_from_slice(&bytes);
+                bytes = separated;
+            }
+
+            summary.bytes += bytes.len();
+            if let Some(stats) = self.stats.as_mut() {
+                stats.summary.bytes += bytes.len();
+            }
+
+            if let Some(ring) = self.ring.as_mut() {
+                // Buffered events are considered delivered, since they may never be written.
+                ring.push(bytes, event_byte_size);
+                finalizers.update_status(EventStatus::Delivered);
 
-                    events_sent.emit(CountByteSize(1, event_byte_size));
+                if dump {
+                    let (bytes, count) = ring.drain();
+                    self.write(&bytes).await?;
+                    events_sent.emit(count);
                     bytes_sent.emit(ByteSize(bytes.len()));
                 }
+                continue;
+            }
+
+            if let Err(()) = self.write(&bytes).await {
+                finalizers.update_status(EventStatus::Errored);
+                return Err(());
+            }
+            finalizers.update_status(EventStatus::Delivered);
+
+            events_sent.emit(CountByteSize(1, event_byte_size));
+            bytes_sent.emit(ByteSize(bytes.len()));
+        }
+
+        if let Some(note) = self.rate_limit.as_mut().and_then(RateLimiter::take_note) {
+            self.write(note.as_bytes()).await?;
+        }
+
+        if let Some(progress) = self.progress.as_mut() {
+            if let Err(error) = progress.finish().await {
+                error!(message = "Error writing progress.", %error);
+            }
+        }
+
+        // With a templated file path, there is no file to write the footer to if no event was
+        // routed.
+        if self.emit_footer && self.output.has_destination() {
+            self.write(summary.footer().as_bytes()).await?;
+        }
+
+        self.output.close().await.map_err(|error| {
+            error!(message = "Error closing output.", %error);
+        })
+    }
+}
+
+/// Tracks the previous log event of each group, so that only the fields that changed are written.
+pub struct DiffTracker {
+    key_field: Option<String>,
+    previous: HashMap<Option<String>, ObjectMap>,
+}
+
+impl DiffTracker {
+    pub fn new(key_field: Option<String>) -> Self {
+        Self {
+            key_field,
+            previous: HashMap::new(),
+        }
+    }
+
+    /// Removes the fields of the event that are unchanged from the previous event of its group,
+    /// keeping the key field.
+    fn diff(&mut self, log: &mut LogEvent) {
+        let Some(fields) = log.as_map() else {
+            return;
+        };
+        let key = self.key_field.as_deref().and_then(|field| {
+            log.parse_path_and_get_value(field)
+                .ok()
+                .flatten()
+                .cloned()
+                .map(|value| (field, value))
+        });
+
+        let group = key
+            .as_ref()
+            .map(|(_, value)| value.to_string_lossy().into_owned());
+        let Some(previous) = self.previous.insert(group, fields.clone()) else {
+            return;
+        };
+        let changed = fields
+            .iter()
+            .filter(|(field, value)| previous.get(*field) != Some(value))
+            .map(|(field, value)| (field.clone(), value.clone()))
+            .collect();
+
+        *log.value_mut() = Value::Object(changed);
+        if let Some((field, value)) = key {
+            if let Err(error) = log.parse_path_and_insert(field, value) {
+                warn!(message = "Failed to insert diff key field.", field = %field, %error);
+            }
+        }
+    }
+}
+
+/// Limits the number of records written each second, counting the records dropped over the
+/// limit.
+pub struct RateLimiter {
+    max_per_second: u32,
+    window_start: Instant,
+    written: u32,
+    dropped: usize,
+    write_notes: bool,
+}
+
+impl RateLimiter {
+    pub fn new(max_per_second: NonZeroU32) -> Self {
+        Self {
+            max_per_second: max_per_second.get(),
+            window_start: Instant::now(),
+            written: 0,
+            dropped: 0,
+            write_notes: true,
+        }
+    }
+
+    /// Sets whether notes reporting the dropped records are written.
+    pub const fn write_notes(mut self, write_notes: bool) -> Self {
+        self.write_notes = write_notes;
+        self
+    }
+
+    /// Whether a record can be written now, starting a new one second window once the current
+    /// one has passed.
+    fn allow(&mut self) -> bool {
+        let now = Instant::now();
+        if now.duration_since(self.window_start) >= Duration::from_secs(1) {
+            self.window_start = now;
+            self.written = 0;
+        }
+        if self.written < self.max_per_second {
+            self.written += 1;
+            true
+        } else {
+            self.dropped += 1;
+            false
+        }
+    }
+
+    /// Takes a note reporting the records dropped since the last note, if any, and if notes are
+    /// written.
+    fn take_note(&mut self) -> Option<String> {
+        let dropped = std::mem::take(&mut self.dropped);
+        (self.write_notes && dropped > 0).then(|| format!("dropped {} records\n", dropped))
+    }
+}
+
+/// The number of progress dots written on each line.
+const PROGRESS_DOTS_PER_LINE: usize = 80;
+
+/// Writes a `.` for each event, as a low-noise indicator that the sink is making progress.
+pub struct ProgressDots {
+    output: Box<dyn ConsoleOutput>,
+    replaces_records: bool,
+    count: usize,
+}
+
+impl ProgressDots {
+    /// Creates a progress indicator writing to `output`.
+    ///
+    /// When `replaces_records` is set, the dots are written instead of the records of the events,
+    /// such as when both would otherwise be written to STDERR.
+    pub fn new(output: Box<dyn ConsoleOutput>, replaces_records: bool) -> Self {
+        Self {
+            output,
+            replaces_records,
+            count: 0,
+        }
+    }
+
+    async fn tick(&mut self) -> std::io::Result<()> {
+        self.count += 1;
+        let dot: &[u8] = if self.count % PROGRESS_DOTS_PER_LINE == 0 {
+            b".\n"
+        } else {
+            b"."
+        };
+        self.output.write_all(dot).await?;
+        self.output.flush().await
+    }
+
+    /// Ends the last line of dots, if it is not already complete.
+    async fn finish(&mut self) -> std::io::Result<()> {
+        if self.count % PROGRESS_DOTS_PER_LINE != 0 {
+            self.output.write_all(b"\n").await?;
+        }
+        self.output.close().await
+    }
+}
+
+/// Formats log events as rows of fixed-width columns.
+pub struct TableFormatter {
+    columns: Vec<(String, usize)>,
+    header: bool,
+}
+
+impl TableFormatter {
+    /// Creates a formatter for the given fields and widths, writing a header with the names of
+    /// the fields before the first row if `header` is set.
+    pub const fn new(columns: Vec<(String, usize)>, header: bool) -> Self {
+        Self { columns, header }
+    }
+
+    /// Writes the event as a row, with each value truncated or padded with spaces to the width
+    /// of its column. Missing fields are written as blank columns.
+    fn write_row(&mut self, log: &LogEvent, bytes: &mut BytesMut) {
+        if std::mem::take(&mut self.header) {
+            let names = self
+                .columns
+                .iter()
+                .map(|(field, _)| field.clone())
+                .collect();
+            self.write_line(names, bytes);
+        }
+        let values = self
+            .columns
+            .iter()
+            .map(|(field, _)| {
+                log.parse_path_and_get_value(field.as_str())
+                    .ok()
+                    .flatten()
+                    .map(|value| value.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            })
+            .collect();
+        self.write_line(values, bytes);
+    }
+
+    fn write_line(&self, values: Vec<String>, bytes: &mut BytesMut) {
+        let mut line = String::new();
+        for (i, (value, (_, width))) in values.iter().zip(&self.columns).enumerate() {
+            if i > 0 {
+                line.push(' ');
+            }
+            let value: String = value.chars().take(*width).collect();
+            let _ = write!(line, "{:<width$}", value, width = *width);
+        }
+        line.push('\n');
+        bytes.extend_from_slice(line.as_bytes());
+    }
+}
+
+/// Counts of the events encoded, written as a footer when `emit_footer` is enabled.
+#[derive(Default)]
+struct Summary {
+    logs: usize,
+    metrics: usize,
+    traces: usize,
+    bytes: usize,
+}
+
+impl Summary {
+    fn count_event(&mut self, event: &Event) {
+        match event {
+            Event::Log(_) => self.logs += 1,
+            Event::Metric(_) => self.metrics += 1,
+            Event::Trace(_) => self.traces += 1,
+        }
+    }
+
+    fn footer(&self) -> String {
+        format!(
+            "--- {} events ({} logs, {} metrics, {} traces), {} bytes ---\n",
+            self.logs + self.metrics + self.traces,
+            self.logs,
+            self.metrics,
+            self.traces,
+            self.bytes
+        )
+    }
+}
+
+/// Counts the events seen since the last periodic statistics line.
+pub struct IntervalStats {
+    interval: Duration,
+    started: tokio::time::Instant,
+    summary: Summary,
+}
+
+impl IntervalStats {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            started: tokio::time::Instant::now(),
+            summary: Summary::default(),
+        }
+    }
+
+    /// Starts counting, returning the ticks at which to write a statistics line.
+    fn start(&mut self) -> BoxStream<'static, ()> {
+        self.started = tokio::time::Instant::now();
+        IntervalStream::new(tokio::time::interval_at(
+            self.started + self.interval,
+            self.interval,
+        ))
+        .map(|_| ())
+        .boxed()
+    }
+
+    /// Takes a line reporting the rates and counts of events since the last line.
+    fn take_line(&mut self) -> String {
+        let now = tokio::time::Instant::now();
+        let seconds = now.duration_since(self.started).as_secs_f64();
+        let summary = std::mem::take(&mut self.summary);
+        self.started = now;
+        let events = summary.logs + summary.metrics + summary.traces;
+        format!(
+            "--- {:.1} events/s, {:.1} bytes/s ({} logs, {} metrics, {} traces) ---\n",
+            events as f64 / seconds,
+            summary.bytes as f64 / seconds,
+            summary.logs,
+            summary.metrics,
+            summary.traces
+        )
+    }
+}
+
+/// Nests the fields of a log event under a single top-level field.
+fn wrap_log(log: &mut LogEvent, field: &str) {
+    let value = std::mem::replace(log.value_mut(), Value::Object(ObjectMap::new()));
+    log.insert(event_path!(field), value);
+}
+
+/// The value that redacted fields are replaced with.
+const REDACTED: &str = "***";
+
+/// Replaces the value of each of the fields present in the log event with `***`.
+fn redact_log(log: &mut LogEvent, fields: &[OwnedTargetPath]) {
+    for field in fields {
+        if log.contains(field) {
+            log.insert(field, REDACTED);
+        }
+    }
+}
+
+/// Writes a value in the streaming form of `jq --stream`.
+///
+/// Each leaf value is written as a `[path, value]` pair, and the last element of each non-empty
+/// array or object is followed by a closing `[path]` event, one per line.
+fn write_json_stream(value: &Value, bytes: &mut BytesMut) -> serde_json::Result<()> {
+    let mut path = Vec::new();
+    write_json_stream_value(value, &mut path, bytes)
+}
+
+fn write_json_stream_value(
+    value: &Value,
+    path: &mut Vec<serde_json::Value>,
+    bytes: &mut BytesMut,
+) -> serde_json::Result<()> {
+    let last: Option<serde_json::Value> = match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                path.push(key.as_str().into());
+                write_json_stream_value(value, path, bytes)?;
+                path.pop();
+            }
+            map.keys().next_back().map(|key| key.as_str().into())
+        }
+        Value::Array(array) if !array.is_empty() => {
+            for (index, value) in array.iter().enumerate() {
+                path.push(index.into());
+                write_json_stream_value(value, path, bytes)?;
+                path.pop();
+            }
+            Some((array.len() - 1).into())
+        }
+        _ => {
+            bytes.extend_from_slice(&serde_json::to_vec(&(&*path, value))?);
+            bytes.put_u8(b'\n');
+            return Ok(());
+        }
+    };
+
+    path.extend(last);
+    bytes.extend_from_slice(&serde_json::to_vec(&(&*path,))?);
+    bytes.put_u8(b'\n');
+    path.pop();
+    Ok(())
+}
+
+/// The indentation of each level of a pretty-printed value.
+const PRETTY_INDENT: &[u8] = b"  ";
+
+/// Writes a value as JSON, indenting only the objects and arrays nested at least `pretty_depth`
+/// deep, followed by a newline.
+fn write_pretty_from_depth(
+    value: &Value,
+    pretty_depth: usize,
+    bytes: &mut BytesMut,
+) -> serde_json::Result<()> {
+    write_pretty_value(value, 0, pretty_depth, None, bytes)?;
+    bytes.put_u8(b'\n');
+    Ok(())
+}
+
+/// Writes a value nested `depth` deep.
+///
+/// `indent` is the indentation level of the line the value starts on once pretty-printing has
+/// begun, or `None` while the value is still written compactly.
+fn write_pretty_value(
+    value: &Value,
+    depth: usize,
+    pretty_depth: usize,
+    indent: Option<usize>,
+    bytes: &mut BytesMut,
+) -> serde_json::Result<()> {
+    let indent = indent.or_else(|| (depth >= pretty_depth).then_some(0));
+    let (open, close, entries): (u8, u8, Vec<(Option<&str>, &Value)>) = match value {
+        Value::Object(map) if !map.is_empty() => (
+            b'{',
+            b'}',
+            map.iter()
+                .map(|(key, value)| (Some(key.as_str()), value))
+                .collect(),
+        ),
+        Value::Array(array) if !array.is_empty() => (
+            b'[',
+            b']',
+            array.iter().map(|value| (None, value)).collect(),
+        ),
+        _ => {
+            bytes.extend_from_slice(&serde_json::to_vec(value)?);
+            return Ok(());
+        }
+    };
+
+    bytes.put_u8(open);
+    for (index, (key, value)) in entries.into_iter().enumerate() {
+        if index > 0 {
+            bytes.put_u8(b',');
+        }
+        if let Some(indent) = indent {
+            bytes.put_u8(b'\n');
+            bytes.extend_from_slice(&PRETTY_INDENT.repeat(indent + 1));
+        }
+        if let Some(key) = key {
+            bytes.extend_from_slice(&serde_json::to_vec(key)?);
+            bytes.put_u8(b':');
+            if indent.is_some() {
+                bytes.put_u8(b' ');
+            }
+        }
+        write_pretty_value(value, depth + 1, pretty_depth, indent.map(|i| i + 1), bytes)?;
+    }
+    if let Some(indent) = indent {
+        bytes.put_u8(b'\n');
+        bytes.extend_from_slice(&PRETTY_INDENT.repeat(indent));
+    }
+    bytes.put_u8(close);
+    Ok(())
+}
+
+/// The prefix written before an event when `prefix_component_id` is enabled.
+fn component_id_prefix(event: &Event) -> String {
+    match event.metadata().upstream_id() {
+        Some(id) => format!("[{}] ", id),
+        None => format!("[{}] ", UNKNOWN_COMPONENT_ID),
+    }
+}
+
+/// Signals requesting a dump of the ring buffer.
+#[cfg(unix)]
+fn dump_signals() -> BoxStream<'static, ()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::user_defined1()) {
+        Ok(mut sigusr1) => async_stream::stream! {
+            while sigusr1.recv().await.is_some() {
+                yield ();
             }
         }
+        .boxed(),
+        Err(error) => {
+            warn!(message = "Failed to set up SIGUSR1 handler.", %error);
+            stream::pending().boxed()
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn dump_signals() -> BoxStream<'static, ()> {
+    stream::pending().boxed()
+}
 
-        Ok(())
+/// Escapes ASCII control characters, other than newlines and tabs, so that they are printed
+/// literally instead of being interpreted by the terminal.
+fn escape_control_chars(bytes: &[u8]) -> BytesMut {
+    let mut escaped = BytesMut::with_capacity(bytes.len());
+    for &byte in bytes {
+        if byte.is_ascii_control() && byte != b'\n' && byte != b'\t' {
+            let _ = write!(escaped, "\\x{:02x}", byte);
+        } else {
+            escaped.put_u8(byte);
+        }
     }
+    escaped
 }
 
 #[cfg(test)]
 mod test {
+    use std::{num::NonZeroUsize, sync::Arc};
+
     use futures::future::ready;
     use futures_util::stream;
-    use vector_lib::codecs::{JsonSerializerConfig, NewlineDelimitedEncoder};
+    use tokio::io::{self, AsyncReadExt};
+    use vector_lib::codecs::{
+        GelfSerializerConfig, JsonSerializerConfig, NewlineDelimitedEncoder, TextSerializerConfig,
+    };
+    use vector_lib::metric_tags;
     use vector_lib::sink::VectorSink;
 
     use super::*;
     use crate::{
-        event::{Event, LogEvent},
-        test_util::components::{run_and_assert_sink_compliance, SINK_TAGS},
+        conditions::AnyCondition,
+        config::OutputId,
+        event::{BatchNotifier, BatchStatus, Metric, MetricKind, MetricValue},
+        sinks::console::{config::Compression, output::TemplatedFiles},
+        template::Template,
+        test_util::{
+            components::{run_and_assert_sink_compliance, SINK_TAGS},
+            lines_from_file, lines_from_gzip_file, temp_dir,
+        },
     };
 
     #[tokio::test]
@@ -85,11 +1027,7 @@ mod test {
             JsonSerializerConfig::default().build().into(),
         );
 
-        let sink = WriterSink {
-            output: Vec::new(),
-            transformer: Default::default(),
-            encoder,
-        };
+        let sink = WriterSink::new(Vec::new(), Default::default(), encoder);
 
         run_and_assert_sink_compliance(
             VectorSink::from_event_streamsink(sink),
@@ -98,4 +1036,868 @@ mod test {
         )
         .await;
     }
+
+    #[tokio::test]
+    async fn sanitizes_control_chars() {
+        let event = Event::Log(LogEvent::from("\x1b[31mred\x1b[0m\x07"));
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(1024);
+        let sink =
+            WriterSink::new(output, Default::default(), encoder).sanitize_control_chars(true);
+
+        Box::new(sink)
+            .run(stream::once(ready(event)).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        assert_eq!(written, "\\x1b[31mred\\x1b[0m\\x07\n");
+    }
+
+    #[tokio::test]
+    async fn ring_dumps_last_events() {
+        let events = ["1", "2", "3", "dump"]
+            .into_iter()
+            .map(|message| Event::Log(LogEvent::from(message)));
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+        let dump_when = AnyCondition::String(r#".message == "dump""#.to_string())
+            .build(&Default::default())
+            .unwrap();
+
+        let (output, mut reader) = io::duplex(1024);
+        let sink = WriterSink::new(output, Default::default(), encoder)
+            .ring(Some(RingBuffer::new(2, Some(dump_when))));
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        assert_eq!(written, "3\ndump\n");
+    }
+
+    #[tokio::test]
+    async fn prints_only_matching_events() {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let events = ["debug", "error", "info"]
+            .into_iter()
+            .map(|message| Event::Log(LogEvent::from(message).with_batch_notifier(&batch)))
+            .collect::<Vec<_>>();
+        drop(batch);
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+        let print_when = AnyCondition::String(r#".message == "error""#.to_string())
+            .build(&Default::default())
+            .unwrap();
+
+        let (output, mut reader) = io::duplex(1024);
+        let sink =
+            WriterSink::new(output, Default::default(), encoder).print_when(Some(print_when));
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        assert_eq!(written, "error\n");
+        assert_eq!(receiver.await, BatchStatus::Delivered);
+    }
+
+    #[tokio::test]
+    async fn limits_records_per_second() {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let events = (0..5)
+            .map(|i| Event::Log(LogEvent::from(format!("event {}", i)).with_batch_notifier(&batch)))
+            .collect::<Vec<_>>();
+        drop(batch);
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(1024);
+        let sink = WriterSink::new(output, Default::default(), encoder)
+            .rate_limit(Some(RateLimiter::new(NonZeroU32::new(2).unwrap())));
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        assert_eq!(written, "event 0\nevent 1\ndropped 3 records\n");
+        assert_eq!(receiver.await, BatchStatus::Delivered);
+    }
+
+    #[test]
+    fn skips_rate_limit_notes_when_disabled() {
+        let mut rate_limit = RateLimiter::new(NonZeroU32::new(1).unwrap()).write_notes(false);
+        assert!(rate_limit.allow());
+        assert!(!rate_limit.allow());
+        assert_eq!(rate_limit.take_note(), None);
+    }
+
+    #[tokio::test]
+    async fn writes_progress_dots() {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let events = (0..170)
+            .map(|i| Event::Log(LogEvent::from(format!("event {}", i)).with_batch_notifier(&batch)))
+            .collect::<Vec<_>>();
+        drop(batch);
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+
+        let (progress, mut progress_reader) = io::duplex(1024);
+        let sink = WriterSink::new(io::sink(), Default::default(), encoder)
+            .progress(Some(ProgressDots::new(Box::new(progress), true)));
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        progress_reader.read_to_string(&mut written).await.unwrap();
+        assert_eq!(written.matches('.').count(), 170);
+        let lines = written.lines().map(str::len).collect::<Vec<_>>();
+        assert_eq!(lines, vec![80, 80, 10]);
+        assert_eq!(receiver.await, BatchStatus::Delivered);
+    }
+
+    #[tokio::test]
+    async fn writes_json_stream() {
+        let mut log = LogEvent::default();
+        log.insert("a", 1);
+        log.insert("b.c", "x");
+        log.insert("b.d", Value::from(vec![Value::from(true)]));
+        log.insert("e", Value::Object(ObjectMap::new()));
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            JsonSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(1024);
+        let sink = WriterSink::new(output, Default::default(), encoder).json_stream(true);
+
+        Box::new(sink)
+            .run(stream::iter(vec![Event::Log(log)]).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        assert_eq!(
+            written.lines().collect::<Vec<_>>(),
+            [
+                r#"[["a"],1]"#,
+                r#"[["b","c"],"x"]"#,
+                r#"[["b","d",0],true]"#,
+                r#"[["b","d",0]]"#,
+                r#"[["b","d"]]"#,
+                r#"[["e"],{}]"#,
+                r#"[["e"]]"#,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn writes_pretty_from_depth() {
+        let mut log = LogEvent::default();
+        log.insert("a", 1);
+        log.insert("b.c.d", "x");
+        log.insert(
+            "b.e",
+            Value::from(vec![Value::from(true), Value::from(false)]),
+        );
+        log.insert("f", Value::Object(ObjectMap::new()));
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            JsonSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(1024);
+        let sink = WriterSink::new(output, Default::default(), encoder).pretty_depth(Some(2));
+
+        Box::new(sink)
+            .run(stream::iter(vec![Event::Log(log)]).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        assert_eq!(
+            written,
+            concat!(
+                r#"{"a":1,"b":{"c":{"#,
+                "\n",
+                r#"  "d": "x""#,
+                "\n",
+                r#"},"e":["#,
+                "\n",
+                "  true,\n",
+                "  false\n",
+                r#"]},"f":{}}"#,
+                "\n",
+            )
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn writes_interval_stats() {
+        let event = |i| Event::Log(LogEvent::from(format!("event {}", i)));
+        // Two events in the first interval and one in the second, with the input ending halfway
+        // through the third.
+        let input = stream::iter(vec![event(0), event(1)])
+            .chain(stream::once(async move {
+                tokio::time::sleep(Duration::from_millis(150)).await;
+                event(2)
+            }))
+            .chain(
+                stream::once(tokio::time::sleep(Duration::from_millis(100)))
+                    .filter_map(|()| ready(None)),
+            );
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(1024);
+        let sink = WriterSink::new(output, Default::default(), encoder)
+            .stats(Some(IntervalStats::new(Duration::from_millis(100))));
+
+        Box::new(sink).run(input.boxed()).await.unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        assert_eq!(
+            written.lines().collect::<Vec<_>>(),
+            [
+                "event 0",
+                "event 1",
+                "--- 20.0 events/s, 160.0 bytes/s (2 logs, 0 metrics, 0 traces) ---",
+                "event 2",
+                "--- 10.0 events/s, 80.0 bytes/s (1 logs, 0 metrics, 0 traces) ---",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn skips_interval_stats_without_templated_file() {
+        let directory = temp_dir();
+        // The first ticks come before any event selects a file.
+        let input = stream::once(async {
+            tokio::time::sleep(Duration::from_millis(120)).await;
+            Event::Log(LogEvent::from("event"))
+        })
+        .chain(
+            stream::once(tokio::time::sleep(Duration::from_millis(60)))
+                .filter_map(|()| ready(None)),
+        );
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+
+        let path = Template::try_from(format!("{}/out.log", directory.display())).unwrap();
+        let sink = WriterSink::new(
+            TemplatedFiles::new(path, NonZeroUsize::new(1).unwrap()),
+            Default::default(),
+            encoder,
+        )
+        .stats(Some(IntervalStats::new(Duration::from_millis(50))));
+
+        Box::new(sink).run(input.boxed()).await.unwrap();
+
+        let lines = lines_from_file(directory.join("out.log"));
+        assert_eq!(lines[0], "event");
+        assert!(lines[1].ends_with("(1 logs, 0 metrics, 0 traces) ---"));
+    }
+
+    #[tokio::test]
+    async fn writes_line_numbers() {
+        let events = (0..3)
+            .map(|i| Event::Log(LogEvent::from(format!("event {}", i))))
+            .collect::<Vec<_>>();
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(1024);
+        let sink = WriterSink::new(output, Default::default(), encoder)
+            .prefix_component_id(true)
+            .line_numbers(true);
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        assert_eq!(
+            written,
+            "1: [unknown] event 0\n2: [unknown] event 1\n3: [unknown] event 2\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn writes_diagnostics() {
+        let mut valid = LogEvent::from("ok");
+        valid.insert("host", "localhost");
+        // GELF requires a `host` field, so this event cannot be encoded.
+        let invalid = LogEvent::from("missing host");
+        let events = vec![
+            Event::Log(valid.clone()),
+            Event::Log(invalid),
+            Event::Log(valid),
+        ];
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            GelfSerializerConfig::new().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(1024);
+        let (diagnostics, mut diagnostics_reader) = io::duplex(1024);
+        let sink = WriterSink::new(output, Default::default(), encoder)
+            .diagnostics(Some(Box::new(diagnostics)));
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        let lines = written.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(record["host"], "localhost");
+        }
+
+        let mut diagnosed = String::new();
+        diagnostics_reader
+            .read_to_string(&mut diagnosed)
+            .await
+            .unwrap();
+        let lines = diagnosed.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1);
+        let record: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(record["kind"], "encode_failed");
+        assert!(record["message"].as_str().unwrap().contains("host"));
+    }
+
+    #[tokio::test]
+    async fn wraps_events_in_field() {
+        let mut log = LogEvent::from("foo");
+        log.insert("host", "localhost");
+        let events = vec![Event::Log(log)];
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            JsonSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(1024);
+        let sink = WriterSink::new(output, Default::default(), encoder)
+            .wrap_field(Some("event".to_string()));
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        let record: serde_json::Value = serde_json::from_str(written.trim_end()).unwrap();
+        assert_eq!(
+            record,
+            serde_json::json!({ "event": { "message": "foo", "host": "localhost" } })
+        );
+    }
+
+    #[tokio::test]
+    async fn redacts_fields() {
+        let mut log = LogEvent::from("foo");
+        log.insert("password", "hunter2");
+        log.insert("user.token", "abc123");
+        log.insert("user.name", "alice");
+        let events = vec![Event::Log(log)];
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            JsonSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(1024);
+        let sink = WriterSink::new(output, Default::default(), encoder).redact_fields(
+            ["password", "user.token", "secret"]
+                .into_iter()
+                .map(|field| vrl::path::parse_target_path(field).unwrap())
+                .collect(),
+        );
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        let record: serde_json::Value = serde_json::from_str(written.trim_end()).unwrap();
+        assert_eq!(
+            record,
+            serde_json::json!({
+                "message": "foo",
+                "password": "***",
+                "user": { "token": "***", "name": "alice" },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn writes_metrics_as_statsd() {
+        let events = vec![
+            Event::Metric(Metric::new(
+                "requests",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 3.0 },
+            )),
+            Event::Metric(
+                Metric::new(
+                    "temperature",
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value: 21.5 },
+                )
+                .with_namespace(Some("sensors"))
+                .with_tags(Some(metric_tags!("room" => "kitchen"))),
+            ),
+            Event::Log(LogEvent::from("foo")),
+        ];
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(1024);
+        let sink = WriterSink::new(output, Default::default(), encoder)
+            .statsd(Some(StatsdEncoder::new(None)));
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        assert_eq!(
+            written,
+            "requests:3|c\nsensors.temperature:21.5|g|#room:kitchen\nfoo\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn writes_log_events_as_table_rows() {
+        let events = [
+            (Some("web-1"), "hello"),
+            (Some("database-server"), "a much longer message"),
+            (None, "no host"),
+        ]
+        .into_iter()
+        .map(|(host, message)| {
+            let mut log = LogEvent::from(message);
+            if let Some(host) = host {
+                log.insert("host", host);
+            }
+            Event::Log(log)
+        })
+        .collect::<Vec<_>>();
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            JsonSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(1024);
+        let sink =
+            WriterSink::new(output, Default::default(), encoder).table(Some(TableFormatter::new(
+                vec![("host".to_owned(), 5), ("message".to_owned(), 8)],
+                true,
+            )));
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        assert_eq!(
+            written,
+            "host  message \nweb-1 hello   \ndatab a much l\n      no host \n"
+        );
+    }
+
+    #[tokio::test]
+    async fn writes_each_event_in_two_encodings() {
+        let events = vec![
+            Event::Log(LogEvent::from("foo")),
+            Event::Log(LogEvent::from("bar")),
+        ];
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+        let dual_encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            JsonSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(1024);
+        let sink =
+            WriterSink::new(output, Default::default(), encoder).dual_encoder(Some(dual_encoder));
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        let lines: Vec<_> = written.lines().collect();
+        assert_eq!(lines.len(), 4);
+        for (pair, message) in lines.chunks(2).zip(["foo", "bar"]) {
+            assert_eq!(pair[0], message);
+            let json: serde_json::Value = serde_json::from_str(pair[1]).unwrap();
+            assert_eq!(json["message"], message);
+        }
+    }
+
+    #[tokio::test]
+    async fn prefixes_component_id() {
+        let mut known = LogEvent::from("foo");
+        known
+            .metadata_mut()
+            .set_upstream_id(Arc::new(OutputId::from("my_transform")));
+        let events = vec![Event::Log(known), Event::Log(LogEvent::from("bar"))];
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(1024);
+        let sink = WriterSink::new(output, Default::default(), encoder).prefix_component_id(true);
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        assert_eq!(written, "[my_transform] foo\n[unknown] bar\n");
+    }
+
+    #[tokio::test]
+    async fn writes_to_templated_files() {
+        let directory = temp_dir();
+        let events = [("a", "1"), ("b", "2"), ("a", "3")]
+            .into_iter()
+            .map(|(tenant, message)| {
+                let mut log = LogEvent::from(message);
+                log.insert("tenant", tenant);
+                Event::Log(log)
+            });
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+
+        let path =
+            Template::try_from(format!("{}/{{{{ tenant }}}}.log", directory.display())).unwrap();
+        let sink = WriterSink::new(
+            TemplatedFiles::new(path, NonZeroUsize::new(1).unwrap()),
+            Default::default(),
+            encoder,
+        );
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        assert_eq!(lines_from_file(directory.join("a.log")), vec!["1", "3"]);
+        assert_eq!(lines_from_file(directory.join("b.log")), vec!["2"]);
+    }
+
+    #[tokio::test]
+    async fn rejects_events_without_templated_file() {
+        let directory = temp_dir();
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let event = Event::Log(LogEvent::from("no tenant").with_batch_notifier(&batch));
+        drop(batch);
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+
+        let path =
+            Template::try_from(format!("{}/{{{{ tenant }}}}.log", directory.display())).unwrap();
+        let sink = WriterSink::new(
+            TemplatedFiles::new(path, NonZeroUsize::new(1).unwrap()),
+            Default::default(),
+            encoder,
+        );
+
+        Box::new(sink)
+            .run(stream::once(ready(event)).boxed())
+            .await
+            .unwrap();
+
+        assert_eq!(receiver.await, BatchStatus::Rejected);
+    }
+
+    #[tokio::test]
+    async fn writes_bom() {
+        for write_bom in [true, false] {
+            let directory = temp_dir();
+            let encoder = Encoder::<Framer>::new(
+                NewlineDelimitedEncoder::new().into(),
+                TextSerializerConfig::default().build().into(),
+            );
+
+            let path = Template::try_from(format!("{}/out.log", directory.display())).unwrap();
+            let sink = WriterSink::new(
+                TemplatedFiles::new(path, NonZeroUsize::new(1).unwrap()).write_bom(write_bom),
+                Default::default(),
+                encoder,
+            );
+
+            let events = vec![
+                Event::Log(LogEvent::from("1")),
+                Event::Log(LogEvent::from("2")),
+            ];
+            Box::new(sink)
+                .run(stream::iter(events).boxed())
+                .await
+                .unwrap();
+
+            let bytes = std::fs::read(directory.join("out.log")).unwrap();
+            if write_bom {
+                assert_eq!(bytes, b"\xEF\xBB\xBF1\n2\n");
+            } else {
+                assert_eq!(bytes, b"1\n2\n");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn rotates_gzip_segments() {
+        let directory = temp_dir();
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+
+        let path = Template::try_from(format!("{}/out.log", directory.display())).unwrap();
+        let sink = WriterSink::new(
+            TemplatedFiles::new(path, NonZeroUsize::new(1).unwrap())
+                .compression(Compression::Gzip)
+                .rotate_after_bytes(NonZeroUsize::new(10)),
+            Default::default(),
+            encoder,
+        );
+
+        let events = ["aaaa", "bbbb", "cccc"]
+            .into_iter()
+            .map(|message| Event::Log(LogEvent::from(message)));
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut segments = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        segments.sort();
+        assert_eq!(segments.len(), 2);
+        for segment in &segments {
+            let name = segment.file_name().unwrap().to_string_lossy();
+            assert!(name.starts_with("out-"));
+            assert!(name.ends_with(".log.gz"));
+        }
+        assert_eq!(lines_from_gzip_file(&segments[0]), vec!["aaaa", "bbbb"]);
+        assert_eq!(lines_from_gzip_file(&segments[1]), vec!["cccc"]);
+    }
+
+    #[tokio::test]
+    async fn separates_event_types() {
+        let metric = || {
+            Event::Metric(Metric::new(
+                "counter",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            ))
+        };
+        let events = vec![
+            Event::Log(LogEvent::from("foo")),
+            Event::Log(LogEvent::from("bar")),
+            metric(),
+            metric(),
+            Event::Log(LogEvent::from("baz")),
+        ];
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            JsonSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(4096);
+        let sink = WriterSink::new(output, Default::default(), encoder)
+            .type_separator(Some("---".to_string()));
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        let separators: Vec<_> = written
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| *line == "---")
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(separators, vec![2, 5]);
+        assert_eq!(written.lines().count(), 7);
+    }
+
+    #[tokio::test]
+    async fn emits_footer() {
+        let events = vec![
+            Event::Log(LogEvent::from("foo")),
+            Event::Log(LogEvent::from("bar")),
+            Event::Metric(Metric::new(
+                "counter",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            )),
+        ];
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(4096);
+        let sink = WriterSink::new(output, Default::default(), encoder).emit_footer(true);
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        let footer = written.lines().last().unwrap();
+        let records_len = written.len() - footer.len() - 1;
+        assert_eq!(
+            footer,
+            format!(
+                "--- 3 events (2 logs, 1 metrics, 0 traces), {} bytes ---",
+                records_len
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn skips_footer_without_templated_file() {
+        let directory = temp_dir();
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            TextSerializerConfig::default().build().into(),
+        );
+
+        let path =
+            Template::try_from(format!("{}/{{{{ tenant }}}}.log", directory.display())).unwrap();
+        let sink = WriterSink::new(
+            TemplatedFiles::new(path, NonZeroUsize::new(1).unwrap()),
+            Default::default(),
+            encoder,
+        )
+        .emit_footer(true);
+
+        Box::new(sink).run(stream::empty().boxed()).await.unwrap();
+
+        assert!(!directory.exists() || std::fs::read_dir(&directory).unwrap().next().is_none());
+    }
+
+    #[tokio::test]
+    async fn writes_only_changed_fields() {
+        let events = [("a", 1, "up"), ("b", 1, "up"), ("a", 2, "up")]
+            .into_iter()
+            .map(|(host, count, status)| {
+                let mut log = LogEvent::default();
+                log.insert("host", host);
+                log.insert("count", count);
+                log.insert("status", status);
+                Event::Log(log)
+            });
+
+        let encoder = Encoder::<Framer>::new(
+            NewlineDelimitedEncoder::new().into(),
+            JsonSerializerConfig::default().build().into(),
+        );
+
+        let (output, mut reader) = io::duplex(4096);
+        let sink = WriterSink::new(output, Default::default(), encoder)
+            .diff(Some(DiffTracker::new(Some("host".to_string()))));
+
+        Box::new(sink)
+            .run(stream::iter(events).boxed())
+            .await
+            .unwrap();
+
+        let mut written = String::new();
+        reader.read_to_string(&mut written).await.unwrap();
+        let lines: Vec<_> = written.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2], r#"{"count":2,"host":"a"}"#);
+    }
 }
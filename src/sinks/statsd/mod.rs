@@ -1,6 +1,5 @@
 mod batch;
 mod config;
-mod encoder;
 mod normalizer;
 mod request_builder;
 mod service;
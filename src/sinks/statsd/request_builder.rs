@@ -10,11 +10,12 @@ use vector_lib::{
     EstimatedJsonEncodedSizeOf,
 };
 
-use super::{encoder::StatsdEncoder, service::StatsdRequest};
+use super::service::StatsdRequest;
 use crate::{
     internal_events::SocketMode,
     sinks::util::{
-        metadata::RequestMetadataBuilder, request_builder::EncodeResult, IncrementalRequestBuilder,
+        metadata::RequestMetadataBuilder, request_builder::EncodeResult, statsd::StatsdEncoder,
+        IncrementalRequestBuilder,
     },
 };
 
@@ -19,6 +19,8 @@ pub mod sink;
 pub mod snappy;
 pub mod socket_bytes_sink;
 pub mod statistic;
+#[cfg(any(feature = "sinks-console", feature = "sinks-statsd"))]
+pub mod statsd;
 pub mod tcp;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test;
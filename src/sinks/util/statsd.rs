@@ -28,7 +28,7 @@ impl From<io::Error> for InfallibleIo {
 }
 
 #[derive(Debug, Clone)]
-pub(super) struct StatsdEncoder {
+pub(crate) struct StatsdEncoder {
     default_namespace: Option<String>,
 }
 
@@ -16,7 +16,7 @@ use self::{
         BackpressureSourceConfig, BasicSourceConfig, ErrorSourceConfig, PanicSourceConfig,
         TripwireSourceConfig,
     },
-    transforms::{BasicTransformConfig, ErrorDefinitionTransformConfig},
+    transforms::{BasicTransformConfig, ErrorDefinitionTransformConfig, TeeTransformConfig},
 };
 
 pub mod sinks;
@@ -66,6 +66,10 @@ pub fn basic_transform(suffix: &str, increase: f64) -> BasicTransformConfig {
     BasicTransformConfig::new(suffix.to_owned(), increase)
 }
 
+pub const fn tee_transform() -> TeeTransformConfig {
+    TeeTransformConfig {}
+}
+
 pub const fn error_definition_transform() -> ErrorDefinitionTransformConfig {
     ErrorDefinitionTransformConfig {}
 }
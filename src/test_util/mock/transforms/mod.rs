@@ -6,6 +6,9 @@ pub use self::basic::BasicTransformConfig;
 mod noop;
 pub use self::noop::NoopTransformConfig;
 
+mod tee;
+pub use self::tee::{TeeTransformConfig, TEE_OUTPUT};
+
 mod error_definitions;
 pub use self::error_definitions::ErrorDefinitionTransformConfig;
 
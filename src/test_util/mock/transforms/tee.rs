@@ -0,0 +1,67 @@
+use std::pin::Pin;
+
+use async_trait::async_trait;
+use futures_util::{stream, Stream, StreamExt};
+use vector_lib::config::LogNamespace;
+use vector_lib::configurable::configurable_component;
+use vector_lib::{
+    config::{DataType, Input, TransformOutput},
+    event::Event,
+    schema::Definition,
+    transform::{MultiOutputTaskTransform, Transform},
+};
+
+use crate::config::{OutputId, TransformConfig, TransformContext};
+
+/// The name of the output that receives a copy of every event.
+pub const TEE_OUTPUT: &str = "copy";
+
+/// Configuration for the `test_tee` transform.
+#[configurable_component(transform("test_tee", "Test (tee)"))]
+#[derive(Clone, Debug, Default)]
+pub struct TeeTransformConfig {}
+
+impl_generate_config_from_default!(TeeTransformConfig);
+
+#[async_trait]
+#[typetag::serde(name = "test_tee")]
+impl TransformConfig for TeeTransformConfig {
+    fn input(&self) -> Input {
+        Input::all()
+    }
+
+    fn outputs(
+        &self,
+        _: vector_lib::enrichment::TableRegistry,
+        definitions: &[(OutputId, Definition)],
+        _: LogNamespace,
+    ) -> Vec<TransformOutput> {
+        let definitions = definitions
+            .iter()
+            .map(|(output, definition)| (output.clone(), definition.clone()))
+            .collect::<std::collections::HashMap<_, _>>();
+
+        vec![
+            TransformOutput::new(DataType::all(), definitions.clone()),
+            TransformOutput::new(DataType::all(), definitions).with_port(TEE_OUTPUT),
+        ]
+    }
+
+    async fn build(&self, _: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::MultiOutputTask(Box::new(TeeTransform)))
+    }
+}
+
+/// Sends every event to both the default output and the [`TEE_OUTPUT`] output.
+struct TeeTransform;
+
+impl MultiOutputTaskTransform for TeeTransform {
+    fn transform(
+        self: Box<Self>,
+        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = (Option<String>, Event)> + Send>> {
+        Box::pin(task.flat_map(|event| {
+            stream::iter([(Some(TEE_OUTPUT.to_string()), event.clone()), (None, event)])
+        }))
+    }
+}
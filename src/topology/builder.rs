@@ -6,7 +6,10 @@ use std::{
     time::Instant,
 };
 
-use futures::{stream::FuturesOrdered, FutureExt, StreamExt, TryStreamExt};
+use futures::{
+    stream::{self, FuturesOrdered},
+    FutureExt, Stream, StreamExt, TryStreamExt,
+};
 use futures_util::stream::FuturesUnordered;
 use once_cell::sync::Lazy;
 use stream_cancel::{StreamExt as StreamCancelExt, Trigger, Tripwire};
@@ -51,7 +54,10 @@ use crate::{
     source_sender::{SourceSenderItem, CHUNK_SIZE},
     spawn_named,
     topology::task::TaskError,
-    transforms::{SyncTransform, TaskTransform, Transform, TransformOutputs, TransformOutputsBuf},
+    transforms::{
+        MultiOutputTaskTransform, SyncTransform, TaskTransform, Transform, TransformOutputs,
+        TransformOutputsBuf,
+    },
     utilization::wrap,
     SourceSender,
 };
@@ -775,6 +781,7 @@ fn build_transform(
             &node.key,
             &node.outputs,
         ),
+        Transform::MultiOutputTask(t) => build_multi_output_task_transform(t, node, input_rx),
     }
 }
 
@@ -959,27 +966,85 @@ impl Runner {
     }
 }
 
-fn build_task_transform(
-    t: Box<dyn TaskTransform<EventArray>>,
+/// Wraps the input of a task transform with utilization tracking, drops events of unsupported
+/// types, and reports the received events.
+fn task_transform_input(
     input_rx: BufferReceiver<EventArray>,
     input_type: DataType,
-    typetag: &str,
-    key: &ComponentKey,
-    outputs: &[TransformOutput],
-) -> (Task, HashMap<OutputId, fanout::ControlChannel>) {
-    let (mut fanout, control) = Fanout::new();
-
+) -> impl Stream<Item = EventArray> {
     let input_rx = crate::utilization::wrap(input_rx.into_stream());
 
     let events_received = register!(EventsReceived);
-    let filtered = input_rx
+    input_rx
         .filter(move |events| ready(filter_events_type(events, input_type)))
         .inspect(move |events| {
             events_received.emit(CountByteSize(
                 events.len(),
                 events.estimated_json_encoded_size_of(),
             ))
-        });
+        })
+}
+
+fn build_multi_output_task_transform(
+    t: Box<dyn MultiOutputTaskTransform>,
+    node: TransformNode,
+    input_rx: BufferReceiver<EventArray>,
+) -> (Task, HashMap<OutputId, fanout::ControlChannel>) {
+    let (mut outputs, controls) = TransformOutputs::new(node.outputs, &node.key);
+    let input_type = node.input_details.data_type();
+
+    let filtered = task_transform_input(input_rx, input_type)
+        .flat_map(|events| stream::iter(events.into_events()));
+
+    // Events that are ready together are sent together, rather than one at a time.
+    let mut stream = t
+        .transform(Box::pin(filtered))
+        .ready_chunks(READY_ARRAY_CAPACITY.get());
+
+    let transform = async move {
+        debug!("Task transform starting.");
+
+        while let Some(events) = stream.next().await {
+            let mut outputs_buf = outputs.new_buf_with_capacity(events.len());
+            for (output, event) in events {
+                outputs_buf.push(output.as_deref(), event);
+            }
+
+            if let Err(e) = outputs.send(&mut outputs_buf).await {
+                debug!("Task transform finished with an error.");
+                return Err(TaskError::wrapped(e));
+            }
+        }
+
+        debug!("Task transform finished normally.");
+        Ok(TaskOutput::Transform)
+    }
+    .boxed();
+
+    let mut output_controls = HashMap::new();
+    for (name, control) in controls {
+        let id = name
+            .map(|name| OutputId::from((&node.key, name)))
+            .unwrap_or_else(|| OutputId::from(&node.key));
+        output_controls.insert(id, control);
+    }
+
+    let task = Task::new(node.key.clone(), node.typetag, transform);
+
+    (task, output_controls)
+}
+
+fn build_task_transform(
+    t: Box<dyn TaskTransform<EventArray>>,
+    input_rx: BufferReceiver<EventArray>,
+    input_type: DataType,
+    typetag: &str,
+    key: &ComponentKey,
+    outputs: &[TransformOutput],
+) -> (Task, HashMap<OutputId, fanout::ControlChannel>) {
+    let (mut fanout, control) = Fanout::new();
+
+    let filtered = task_transform_input(input_rx, input_type);
     let events_sent = register!(EventsSent::from(internal_event::Output(None)));
     let output_id = Arc::new(OutputId {
         component: key.clone(),
@@ -9,10 +9,10 @@ use crate::config::schema::Definition;
 use crate::{
     config::{unit_test::UnitTestSourceConfig, ConfigBuilder},
     test_util::{
-        components::assert_transform_compliance,
+        components::{assert_transform_compliance, COMPONENT_MULTIPLE_OUTPUTS_TESTS},
         mock::{
-            oneshot_sink,
-            transforms::{NoopTransformConfig, TransformType},
+            oneshot_sink, tee_transform,
+            transforms::{NoopTransformConfig, TransformType, TEE_OUTPUT},
         },
         start_topology,
     },
@@ -118,3 +118,56 @@ async fn test_task_transform_single_event() {
     })
     .await;
 }
+
+#[tokio::test]
+async fn test_multi_output_task_transform_single_event() {
+    assert_transform_compliance(async {
+        let mut original_event = Event::Log(LogEvent::from("function transform being tested"));
+
+        let mut builder = ConfigBuilder::default();
+        let (default_tx, default_rx) = channel();
+        let (copy_tx, copy_rx) = channel();
+
+        builder.add_source(
+            "in",
+            UnitTestSourceConfig {
+                events: vec![original_event.clone()],
+            },
+        );
+        builder.add_transform("transform", &["in"], tee_transform());
+        builder.add_sink("out", &["transform"], oneshot_sink(default_tx));
+        builder.add_sink(
+            "copy",
+            &[format!("transform.{TEE_OUTPUT}").as_str()],
+            oneshot_sink(copy_tx),
+        );
+
+        let config = builder.build().expect("building config should not fail");
+        let (topology, _) = start_topology(config, false).await;
+        topology.stop().await;
+
+        original_event.set_source_id(Arc::new(ComponentKey::from("in")));
+        original_event
+            .metadata_mut()
+            .set_schema_definition(&Arc::new(Definition::default_legacy_namespace()));
+
+        for (rx, output_id) in [
+            (default_rx, OutputId::from("transform")),
+            (
+                copy_rx,
+                OutputId::from((&ComponentKey::from("transform"), TEE_OUTPUT.to_string())),
+            ),
+        ] {
+            let events = rx.await.expect("must get back event from rx");
+            let mut events = events.into_events().collect::<Vec<_>>();
+            assert_eq!(events.len(), 1);
+
+            let mut expected = original_event.clone();
+            expected.set_upstream_id(Arc::new(output_id));
+            assert_eq!(expected, events.remove(0));
+        }
+
+        COMPONENT_MULTIPLE_OUTPUTS_TESTS.assert(&["output"]);
+    })
+    .await;
+}
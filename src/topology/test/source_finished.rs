@@ -3,7 +3,7 @@ use vector_lib::codecs::{encoding::FramingConfig, TextSerializerConfig};
 
 use crate::{
     config::Config,
-    sinks::console::{ConsoleSinkConfig, Target},
+    sinks::console::ConsoleSinkConfig,
     sources::demo_logs::DemoLogsConfig,
     test_util::{start_topology, trace_init},
 };
@@ -20,9 +20,8 @@ async fn sources_finished() {
         "out",
         &["in"],
         ConsoleSinkConfig {
-            target: Target::Stdout,
             encoding: (None::<FramingConfig>, TextSerializerConfig::default()).into(),
-            acknowledgements: Default::default(),
+            ..Default::default()
         },
     );
 
@@ -1,17 +1,29 @@
-use std::collections::HashSet;
+use std::{
+    any::Any,
+    cmp::Ordering,
+    collections::{HashSet, VecDeque},
+};
 
 use bytes::{Bytes, BytesMut};
 use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
 use ordered_float::NotNan;
+use serde::{de, Deserialize, Deserializer};
 use vector_lib::configurable::configurable_component;
+use vector_lib::lookup::PathPrefix;
 use vrl::event_path;
 
-use crate::event::{KeyString, LogEvent, Value};
+use super::{field_value, nested_path};
+use crate::event::{KeyString, LogEvent, ObjectMap, Value};
 
 /// Strategies for merging events.
+///
+/// Strategies that take no options can be given by name alone, such as `"sum"`, which is
+/// equivalent to `{ strategy = "sum" }`.
 #[configurable_component]
 #[derive(Clone, Debug)]
-#[serde(rename_all = "snake_case")]
+#[serde(tag = "strategy", rename_all = "snake_case", deny_unknown_fields)]
+#[configurable(metadata(docs::enum_tag_description = "The merge strategy to apply."))]
 pub enum MergeStrategy {
     /// Discard all but the first value found.
     Discard,
@@ -21,17 +33,109 @@ pub enum MergeStrategy {
     /// Works as a way to coalesce by not retaining `null`.
     Retain,
 
+    /// Keep the first value found that is not `null` or an empty string.
+    ///
+    /// If no such value is found, the first value is kept.
+    Coalesce,
+
     /// Sum all numeric values.
-    Sum,
+    Sum {
+        /// The field to store the last numeric value seen in, alongside the sum.
+        ///
+        /// When set, the sum is written to the original field and the last value is written to
+        /// this top-level field.
+        #[configurable(metadata(docs::examples = "value_last"))]
+        #[serde(default)]
+        keep_last_as: Option<String>,
+
+        /// The field holding the unit of each value, such as `MB`.
+        ///
+        /// When set, only values with the same unit as the first value of the group are summed,
+        /// with a missing unit treated as a unit of its own. Values with a different unit are skipped
+        /// with a warning. The unit is written to this field alongside the sum.
+        #[configurable(metadata(docs::examples = "unit"))]
+        #[serde(default)]
+        unit_field: Option<String>,
+    },
 
     /// Keep the maximum numeric value seen.
-    Max,
+    Max {
+        #[configurable(derived)]
+        #[serde(default)]
+        keep: TieBreak,
+
+        /// The top-level fields to take from the event holding the kept value.
+        ///
+        /// When set, these fields are not merged on their own. Instead, the combined event carries
+        /// the values they had in the event holding the kept value, with `keep` choosing between
+        /// events holding equal values. Fields missing from that event are left out.
+        #[configurable(metadata(docs::examples = "host"))]
+        #[serde(default)]
+        context_fields: Vec<String>,
+    },
 
     /// Keep the minimum numeric value seen.
-    Min,
+    Min {
+        #[configurable(derived)]
+        #[serde(default)]
+        keep: TieBreak,
+
+        /// The top-level fields to take from the event holding the kept value.
+        ///
+        /// When set, these fields are not merged on their own. Instead, the combined event carries
+        /// the values they had in the event holding the kept value, with `keep` choosing between
+        /// events holding equal values. Fields missing from that event are left out.
+        #[configurable(metadata(docs::examples = "host"))]
+        #[serde(default)]
+        context_fields: Vec<String>,
+    },
+
+    /// Count the number of non-null values seen.
+    Count,
+
+    /// Count the number of non-null values seen, along with how many of them are distinct.
+    ///
+    /// The number of values is written to the field, and the number of distinct values to a
+    /// `[field-name]_distinct_count` field.
+    DistinctCount,
+
+    /// Keep `true` if any boolean value seen is `true`.
+    ///
+    /// Values that are not booleans are skipped with a warning.
+    Any,
+
+    /// Keep `true` only if every boolean value seen is `true`.
+    ///
+    /// Values that are not booleans are skipped with a warning.
+    All,
+
+    /// Count the number of times a boolean value changed from one event to the next.
+    ///
+    /// Values that are not booleans are skipped with a warning.
+    ToggleCount,
 
     /// Append each value to an array.
-    Array,
+    Array {
+        /// A field to sort object elements of the array by.
+        ///
+        /// Elements are sorted in ascending order when the group is flushed. Elements that are not
+        /// objects, or that are missing the field, are placed last. Elements with equal values
+        /// keep the order they were received in.
+        #[configurable(metadata(docs::examples = "timestamp"))]
+        #[serde(default)]
+        sort_by: Option<String>,
+
+        /// The maximum number of elements to keep in the array.
+        ///
+        /// What happens once the array is full is set by `on_overflow`. Must be greater than `0`.
+        #[configurable(metadata(docs::examples = 100))]
+        #[serde(default)]
+        max_len: Option<usize>,
+
+        #[configurable(derived)]
+        #[serde(default)]
+        on_overflow: ArrayOverflow,
+    },
 
     /// Concatenate each string value, delimited with a space.
     Concat,
@@ -50,6 +154,381 @@ pub enum MergeStrategy {
 
     /// Create a flattened array of all unique values.
     FlatUnique,
+
+    /// Compute an exponential moving average of all numeric values, in the order they are
+    /// received.
+    Ema {
+        /// The smoothing factor applied to each new value.
+        ///
+        /// Must be greater than `0` and at most `1`. Higher values discount older values faster.
+        alpha: f64,
+    },
+
+    /// Compute the average of all numeric values, optionally weighted towards the most recent
+    /// ones.
+    ///
+    /// Each time a value is received, the weights of the values before it are multiplied by
+    /// `decay`, so the last value has a weight of `1`, the one before it a weight of `decay`, and
+    /// so on.
+    Avg {
+        /// The factor that the weights of earlier values are multiplied by for each new value.
+        ///
+        /// Must be greater than `0` and at most `1`. Lower values discount older values faster, and
+        /// `1` weights all values equally.
+        #[configurable(metadata(docs::examples = 0.9))]
+        #[serde(default = "default_avg_decay")]
+        decay: f64,
+    },
+
+    /// Keep the last numeric value seen, and flag whether it is an anomaly.
+    ///
+    /// The last value is compared to the mean and standard deviation of the values before it. A
+    /// `[field-name]_anomaly` field is added, set to `true` if the last value deviates from the
+    /// mean by more than `sigma` standard deviations. With fewer than three values before the last
+    /// one, there is too little data to judge, so it is set to `false`.
+    ZscoreFlag {
+        /// The number of standard deviations from the mean above which a value is an anomaly.
+        ///
+        /// Must be greater than `0`.
+        #[configurable(metadata(docs::examples = 3.0))]
+        sigma: f64,
+    },
+
+    /// Keep the most frequent values seen, along with how many times each was seen.
+    ///
+    /// The values are emitted as an array of objects with `value` and `count` fields, ordered
+    /// from most to least frequent. Values seen equally often are ordered by when they were first
+    /// seen.
+    TopK {
+        /// The number of values to keep.
+        ///
+        /// Must be greater than `0`.
+        #[configurable(metadata(docs::examples = 5))]
+        k: usize,
+    },
+
+    /// Create a sorted array of the unique values seen, keeping at most `max` of them.
+    ///
+    /// Arrays are flattened into their elements, and `null` values are skipped. The array is
+    /// sorted in ascending order, and once it is full, only the `max` smallest values are kept.
+    SortedUnique {
+        /// The maximum number of values to keep.
+        ///
+        /// Must be greater than `0`.
+        #[configurable(metadata(docs::examples = 100))]
+        max: usize,
+    },
+
+    /// Remove the elements of each later array from the first array seen.
+    ///
+    /// The first array is the baseline, and each element of a later array removes one matching
+    /// element from it, as a multiset difference. Elements that are not present are ignored.
+    ArrayDiff,
+
+    /// Compute the bounding box of coordinates given as objects with `lat` and `lon` fields.
+    ///
+    /// The bounding box is emitted as an object with `min_lat`, `min_lon`, `max_lat`, and
+    /// `max_lon` fields. Values that are not coordinates are skipped.
+    #[serde(rename = "bbox")]
+    BoundingBox,
+
+    /// Sum the durations, in milliseconds, between a start and an end timestamp of each event.
+    ///
+    /// The merged field itself is not read from events. Events missing either timestamp are
+    /// skipped.
+    SumDuration {
+        /// The field containing the start timestamp of each event.
+        #[configurable(metadata(docs::examples = "started_at"))]
+        start: String,
+
+        /// The field containing the end timestamp of each event.
+        #[configurable(metadata(docs::examples = "ended_at"))]
+        end: String,
+    },
+
+    /// Sum a numeric field of objects, grouped by another field of the objects.
+    ///
+    /// Values are arrays of objects, or single objects. The sums are emitted as an array of
+    /// objects, in the order their keys were first seen, each containing the `key` field and the
+    /// sum in a field named after `value` with a `_total` suffix. Objects missing either field, or
+    /// with a non-numeric value, are skipped.
+    GroupSum {
+        /// The field of each object to group by.
+        #[configurable(metadata(docs::examples = "sku"))]
+        key: String,
+
+        /// The numeric field of each object to sum.
+        #[configurable(metadata(docs::examples = "qty"))]
+        value: String,
+    },
+
+    /// Keep the value from the event with the latest timestamp, regardless of the order events are
+    /// received in.
+    ///
+    /// A value only replaces the current one if its event's timestamp is later than the timestamp
+    /// of the event that set the current value. Events missing the timestamp are skipped.
+    LastByTimestamp {
+        /// The field containing the timestamp of each event.
+        #[configurable(metadata(docs::examples = "updated_at"))]
+        timestamp_field: String,
+    },
+}
+
+/// What to do once an array reaches its `max_len`.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArrayOverflow {
+    /// Discard new elements, keeping the oldest ones.
+    #[default]
+    DropNew,
+
+    /// Discard the oldest elements to make room for new ones, keeping the newest ones.
+    DropOldest,
+
+    /// Flush the group with the event that fills the array.
+    Flush,
+}
+
+/// Which of several events holding equal values to keep the value and context fields of.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreak {
+    /// Keep the first of equal values seen.
+    #[default]
+    First,
+
+    /// Keep the last of equal values seen.
+    Last,
+}
+
+impl TieBreak {
+    /// Whether a new value replaces the current one, given how the new value compares to it and
+    /// the ordering that the strategy prefers.
+    fn replaces(self, ordering: Ordering, preferred: Ordering) -> bool {
+        ordering == preferred || (self == TieBreak::Last && ordering == Ordering::Equal)
+    }
+}
+
+/// How to merge timestamp fields that have no merge strategy.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampStrategy {
+    /// Keep the first timestamp, and add a `[field-name]_end` field with the last one.
+    #[default]
+    KeepFirstWithEnd,
+
+    /// Keep the first timestamp.
+    KeepFirst,
+
+    /// Keep the last timestamp.
+    KeepLast,
+
+    /// Replace the field with an object holding the first timestamp in `start` and the last one
+    /// in `end`.
+    Range,
+}
+
+const fn default_avg_decay() -> f64 {
+    1.0
+}
+
+impl MergeStrategy {
+    /// Checks that the options given for this strategy are valid.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        match self {
+            MergeStrategy::Avg { decay } => {
+                if *decay > 0.0 && *decay <= 1.0 {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "`decay` must be greater than 0 and at most 1, found: {}",
+                        decay
+                    ))
+                }
+            }
+            MergeStrategy::Ema { alpha } => {
+                if *alpha > 0.0 && *alpha <= 1.0 {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "`alpha` must be greater than 0 and at most 1, found: {}",
+                        alpha
+                    ))
+                }
+            }
+            MergeStrategy::ZscoreFlag { sigma } => {
+                if *sigma > 0.0 {
+                    Ok(())
+                } else {
+                    Err(format!("`sigma` must be greater than 0, found: {}", sigma))
+                }
+            }
+            MergeStrategy::TopK { k } => {
+                if *k > 0 {
+                    Ok(())
+                } else {
+                    Err("`k` must be greater than 0".to_string())
+                }
+            }
+            MergeStrategy::SortedUnique { max } => {
+                if *max > 0 {
+                    Ok(())
+                } else {
+                    Err("`max` must be greater than 0".to_string())
+                }
+            }
+            MergeStrategy::Array {
+                max_len: Some(0), ..
+            } => Err("`max_len` must be greater than 0".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    /// The name of this strategy, as given in the configuration.
+    pub(crate) const fn name(&self) -> &'static str {
+        match self {
+            MergeStrategy::Discard => "discard",
+            MergeStrategy::Retain => "retain",
+            MergeStrategy::Coalesce => "coalesce",
+            MergeStrategy::Sum { .. } => "sum",
+            MergeStrategy::Max { .. } => "max",
+            MergeStrategy::Min { .. } => "min",
+            MergeStrategy::Count => "count",
+            MergeStrategy::DistinctCount => "distinct_count",
+            MergeStrategy::Any => "any",
+            MergeStrategy::All => "all",
+            MergeStrategy::ToggleCount => "toggle_count",
+            MergeStrategy::Array { .. } => "array",
+            MergeStrategy::Concat => "concat",
+            MergeStrategy::ConcatNewline => "concat_newline",
+            MergeStrategy::ConcatRaw => "concat_raw",
+            MergeStrategy::ShortestArray => "shortest_array",
+            MergeStrategy::LongestArray => "longest_array",
+            MergeStrategy::FlatUnique => "flat_unique",
+            MergeStrategy::Ema { .. } => "ema",
+            MergeStrategy::Avg { .. } => "avg",
+            MergeStrategy::ZscoreFlag { .. } => "zscore_flag",
+            MergeStrategy::TopK { .. } => "top_k",
+            MergeStrategy::SortedUnique { .. } => "sorted_unique",
+            MergeStrategy::ArrayDiff => "array_diff",
+            MergeStrategy::BoundingBox => "bbox",
+            MergeStrategy::SumDuration { .. } => "sum_duration",
+            MergeStrategy::GroupSum { .. } => "group_sum",
+            MergeStrategy::LastByTimestamp { .. } => "last_by_timestamp",
+        }
+    }
+
+    /// Whether or not this strategy flushes its group once the merged value is full.
+    pub(crate) const fn flushes_when_full(&self) -> bool {
+        matches!(
+            self,
+            MergeStrategy::Array {
+                max_len: Some(_),
+                on_overflow: ArrayOverflow::Flush,
+                ..
+            }
+        )
+    }
+
+    /// Whether or not this strategy derives its value from other fields of the event, rather
+    /// than from the field being merged.
+    pub(crate) fn is_derived(&self) -> bool {
+        matches!(
+            self,
+            MergeStrategy::SumDuration { .. }
+                | MergeStrategy::LastByTimestamp { .. }
+                | MergeStrategy::Sum {
+                    unit_field: Some(_),
+                    ..
+                }
+        ) || !self.context_fields().is_empty()
+    }
+
+    /// The fields taken from the event holding the kept value, rather than merged on their own.
+    pub(crate) fn context_fields(&self) -> &[String] {
+        match self {
+            MergeStrategy::Max { context_fields, .. }
+            | MergeStrategy::Min { context_fields, .. } => context_fields,
+            _ => &[],
+        }
+    }
+
+    /// Whether or not this strategy sums or counts the merged values, so that its result grows
+    /// with the number of events merged.
+    pub(crate) const fn accumulates(&self) -> bool {
+        matches!(
+            self,
+            MergeStrategy::Sum { .. } | MergeStrategy::SumDuration { .. } | MergeStrategy::Count
+        )
+    }
+
+    /// Derives the value to merge from the fields of an event, for strategies where
+    /// `is_derived` is true.
+    pub(crate) fn derive_value(&self, key: &str, event: &Value) -> Option<Value> {
+        match self {
+            MergeStrategy::SumDuration { start, end } => {
+                match (field_value(event, start), field_value(event, end)) {
+                    (Some(Value::Timestamp(start)), Some(Value::Timestamp(end))) => {
+                        Some(Value::Integer((*end - *start).num_milliseconds()))
+                    }
+                    _ => None,
+                }
+            }
+            MergeStrategy::LastByTimestamp { timestamp_field } => {
+                match (field_value(event, timestamp_field), field_value(event, key)) {
+                    (Some(Value::Timestamp(timestamp)), Some(value)) => Some(Value::Array(vec![
+                        Value::Timestamp(*timestamp),
+                        value.clone(),
+                    ])),
+                    _ => None,
+                }
+            }
+            MergeStrategy::Sum {
+                unit_field: Some(unit_field),
+                ..
+            } => field_value(event, key).map(|value| {
+                let unit = field_value(event, unit_field).cloned();
+                Value::Array(vec![value.clone(), unit.unwrap_or(Value::Null)])
+            }),
+            MergeStrategy::Max { context_fields, .. }
+            | MergeStrategy::Min { context_fields, .. } => field_value(event, key).map(|value| {
+                let context = context_fields
+                    .iter()
+                    .filter_map(|field| {
+                        let value = event.as_object()?.get(field.as_str())?;
+                        Some((field.as_str().into(), value.clone()))
+                    })
+                    .collect();
+                Value::Array(vec![value.clone(), Value::Object(context)])
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Deserializes a map of field names to merge strategies, accepting a bare strategy name as
+/// shorthand for a strategy without options.
+pub(crate) fn deserialize_merge_strategies<'de, D>(
+    deserializer: D,
+) -> Result<IndexMap<KeyString, MergeStrategy>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    IndexMap::<KeyString, serde_json::Value>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(field, strategy)| {
+            let strategy = match strategy {
+                serde_json::Value::String(name) => serde_json::json!({ "strategy": name }),
+                strategy => strategy,
+            };
+            MergeStrategy::deserialize(strategy)
+                .map(|strategy| (field, strategy))
+                .map_err(de::Error::custom)
+        })
+        .collect()
 }
 
 #[derive(Debug, Clone)]
@@ -74,6 +553,40 @@ impl ReduceValueMerger for DiscardMerger {
     }
 }
 
+#[derive(Debug, Clone)]
+struct CoalesceMerger {
+    v: Value,
+}
+
+impl CoalesceMerger {
+    #[allow(clippy::missing_const_for_fn)] // const cannot run destructor
+    fn new(v: Value) -> Self {
+        Self { v }
+    }
+
+    fn is_blank(v: &Value) -> bool {
+        match v {
+            Value::Null => true,
+            Value::Bytes(b) => b.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+impl ReduceValueMerger for CoalesceMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        if Self::is_blank(&self.v) && !Self::is_blank(&v) {
+            self.v = v;
+        }
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        v.insert(event_path!(k.as_str()), self.v);
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 struct RetainMerger {
     v: Value,
@@ -169,18 +682,126 @@ impl ReduceValueMerger for ConcatArrayMerger {
 
 #[derive(Debug, Clone)]
 struct ArrayMerger {
-    v: Vec<Value>,
+    v: VecDeque<Value>,
+    sort_by: Option<String>,
+    max_len: Option<usize>,
+    on_overflow: ArrayOverflow,
 }
 
 impl ArrayMerger {
-    fn new(v: Value) -> Self {
-        Self { v: vec![v] }
+    fn new(
+        v: Value,
+        sort_by: Option<String>,
+        max_len: Option<usize>,
+        on_overflow: ArrayOverflow,
+    ) -> Self {
+        Self {
+            v: VecDeque::from([v]),
+            sort_by,
+            max_len,
+            on_overflow,
+        }
     }
 }
 
 impl ReduceValueMerger for ArrayMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
-        self.v.push(v);
+        if self.max_len.is_some_and(|max_len| self.v.len() >= max_len) {
+            match self.on_overflow {
+                // A full array flushes its group before another value can be added.
+                ArrayOverflow::DropNew | ArrayOverflow::Flush => return Ok(()),
+                ArrayOverflow::DropOldest => {
+                    self.v.pop_front();
+                }
+            }
+        }
+        self.v.push_back(v);
+        Ok(())
+    }
+
+    fn is_full(&self) -> bool {
+        self.on_overflow == ArrayOverflow::Flush
+            && self.max_len.is_some_and(|max_len| self.v.len() >= max_len)
+    }
+
+    fn merge(&mut self, other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        let other = downcast::<Self>(other)?;
+        for v in other.v {
+            self.add(v)?;
+        }
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        let Self {
+            v: values, sort_by, ..
+        } = *self;
+        let mut values = Vec::from(values);
+        if let Some(field) = sort_by.as_deref() {
+            let key = |element: &Value| {
+                element
+                    .as_object()
+                    .and_then(|object| object.get(field))
+                    .cloned()
+            };
+            // The sort is stable, so elements with equal keys keep the order they were received in.
+            values.sort_by(|a, b| match (key(a), key(b)) {
+                (Some(a), Some(b)) => compare_values(&a, &b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            });
+        }
+        v.insert(event_path!(k.as_str()), Value::Array(values));
+        Ok(())
+    }
+}
+
+/// Orders values of the same type, treating values of different types as equal.
+pub(crate) fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+        (Value::Float(a), Value::Float(b)) => a.cmp(b),
+        (Value::Integer(a), Value::Float(b)) => (*a as f64).total_cmp(&b.into_inner()),
+        (Value::Float(a), Value::Integer(b)) => a.into_inner().total_cmp(&(*b as f64)),
+        (Value::Bytes(a), Value::Bytes(b)) => a.cmp(b),
+        (Value::Timestamp(a), Value::Timestamp(b)) => a.cmp(b),
+        (Value::Boolean(a), Value::Boolean(b)) => a.cmp(b),
+        _ => Ordering::Equal,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ArrayDiffMerger {
+    v: Vec<Value>,
+}
+
+impl ArrayDiffMerger {
+    fn new(v: Vec<Value>) -> Self {
+        Self { v }
+    }
+}
+
+impl ReduceValueMerger for ArrayDiffMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        if let Value::Array(a) = v {
+            for removed in a {
+                if let Some(i) = self.v.iter().position(|value| *value == removed) {
+                    self.v.remove(i);
+                }
+            }
+            Ok(())
+        } else {
+            Err(format!(
+                "expected array value, found: '{}'",
+                v.to_string_lossy()
+            ))
+        }
+    }
+
+    fn merge(&mut self, _other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        // The other group removed its elements from a different first array, so what is left of
+        // this one is kept.
         Ok(())
     }
 
@@ -303,204 +924,449 @@ impl ReduceValueMerger for FlatUniqueMerger {
 }
 
 #[derive(Debug, Clone)]
-struct TimestampWindowMerger {
-    started: DateTime<Utc>,
-    latest: DateTime<Utc>,
+struct SortedUniqueMerger {
+    // The unique values kept, in ascending order.
+    v: Vec<Value>,
+    max: usize,
 }
 
-impl TimestampWindowMerger {
-    const fn new(v: DateTime<Utc>) -> Self {
-        Self {
-            started: v,
-            latest: v,
+impl SortedUniqueMerger {
+    fn new(v: Value, max: usize) -> Self {
+        let mut merger = Self { v: Vec::new(), max };
+        merger.insert_flattened(v);
+        merger
+    }
+
+    fn insert_flattened(&mut self, v: Value) {
+        match v {
+            Value::Array(values) => values.into_iter().for_each(|v| self.insert(v)),
+            v => self.insert(v),
+        }
+    }
+
+    fn insert(&mut self, v: Value) {
+        if v == Value::Null || self.v.contains(&v) {
+            return;
         }
+        let index = self
+            .v
+            .partition_point(|probe| compare_values(probe, &v) != Ordering::Greater);
+        self.v.insert(index, v);
+        self.v.truncate(self.max);
     }
 }
 
-impl ReduceValueMerger for TimestampWindowMerger {
+impl ReduceValueMerger for SortedUniqueMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
-        if let Value::Timestamp(ts) = v {
-            self.latest = ts
-        } else {
-            return Err(format!(
-                "expected timestamp value, found: {}",
-                v.to_string_lossy()
-            ));
-        }
+        self.insert_flattened(v);
         Ok(())
     }
 
     fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
-        v.insert(
-            event_path!(format!("{}_end", k).as_str()),
-            Value::Timestamp(self.latest),
-        );
-        v.insert(event_path!(k.as_str()), Value::Timestamp(self.started));
+        v.insert(event_path!(k.as_str()), Value::Array(self.v));
         Ok(())
     }
 }
 
 #[derive(Debug, Clone)]
-enum NumberMergerValue {
-    Int(i64),
-    Float(NotNan<f64>),
-}
-
-impl From<i64> for NumberMergerValue {
-    fn from(v: i64) -> Self {
-        NumberMergerValue::Int(v)
-    }
+struct TopKMerger {
+    k: usize,
+    // Counts of each value, in the order they were first seen.
+    counts: IndexMap<Value, usize>,
 }
 
-impl From<NotNan<f64>> for NumberMergerValue {
-    fn from(v: NotNan<f64>) -> Self {
-        NumberMergerValue::Float(v)
+impl TopKMerger {
+    #[allow(clippy::mutable_key_type)] // false positive due to bytes::Bytes
+    fn new(v: Value, k: usize) -> Self {
+        let mut counts = IndexMap::new();
+        counts.insert(v, 1);
+        Self { k, counts }
     }
 }
 
-#[derive(Debug, Clone)]
-struct AddNumbersMerger {
-    v: NumberMergerValue,
-}
-
-impl AddNumbersMerger {
-    const fn new(v: NumberMergerValue) -> Self {
-        Self { v }
+impl ReduceValueMerger for TopKMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        *self.counts.entry(v).or_insert(0) += 1;
+        Ok(())
     }
-}
 
-impl ReduceValueMerger for AddNumbersMerger {
-    fn add(&mut self, v: Value) -> Result<(), String> {
-        // Try and keep max precision with integer values, but once we've
-        // received a float downgrade to float precision.
-        match v {
-            Value::Integer(i) => match self.v {
-                NumberMergerValue::Int(j) => self.v = NumberMergerValue::Int(i + j),
-                NumberMergerValue::Float(j) => {
-                    self.v = NumberMergerValue::Float(NotNan::new(i as f64).unwrap() + j)
-                }
-            },
-            Value::Float(f) => match self.v {
-                NumberMergerValue::Int(j) => self.v = NumberMergerValue::Float(f + j as f64),
-                NumberMergerValue::Float(j) => self.v = NumberMergerValue::Float(f + j),
-            },
-            _ => {
-                return Err(format!(
-                    "expected numeric value, found: '{}'",
-                    v.to_string_lossy()
-                ));
-            }
+    fn merge(&mut self, other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        let other = downcast::<Self>(other)?;
+        for (v, count) in other.counts {
+            *self.counts.entry(v).or_insert(0) += count;
         }
         Ok(())
     }
 
     fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
-        match self.v {
-            NumberMergerValue::Float(f) => v.insert(event_path!(k.as_str()), Value::Float(f)),
-            NumberMergerValue::Int(i) => v.insert(event_path!(k.as_str()), Value::Integer(i)),
-        };
+        let mut counts: Vec<_> = self.counts.into_iter().collect();
+        // The sort is stable, so ties are kept in the order they were first seen.
+        counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+        counts.truncate(self.k);
+
+        let top = counts
+            .into_iter()
+            .map(|(value, count)| {
+                let mut object = ObjectMap::new();
+                object.insert("value".into(), value);
+                object.insert("count".into(), Value::from(count as i64));
+                Value::Object(object)
+            })
+            .collect();
+        v.insert(event_path!(k.as_str()), Value::Array(top));
         Ok(())
     }
 }
 
 #[derive(Debug, Clone)]
-struct MaxNumberMerger {
-    v: NumberMergerValue,
+struct GroupSumMerger {
+    key: String,
+    value: String,
+    // Sums of each key, in the order they were first seen.
+    sums: IndexMap<Value, AddNumbersMerger>,
 }
 
-impl MaxNumberMerger {
-    const fn new(v: NumberMergerValue) -> Self {
-        Self { v }
+impl GroupSumMerger {
+    fn new(v: Value, key: &str, value: &str) -> Result<Self, String> {
+        let mut merger = Self {
+            key: key.to_string(),
+            value: value.to_string(),
+            sums: IndexMap::new(),
+        };
+        merger.add(v)?;
+        Ok(merger)
+    }
+
+    #[allow(clippy::mutable_key_type)] // false positive due to bytes::Bytes
+    fn add_object(&mut self, object: ObjectMap) {
+        let (Some(key), Some(value)) = (
+            object.get(self.key.as_str()),
+            object.get(self.value.as_str()),
+        ) else {
+            return;
+        };
+        let value = match value {
+            Value::Integer(i) => NumberMergerValue::from(*i),
+            Value::Float(f) => NumberMergerValue::from(*f),
+            _ => return,
+        };
+        match self.sums.get_mut(key) {
+            Some(sum) => {
+                // Only fails for non-numeric values, which are skipped above.
+                let _ = sum.add(value.into());
+            }
+            None => {
+                self.sums.insert(key.clone(), AddNumbersMerger::new(value));
+            }
+        }
     }
 }
 
-impl ReduceValueMerger for MaxNumberMerger {
+impl ReduceValueMerger for GroupSumMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
-        // Try and keep max precision with integer values, but once we've
-        // received a float downgrade to float precision.
         match v {
-            Value::Integer(i) => {
-                match self.v {
-                    NumberMergerValue::Int(i2) => {
-                        if i > i2 {
-                            self.v = NumberMergerValue::Int(i);
-                        }
+            Value::Array(elements) => {
+                for element in elements {
+                    if let Value::Object(object) = element {
+                        self.add_object(object);
                     }
-                    NumberMergerValue::Float(f2) => {
-                        let f = NotNan::new(i as f64).unwrap();
-                        if f > f2 {
-                            self.v = NumberMergerValue::Float(f);
-                        }
-                    }
-                };
-            }
-            Value::Float(f) => {
-                let f2 = match self.v {
-                    NumberMergerValue::Int(i2) => NotNan::new(i2 as f64).unwrap(),
-                    NumberMergerValue::Float(f2) => f2,
-                };
-                if f > f2 {
-                    self.v = NumberMergerValue::Float(f);
                 }
             }
-            _ => {
+            Value::Object(object) => self.add_object(object),
+            v => {
                 return Err(format!(
-                    "expected numeric value, found: '{}'",
+                    "expected array or object value, found: '{}'",
                     v.to_string_lossy()
-                ));
+                ))
             }
         }
         Ok(())
     }
 
-    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
-        match self.v {
-            NumberMergerValue::Float(f) => v.insert(event_path!(k.as_str()), Value::Float(f)),
-            NumberMergerValue::Int(i) => v.insert(event_path!(k.as_str()), Value::Integer(i)),
-        };
+    fn merge(&mut self, other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        let other = downcast::<Self>(other)?;
+        for (key, sum) in other.sums {
+            match self.sums.get_mut(&key) {
+                Some(total) => total.add(sum.v.into())?,
+                None => {
+                    self.sums.insert(key, sum);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        let total_field = format!("{}_total", self.value);
+        let sums = self
+            .sums
+            .into_iter()
+            .map(|(key, sum)| {
+                let mut object = ObjectMap::new();
+                object.insert(self.key.as_str().into(), key);
+                object.insert(total_field.as_str().into(), Value::from(sum.v));
+                Value::Object(object)
+            })
+            .collect();
+        v.insert(event_path!(k.as_str()), Value::Array(sums));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct BoundingBoxMerger {
+    min_lat: f64,
+    min_lon: f64,
+    max_lat: f64,
+    max_lon: f64,
+}
+
+/// Returns the latitude and longitude of a coordinate object.
+fn coordinates(v: &Value) -> Result<(f64, f64), String> {
+    let number = |key: &str| match v.as_object().and_then(|object| object.get(key)) {
+        Some(Value::Integer(i)) => Some(*i as f64),
+        Some(Value::Float(f)) => Some(f.into_inner()),
+        _ => None,
+    };
+    match (number("lat"), number("lon")) {
+        (Some(lat), Some(lon)) => Ok((lat, lon)),
+        _ => Err(format!(
+            "expected object with numeric `lat` and `lon` fields, found: '{}'",
+            v.to_string_lossy()
+        )),
+    }
+}
+
+impl BoundingBoxMerger {
+    fn new(v: Value) -> Result<Self, String> {
+        let (lat, lon) = coordinates(&v)?;
+        Ok(Self {
+            min_lat: lat,
+            min_lon: lon,
+            max_lat: lat,
+            max_lon: lon,
+        })
+    }
+}
+
+impl ReduceValueMerger for BoundingBoxMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        let (lat, lon) = coordinates(&v)?;
+        self.min_lat = self.min_lat.min(lat);
+        self.min_lon = self.min_lon.min(lon);
+        self.max_lat = self.max_lat.max(lat);
+        self.max_lon = self.max_lon.max(lon);
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        let other = downcast::<Self>(other)?;
+        self.min_lat = self.min_lat.min(other.min_lat);
+        self.min_lon = self.min_lon.min(other.min_lon);
+        self.max_lat = self.max_lat.max(other.max_lat);
+        self.max_lon = self.max_lon.max(other.max_lon);
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        let mut bbox = ObjectMap::new();
+        for (key, value) in [
+            ("min_lat", self.min_lat),
+            ("min_lon", self.min_lon),
+            ("max_lat", self.max_lat),
+            ("max_lon", self.max_lon),
+        ] {
+            let value = NotNan::new(value).map_err(|_| "coordinate is not a number".to_string())?;
+            bbox.insert(key.into(), Value::Float(value));
+        }
+        v.insert(event_path!(k.as_str()), Value::Object(bbox));
         Ok(())
     }
 }
 
 #[derive(Debug, Clone)]
-struct MinNumberMerger {
+struct TimestampWindowMerger {
+    started: DateTime<Utc>,
+    latest: DateTime<Utc>,
+    strategy: TimestampStrategy,
+}
+
+impl TimestampWindowMerger {
+    const fn new(v: DateTime<Utc>, strategy: TimestampStrategy) -> Self {
+        Self {
+            started: v,
+            latest: v,
+            strategy,
+        }
+    }
+}
+
+impl ReduceValueMerger for TimestampWindowMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        if let Value::Timestamp(ts) = v {
+            self.latest = ts
+        } else {
+            return Err(format!(
+                "expected timestamp value, found: {}",
+                v.to_string_lossy()
+            ));
+        }
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        let other = downcast::<Self>(other)?;
+        self.started = self.started.min(other.started);
+        self.latest = self.latest.max(other.latest);
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        let value = match self.strategy {
+            TimestampStrategy::KeepFirstWithEnd => {
+                v.insert(
+                    event_path!(format!("{}_end", k).as_str()),
+                    Value::Timestamp(self.latest),
+                );
+                Value::Timestamp(self.started)
+            }
+            TimestampStrategy::KeepFirst => Value::Timestamp(self.started),
+            TimestampStrategy::KeepLast => Value::Timestamp(self.latest),
+            TimestampStrategy::Range => Value::Object(ObjectMap::from([
+                ("start".into(), Value::Timestamp(self.started)),
+                ("end".into(), Value::Timestamp(self.latest)),
+            ])),
+        };
+        v.insert(event_path!(k.as_str()), value);
+        Ok(())
+    }
+}
+
+/// Keeps the value with the latest timestamp, from values derived as `[timestamp, value]` pairs.
+#[derive(Debug, Clone)]
+struct LastByTimestampMerger {
+    timestamp: DateTime<Utc>,
+    v: Value,
+}
+
+impl LastByTimestampMerger {
+    fn new(v: Value) -> Result<Self, String> {
+        let (timestamp, v) = Self::timestamped(v)?;
+        Ok(Self { timestamp, v })
+    }
+
+    fn timestamped(v: Value) -> Result<(DateTime<Utc>, Value), String> {
+        match v {
+            Value::Array(pair) => match <[Value; 2]>::try_from(pair) {
+                Ok([Value::Timestamp(timestamp), v]) => Ok((timestamp, v)),
+                _ => Err("expected a timestamp and a value".to_string()),
+            },
+            v => Err(format!(
+                "expected a timestamped value, found: '{}'",
+                v.to_string_lossy()
+            )),
+        }
+    }
+}
+
+impl ReduceValueMerger for LastByTimestampMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        let (timestamp, v) = Self::timestamped(v)?;
+        if timestamp > self.timestamp {
+            self.timestamp = timestamp;
+            self.v = v;
+        }
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        let other = downcast::<Self>(other)?;
+        if other.timestamp > self.timestamp {
+            *self = *other;
+        }
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        v.insert(event_path!(k.as_str()), self.v);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum NumberMergerValue {
+    Int(i64),
+    Float(NotNan<f64>),
+}
+
+impl From<i64> for NumberMergerValue {
+    fn from(v: i64) -> Self {
+        NumberMergerValue::Int(v)
+    }
+}
+
+impl From<NotNan<f64>> for NumberMergerValue {
+    fn from(v: NotNan<f64>) -> Self {
+        NumberMergerValue::Float(v)
+    }
+}
+
+impl From<&NumberMergerValue> for f64 {
+    fn from(v: &NumberMergerValue) -> Self {
+        match v {
+            NumberMergerValue::Int(i) => *i as f64,
+            NumberMergerValue::Float(f) => f.into_inner(),
+        }
+    }
+}
+
+impl From<NumberMergerValue> for Value {
+    fn from(v: NumberMergerValue) -> Self {
+        match v {
+            NumberMergerValue::Float(f) => Value::Float(f),
+            NumberMergerValue::Int(i) => Value::Integer(i),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AddNumbersMerger {
     v: NumberMergerValue,
+    last: Option<(String, NumberMergerValue)>,
 }
 
-impl MinNumberMerger {
+impl AddNumbersMerger {
     const fn new(v: NumberMergerValue) -> Self {
-        Self { v }
+        Self { v, last: None }
+    }
+
+    /// Also keeps the last value added, to be inserted into the `keep_last_as` field.
+    fn keep_last_as(mut self, keep_last_as: Option<&String>) -> Self {
+        self.last = keep_last_as.map(|field| (field.clone(), self.v.clone()));
+        self
     }
 }
 
-impl ReduceValueMerger for MinNumberMerger {
+impl ReduceValueMerger for AddNumbersMerger {
     fn add(&mut self, v: Value) -> Result<(), String> {
         // Try and keep max precision with integer values, but once we've
         // received a float downgrade to float precision.
-        match v {
+        let added = match v {
             Value::Integer(i) => {
                 match self.v {
-                    NumberMergerValue::Int(i2) => {
-                        if i < i2 {
-                            self.v = NumberMergerValue::Int(i);
-                        }
-                    }
-                    NumberMergerValue::Float(f2) => {
-                        let f = NotNan::new(i as f64).unwrap();
-                        if f < f2 {
-                            self.v = NumberMergerValue::Float(f);
-                        }
+                    NumberMergerValue::Int(j) => self.v = NumberMergerValue::Int(i + j),
+                    NumberMergerValue::Float(j) => {
+                        self.v = NumberMergerValue::Float(NotNan::new(i as f64).unwrap() + j)
                     }
-                };
+                }
+                NumberMergerValue::Int(i)
             }
             Value::Float(f) => {
-                let f2 = match self.v {
-                    NumberMergerValue::Int(i2) => NotNan::new(i2 as f64).unwrap(),
-                    NumberMergerValue::Float(f2) => f2,
-                };
-                if f < f2 {
-                    self.v = NumberMergerValue::Float(f);
+                match self.v {
+                    NumberMergerValue::Int(j) => self.v = NumberMergerValue::Float(f + j as f64),
+                    NumberMergerValue::Float(j) => self.v = NumberMergerValue::Float(f + j),
                 }
+                NumberMergerValue::Float(f)
             }
             _ => {
                 return Err(format!(
@@ -508,22 +1374,608 @@ impl ReduceValueMerger for MinNumberMerger {
                     v.to_string_lossy()
                 ));
             }
+        };
+        if let Some((_, last)) = self.last.as_mut() {
+            *last = added;
+        }
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        // The sum of the other group is not a value of its own, so the last value is kept.
+        let other = downcast::<Self>(other)?;
+        let last = self.last.take();
+        self.add(other.v.into())?;
+        self.last = last;
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        if let Some((field, last)) = self.last {
+            v.insert(event_path!(field.as_str()), Value::from(last));
+        }
+        v.insert(event_path!(k.as_str()), Value::from(self.v));
+        Ok(())
+    }
+}
+
+/// Sums values derived as `[value, unit]` pairs, skipping values with a different unit than the
+/// first value.
+#[derive(Debug, Clone)]
+struct UnitSumMerger {
+    sum: AddNumbersMerger,
+    unit_field: String,
+    unit: Value,
+}
+
+impl UnitSumMerger {
+    fn new(v: Value, keep_last_as: Option<&String>, unit_field: &str) -> Result<Self, String> {
+        let (v, unit) = Self::with_unit(v)?;
+        let sum = match v {
+            Value::Integer(i) => AddNumbersMerger::new(i.into()),
+            Value::Float(f) => AddNumbersMerger::new(f.into()),
+            v => {
+                return Err(format!(
+                    "expected number value, found: '{}'",
+                    v.to_string_lossy()
+                ))
+            }
+        };
+        Ok(Self {
+            sum: sum.keep_last_as(keep_last_as),
+            unit_field: unit_field.to_string(),
+            unit,
+        })
+    }
+
+    fn with_unit(v: Value) -> Result<(Value, Value), String> {
+        match v {
+            Value::Array(pair) => match <[Value; 2]>::try_from(pair) {
+                Ok([v, unit]) => Ok((v, unit)),
+                Err(_) => Err("expected a value and a unit".to_string()),
+            },
+            v => Err(format!(
+                "expected a value with a unit, found: '{}'",
+                v.to_string_lossy()
+            )),
         }
+    }
+
+    fn check_unit(&self, unit: &Value) -> Result<(), String> {
+        if *unit == self.unit {
+            Ok(())
+        } else {
+            Err(format!(
+                "mismatched units, expected '{}' but found '{}'; value not summed",
+                self.unit.to_string_lossy(),
+                unit.to_string_lossy()
+            ))
+        }
+    }
+}
+
+impl ReduceValueMerger for UnitSumMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        let (v, unit) = Self::with_unit(v)?;
+        self.check_unit(&unit)?;
+        self.sum.add(v)
+    }
+
+    fn merge(&mut self, other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        let other = downcast::<Self>(other)?;
+        self.check_unit(&other.unit)?;
+        self.sum.merge(Box::new(other.sum))
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        if !self.unit.is_null() {
+            match nested_path(&self.unit_field) {
+                Some(path) => v.insert((PathPrefix::Event, &path), self.unit),
+                None => v.insert(event_path!(self.unit_field.as_str()), self.unit),
+            };
+        }
+        Box::new(self.sum).insert_into(k, v)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CountMerger {
+    count: i64,
+}
+
+impl CountMerger {
+    const fn new(v: &Value) -> Self {
+        Self {
+            count: if matches!(v, Value::Null) { 0 } else { 1 },
+        }
+    }
+}
+
+impl ReduceValueMerger for CountMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        if Value::Null != v {
+            self.count += 1;
+        }
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        self.count += downcast::<Self>(other)?.count;
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        v.insert(event_path!(k.as_str()), Value::Integer(self.count));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DistinctCountMerger {
+    count: i64,
+    distinct: HashSet<Value>,
+}
+
+impl DistinctCountMerger {
+    fn new(v: Value) -> Self {
+        let mut merger = Self {
+            count: 0,
+            distinct: HashSet::new(),
+        };
+        merger.insert(v);
+        merger
+    }
+
+    #[allow(clippy::mutable_key_type)] // false positive due to bytes::Bytes
+    fn insert(&mut self, v: Value) {
+        if Value::Null != v {
+            self.count += 1;
+            self.distinct.insert(v);
+        }
+    }
+}
+
+impl ReduceValueMerger for DistinctCountMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        self.insert(v);
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        let other = downcast::<Self>(other)?;
+        self.count += other.count;
+        self.distinct.extend(other.distinct);
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        v.insert(
+            event_path!(format!("{}_distinct_count", k).as_str()),
+            Value::Integer(self.distinct.len() as i64),
+        );
+        v.insert(event_path!(k.as_str()), Value::Integer(self.count));
+        Ok(())
+    }
+}
+
+fn expect_boolean(v: &Value) -> Result<bool, String> {
+    match v {
+        Value::Boolean(b) => Ok(*b),
+        _ => Err(format!(
+            "expected boolean value, found: '{}'",
+            v.to_string_lossy()
+        )),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct BooleanMerger {
+    v: bool,
+    // `true` to keep `true` if any value is, `false` to keep it only if all values are
+    any: bool,
+}
+
+impl BooleanMerger {
+    const fn new(v: bool, any: bool) -> Self {
+        Self { v, any }
+    }
+
+    fn combine(&mut self, v: bool) {
+        if self.any {
+            self.v |= v;
+        } else {
+            self.v &= v;
+        }
+    }
+}
+
+impl ReduceValueMerger for BooleanMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        self.combine(expect_boolean(&v)?);
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        let other = downcast::<Self>(other)?;
+        self.combine(other.v);
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        v.insert(event_path!(k.as_str()), Value::Boolean(self.v));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ToggleCountMerger {
+    first: bool,
+    last: bool,
+    toggles: i64,
+}
+
+impl ToggleCountMerger {
+    const fn new(v: bool) -> Self {
+        Self {
+            first: v,
+            last: v,
+            toggles: 0,
+        }
+    }
+}
+
+impl ReduceValueMerger for ToggleCountMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        let v = expect_boolean(&v)?;
+        if v != self.last {
+            self.toggles += 1;
+        }
+        self.last = v;
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        let other = downcast::<Self>(other)?;
+        // the other group's values follow this group's
+        if other.first != self.last {
+            self.toggles += 1;
+        }
+        self.toggles += other.toggles;
+        self.last = other.last;
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        v.insert(event_path!(k.as_str()), Value::Integer(self.toggles));
+        Ok(())
+    }
+}
+
+/// Keeps the greatest or least numeric value, along with the context fields of the event holding
+/// it when `context_fields` is set.
+#[derive(Debug, Clone)]
+struct ExtremumMerger {
+    v: NumberMergerValue,
+    keep: TieBreak,
+    // `Ordering::Greater` to keep the maximum, `Ordering::Less` to keep the minimum.
+    preferred: Ordering,
+    // The context fields of the event holding the kept value, when values are derived with them.
+    context: Option<ObjectMap>,
+}
+
+impl ExtremumMerger {
+    fn new(
+        v: Value,
+        keep: TieBreak,
+        preferred: Ordering,
+        with_context: bool,
+    ) -> Result<Self, String> {
+        let (v, context) = Self::with_context(v, with_context)?;
+        let v = match v {
+            Value::Integer(i) => i.into(),
+            Value::Float(f) => f.into(),
+            v => {
+                return Err(format!(
+                    "expected number value, found: '{}'",
+                    v.to_string_lossy()
+                ))
+            }
+        };
+        Ok(Self {
+            v,
+            keep,
+            preferred,
+            context,
+        })
+    }
+
+    fn with_context(v: Value, with_context: bool) -> Result<(Value, Option<ObjectMap>), String> {
+        if !with_context {
+            return Ok((v, None));
+        }
+        match v {
+            Value::Array(pair) => match <[Value; 2]>::try_from(pair) {
+                Ok([v, Value::Object(context)]) => Ok((v, Some(context))),
+                _ => Err("expected a value and its context".to_string()),
+            },
+            v => Err(format!(
+                "expected a value with its context, found: '{}'",
+                v.to_string_lossy()
+            )),
+        }
+    }
+}
+
+impl ReduceValueMerger for ExtremumMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        let (v, context) = Self::with_context(v, self.context.is_some())?;
+        // Try and keep max precision with integer values, but once we've
+        // received a float downgrade to float precision.
+        let (ordering, v) = match (v, &self.v) {
+            (Value::Integer(i), NumberMergerValue::Int(i2)) => (i.cmp(i2), i.into()),
+            (Value::Integer(i), NumberMergerValue::Float(f2)) => {
+                let f = NotNan::new(i as f64).unwrap();
+                (f.cmp(f2), f.into())
+            }
+            (Value::Float(f), v2) => {
+                let f2 = match v2 {
+                    NumberMergerValue::Int(i2) => NotNan::new(*i2 as f64).unwrap(),
+                    NumberMergerValue::Float(f2) => *f2,
+                };
+                (f.cmp(&f2), f.into())
+            }
+            (v, _) => {
+                return Err(format!(
+                    "expected numeric value, found: '{}'",
+                    v.to_string_lossy()
+                ));
+            }
+        };
+        if self.keep.replaces(ordering, self.preferred) {
+            self.v = v;
+            if context.is_some() {
+                self.context = context;
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        match self.v {
+            NumberMergerValue::Float(f) => v.insert(event_path!(k.as_str()), Value::Float(f)),
+            NumberMergerValue::Int(i) => v.insert(event_path!(k.as_str()), Value::Integer(i)),
+        };
+        for (field, value) in self.context.into_iter().flatten() {
+            v.insert(event_path!(field.as_str()), value);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EmaMerger {
+    v: f64,
+    alpha: f64,
+}
+
+impl EmaMerger {
+    const fn new(v: f64, alpha: f64) -> Self {
+        Self { v, alpha }
+    }
+}
+
+impl ReduceValueMerger for EmaMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        let v = match v {
+            Value::Integer(i) => i as f64,
+            Value::Float(f) => f.into_inner(),
+            _ => {
+                return Err(format!(
+                    "expected numeric value, found: '{}'",
+                    v.to_string_lossy()
+                ));
+            }
+        };
+        self.v = self.alpha * v + (1.0 - self.alpha) * self.v;
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        let ema = NotNan::new(self.v).map_err(|_| "moving average is not a number".to_string())?;
+        v.insert(event_path!(k.as_str()), Value::Float(ema));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AvgMerger {
+    // the sum of the values and of their weights, each weight discounted by `decay` for every
+    // value received after it
+    weighted_sum: f64,
+    weight: f64,
+    count: u64,
+    decay: f64,
+}
+
+impl AvgMerger {
+    const fn new(v: f64, decay: f64) -> Self {
+        Self {
+            weighted_sum: v,
+            weight: 1.0,
+            count: 1,
+            decay,
+        }
+    }
+}
+
+impl ReduceValueMerger for AvgMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        let v = match v {
+            Value::Integer(i) => i as f64,
+            Value::Float(f) => f.into_inner(),
+            _ => {
+                return Err(format!(
+                    "expected numeric value, found: '{}'",
+                    v.to_string_lossy()
+                ));
+            }
+        };
+        self.weighted_sum = self.weighted_sum * self.decay + v;
+        self.weight = self.weight * self.decay + 1.0;
+        self.count += 1;
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        let other = downcast::<Self>(other)?;
+        // the values of the other group are taken as received after those of this group
+        let discount = self.decay.powf(other.count as f64);
+        self.weighted_sum = self.weighted_sum * discount + other.weighted_sum;
+        self.weight = self.weight * discount + other.weight;
+        self.count += other.count;
+        Ok(())
+    }
+
+    fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
+        let avg = NotNan::new(self.weighted_sum / self.weight)
+            .map_err(|_| "average is not a number".to_string())?;
+        v.insert(event_path!(k.as_str()), Value::Float(avg));
+        Ok(())
+    }
+}
+
+/// The minimum number of values before the last one needed to flag an anomaly.
+const ZSCORE_MIN_SAMPLES: u64 = 3;
+
+#[derive(Debug, Clone)]
+struct ZscoreFlagMerger {
+    sigma: f64,
+    last: NumberMergerValue,
+    // Running count, mean, and sum of squared differences from the mean of the values before
+    // `last`, using Welford's algorithm.
+    count: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl ZscoreFlagMerger {
+    const fn new(v: NumberMergerValue, sigma: f64) -> Self {
+        Self {
+            sigma,
+            last: v,
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    fn is_anomaly(&self) -> bool {
+        if self.count < ZSCORE_MIN_SAMPLES {
+            return false;
+        }
+        let stddev = (self.m2 / self.count as f64).sqrt();
+        (f64::from(&self.last) - self.mean).abs() > self.sigma * stddev
+    }
+}
+
+impl ReduceValueMerger for ZscoreFlagMerger {
+    fn add(&mut self, v: Value) -> Result<(), String> {
+        let v = match v {
+            Value::Integer(i) => NumberMergerValue::Int(i),
+            Value::Float(f) => NumberMergerValue::Float(f),
+            _ => {
+                return Err(format!(
+                    "expected numeric value, found: '{}'",
+                    v.to_string_lossy()
+                ));
+            }
+        };
+        let previous = f64::from(&std::mem::replace(&mut self.last, v));
+        self.count += 1;
+        let delta = previous - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (previous - self.mean);
         Ok(())
     }
 
     fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String> {
-        match self.v {
-            NumberMergerValue::Float(f) => v.insert(event_path!(k.as_str()), Value::Float(f)),
-            NumberMergerValue::Int(i) => v.insert(event_path!(k.as_str()), Value::Integer(i)),
-        };
+        v.insert(
+            event_path!(format!("{}_anomaly", k).as_str()),
+            Value::Boolean(self.is_anomaly()),
+        );
+        v.insert(event_path!(k.as_str()), Value::from(self.last));
         Ok(())
     }
 }
 
-pub trait ReduceValueMerger: std::fmt::Debug + Send + Sync {
+pub trait ReduceValueMerger: std::fmt::Debug + Send + Sync + CloneMerger + AnyMerger {
     fn add(&mut self, v: Value) -> Result<(), String>;
     fn insert_into(self: Box<Self>, k: KeyString, v: &mut LogEvent) -> Result<(), String>;
+
+    /// Whether the merged value is full, in which case its group is flushed.
+    fn is_full(&self) -> bool {
+        false
+    }
+
+    /// Merges the merger of the same field from another group, when the two groups are
+    /// consolidated.
+    ///
+    /// By default, the merged value of the other group is added as the value of one more event.
+    fn merge(&mut self, other: Box<dyn ReduceValueMerger>) -> Result<(), String> {
+        let mut merged = LogEvent::default();
+        other.insert_into("merged".into(), &mut merged)?;
+        match merged.remove(event_path!("merged")) {
+            Some(v) => self.add(v),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Allows a merger behind a `Box<dyn ReduceValueMerger>` to be converted back to its own type,
+/// so that two groups can be merged field by field.
+pub trait AnyMerger {
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T> AnyMerger for T
+where
+    T: ReduceValueMerger + 'static,
+{
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// Returns the merger of another group as the type of the merger it is merged into.
+///
+/// Both are of the same type when the field has a merge strategy, but fields merged by default
+/// get a merger for the type of their first value, which may differ between groups.
+fn downcast<T: 'static>(other: Box<dyn ReduceValueMerger>) -> Result<Box<T>, String> {
+    other
+        .into_any()
+        .downcast()
+        .map_err(|_| "cannot merge values of different types".to_string())
+}
+
+/// Allows a merger to be cloned behind a `Box<dyn ReduceValueMerger>`, so the current value of a
+/// group can be taken without consuming it.
+pub trait CloneMerger {
+    fn clone_merger(&self) -> Box<dyn ReduceValueMerger>;
+}
+
+impl<T> CloneMerger for T
+where
+    T: ReduceValueMerger + Clone + 'static,
+{
+    fn clone_merger(&self) -> Box<dyn ReduceValueMerger> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn ReduceValueMerger> {
+    fn clone(&self) -> Self {
+        (**self).clone_merger()
+    }
 }
 
 impl From<Value> for Box<dyn ReduceValueMerger> {
@@ -531,7 +1983,9 @@ impl From<Value> for Box<dyn ReduceValueMerger> {
         match v {
             Value::Integer(i) => Box::new(AddNumbersMerger::new(i.into())),
             Value::Float(f) => Box::new(AddNumbersMerger::new(f.into())),
-            Value::Timestamp(ts) => Box::new(TimestampWindowMerger::new(ts)),
+            Value::Timestamp(ts) => {
+                Box::new(TimestampWindowMerger::new(ts, TimestampStrategy::default()))
+            }
             Value::Object(_) => Box::new(DiscardMerger::new(v)),
             Value::Null => Box::new(DiscardMerger::new(v)),
             Value::Boolean(_) => Box::new(DiscardMerger::new(v)),
@@ -542,35 +1996,69 @@ impl From<Value> for Box<dyn ReduceValueMerger> {
     }
 }
 
+/// Returns the merger for a field without a merge strategy, merging timestamps with the given
+/// strategy.
+pub(crate) fn get_default_merger(
+    v: Value,
+    timestamp_strategy: TimestampStrategy,
+) -> Box<dyn ReduceValueMerger> {
+    match v {
+        Value::Timestamp(ts) => Box::new(TimestampWindowMerger::new(ts, timestamp_strategy)),
+        v => v.into(),
+    }
+}
+
 pub(crate) fn get_value_merger(
     v: Value,
     m: &MergeStrategy,
 ) -> Result<Box<dyn ReduceValueMerger>, String> {
     match m {
-        MergeStrategy::Sum => match v {
-            Value::Integer(i) => Ok(Box::new(AddNumbersMerger::new(i.into()))),
-            Value::Float(f) => Ok(Box::new(AddNumbersMerger::new(f.into()))),
-            _ => Err(format!(
-                "expected number value, found: '{}'",
-                v.to_string_lossy()
+        MergeStrategy::Sum {
+            keep_last_as,
+            unit_field: Some(unit_field),
+        } => Ok(Box::new(UnitSumMerger::new(
+            v,
+            keep_last_as.as_ref(),
+            unit_field,
+        )?)),
+        MergeStrategy::Sum {
+            keep_last_as,
+            unit_field: None,
+        } => match v {
+            Value::Integer(i) => Ok(Box::new(
+                AddNumbersMerger::new(i.into()).keep_last_as(keep_last_as.as_ref()),
             )),
-        },
-        MergeStrategy::Max => match v {
-            Value::Integer(i) => Ok(Box::new(MaxNumberMerger::new(i.into()))),
-            Value::Float(f) => Ok(Box::new(MaxNumberMerger::new(f.into()))),
-            _ => Err(format!(
-                "expected number value, found: '{}'",
-                v.to_string_lossy()
+            Value::Float(f) => Ok(Box::new(
+                AddNumbersMerger::new(f.into()).keep_last_as(keep_last_as.as_ref()),
             )),
-        },
-        MergeStrategy::Min => match v {
-            Value::Integer(i) => Ok(Box::new(MinNumberMerger::new(i.into()))),
-            Value::Float(f) => Ok(Box::new(MinNumberMerger::new(f.into()))),
             _ => Err(format!(
                 "expected number value, found: '{}'",
                 v.to_string_lossy()
             )),
         },
+        MergeStrategy::Max {
+            keep,
+            context_fields,
+        } => Ok(Box::new(ExtremumMerger::new(
+            v,
+            *keep,
+            Ordering::Greater,
+            !context_fields.is_empty(),
+        )?)),
+        MergeStrategy::Min {
+            keep,
+            context_fields,
+        } => Ok(Box::new(ExtremumMerger::new(
+            v,
+            *keep,
+            Ordering::Less,
+            !context_fields.is_empty(),
+        )?)),
+        MergeStrategy::Count => Ok(Box::new(CountMerger::new(&v))),
+        MergeStrategy::DistinctCount => Ok(Box::new(DistinctCountMerger::new(v))),
+        MergeStrategy::Any => Ok(Box::new(BooleanMerger::new(expect_boolean(&v)?, true))),
+        MergeStrategy::All => Ok(Box::new(BooleanMerger::new(expect_boolean(&v)?, false))),
+        MergeStrategy::ToggleCount => Ok(Box::new(ToggleCountMerger::new(expect_boolean(&v)?))),
         MergeStrategy::Concat => match v {
             Value::Bytes(b) => Ok(Box::new(ConcatMerger::new(b, Some(' ')))),
             Value::Array(a) => Ok(Box::new(ConcatArrayMerger::new(a))),
@@ -593,7 +2081,16 @@ pub(crate) fn get_value_merger(
                 v.to_string_lossy()
             )),
         },
-        MergeStrategy::Array => Ok(Box::new(ArrayMerger::new(v))),
+        MergeStrategy::Array {
+            sort_by,
+            max_len,
+            on_overflow,
+        } => Ok(Box::new(ArrayMerger::new(
+            v,
+            sort_by.clone(),
+            *max_len,
+            *on_overflow,
+        ))),
         MergeStrategy::ShortestArray => match v {
             Value::Array(a) => Ok(Box::new(ShortestArrayMerger::new(a))),
             _ => Err(format!(
@@ -610,38 +2107,125 @@ pub(crate) fn get_value_merger(
         },
         MergeStrategy::Discard => Ok(Box::new(DiscardMerger::new(v))),
         MergeStrategy::Retain => Ok(Box::new(RetainMerger::new(v))),
+        MergeStrategy::Coalesce => Ok(Box::new(CoalesceMerger::new(v))),
         MergeStrategy::FlatUnique => Ok(Box::new(FlatUniqueMerger::new(v))),
+        MergeStrategy::Ema { alpha } => match v {
+            Value::Integer(i) => Ok(Box::new(EmaMerger::new(i as f64, *alpha))),
+            Value::Float(f) => Ok(Box::new(EmaMerger::new(f.into_inner(), *alpha))),
+            _ => Err(format!(
+                "expected number value, found: '{}'",
+                v.to_string_lossy()
+            )),
+        },
+        MergeStrategy::Avg { decay } => match v {
+            Value::Integer(i) => Ok(Box::new(AvgMerger::new(i as f64, *decay))),
+            Value::Float(f) => Ok(Box::new(AvgMerger::new(f.into_inner(), *decay))),
+            _ => Err(format!(
+                "expected number value, found: '{}'",
+                v.to_string_lossy()
+            )),
+        },
+        MergeStrategy::ZscoreFlag { sigma } => match v {
+            Value::Integer(i) => Ok(Box::new(ZscoreFlagMerger::new(i.into(), *sigma))),
+            Value::Float(f) => Ok(Box::new(ZscoreFlagMerger::new(f.into(), *sigma))),
+            _ => Err(format!(
+                "expected number value, found: '{}'",
+                v.to_string_lossy()
+            )),
+        },
+        MergeStrategy::TopK { k } => Ok(Box::new(TopKMerger::new(v, *k))),
+        MergeStrategy::SortedUnique { max } => Ok(Box::new(SortedUniqueMerger::new(v, *max))),
+        MergeStrategy::ArrayDiff => match v {
+            Value::Array(a) => Ok(Box::new(ArrayDiffMerger::new(a))),
+            _ => Err(format!(
+                "expected array value, found: '{}'",
+                v.to_string_lossy()
+            )),
+        },
+        MergeStrategy::BoundingBox => Ok(Box::new(BoundingBoxMerger::new(v)?)),
+        MergeStrategy::GroupSum { key, value } => Ok(Box::new(GroupSumMerger::new(v, key, value)?)),
+        MergeStrategy::SumDuration { .. } => match v {
+            Value::Integer(i) => Ok(Box::new(AddNumbersMerger::new(i.into()))),
+            _ => Err(format!(
+                "expected duration value, found: '{}'",
+                v.to_string_lossy()
+            )),
+        },
+        MergeStrategy::LastByTimestamp { .. } => Ok(Box::new(LastByTimestampMerger::new(v)?)),
     }
 }
 
 #[cfg(test)]
 mod test {
     use serde_json::json;
+    use vrl::btreemap;
 
     use super::*;
     use crate::event::LogEvent;
 
+    const MAX: MergeStrategy = MergeStrategy::Max {
+        keep: TieBreak::First,
+        context_fields: Vec::new(),
+    };
+    const MIN: MergeStrategy = MergeStrategy::Min {
+        keep: TieBreak::First,
+        context_fields: Vec::new(),
+    };
+
     #[test]
     fn initial_values() {
         assert!(get_value_merger("foo".into(), &MergeStrategy::Discard).is_ok());
         assert!(get_value_merger("foo".into(), &MergeStrategy::Retain).is_ok());
-        assert!(get_value_merger("foo".into(), &MergeStrategy::Sum).is_err());
-        assert!(get_value_merger("foo".into(), &MergeStrategy::Max).is_err());
-        assert!(get_value_merger("foo".into(), &MergeStrategy::Min).is_err());
-        assert!(get_value_merger("foo".into(), &MergeStrategy::Array).is_ok());
+        assert!(get_value_merger("foo".into(), &MergeStrategy::Coalesce).is_ok());
+        assert!(get_value_merger(
+            "foo".into(),
+            &MergeStrategy::Sum {
+                keep_last_as: None,
+                unit_field: None,
+            }
+        )
+        .is_err());
+        assert!(get_value_merger("foo".into(), &MAX).is_err());
+        assert!(get_value_merger("foo".into(), &MIN).is_err());
+        assert!(get_value_merger(
+            "foo".into(),
+            &MergeStrategy::Array {
+                sort_by: None,
+                max_len: None,
+                on_overflow: ArrayOverflow::DropNew,
+            }
+        )
+        .is_ok());
         assert!(get_value_merger("foo".into(), &MergeStrategy::LongestArray).is_err());
         assert!(get_value_merger("foo".into(), &MergeStrategy::ShortestArray).is_err());
         assert!(get_value_merger("foo".into(), &MergeStrategy::Concat).is_ok());
         assert!(get_value_merger("foo".into(), &MergeStrategy::ConcatNewline).is_ok());
         assert!(get_value_merger("foo".into(), &MergeStrategy::ConcatRaw).is_ok());
         assert!(get_value_merger("foo".into(), &MergeStrategy::FlatUnique).is_ok());
+        assert!(get_value_merger("foo".into(), &MergeStrategy::Count).is_ok());
+        assert!(get_value_merger("foo".into(), &MergeStrategy::DistinctCount).is_ok());
 
         assert!(get_value_merger(42.into(), &MergeStrategy::Discard).is_ok());
         assert!(get_value_merger(42.into(), &MergeStrategy::Retain).is_ok());
-        assert!(get_value_merger(42.into(), &MergeStrategy::Sum).is_ok());
-        assert!(get_value_merger(42.into(), &MergeStrategy::Min).is_ok());
-        assert!(get_value_merger(42.into(), &MergeStrategy::Max).is_ok());
-        assert!(get_value_merger(42.into(), &MergeStrategy::Array).is_ok());
+        assert!(get_value_merger(
+            42.into(),
+            &MergeStrategy::Sum {
+                keep_last_as: None,
+                unit_field: None,
+            }
+        )
+        .is_ok());
+        assert!(get_value_merger(42.into(), &MIN).is_ok());
+        assert!(get_value_merger(42.into(), &MAX).is_ok());
+        assert!(get_value_merger(
+            42.into(),
+            &MergeStrategy::Array {
+                sort_by: None,
+                max_len: None,
+                on_overflow: ArrayOverflow::DropNew,
+            }
+        )
+        .is_ok());
         assert!(get_value_merger(42.into(), &MergeStrategy::LongestArray).is_err());
         assert!(get_value_merger(42.into(), &MergeStrategy::ShortestArray).is_err());
         assert!(get_value_merger(42.into(), &MergeStrategy::Concat).is_err());
@@ -651,10 +2235,25 @@ mod test {
 
         assert!(get_value_merger(42.into(), &MergeStrategy::Discard).is_ok());
         assert!(get_value_merger(42.into(), &MergeStrategy::Retain).is_ok());
-        assert!(get_value_merger(4.2.into(), &MergeStrategy::Sum).is_ok());
-        assert!(get_value_merger(4.2.into(), &MergeStrategy::Min).is_ok());
-        assert!(get_value_merger(4.2.into(), &MergeStrategy::Max).is_ok());
-        assert!(get_value_merger(4.2.into(), &MergeStrategy::Array).is_ok());
+        assert!(get_value_merger(
+            4.2.into(),
+            &MergeStrategy::Sum {
+                keep_last_as: None,
+                unit_field: None,
+            }
+        )
+        .is_ok());
+        assert!(get_value_merger(4.2.into(), &MIN).is_ok());
+        assert!(get_value_merger(4.2.into(), &MAX).is_ok());
+        assert!(get_value_merger(
+            4.2.into(),
+            &MergeStrategy::Array {
+                sort_by: None,
+                max_len: None,
+                on_overflow: ArrayOverflow::DropNew,
+            }
+        )
+        .is_ok());
         assert!(get_value_merger(4.2.into(), &MergeStrategy::LongestArray).is_err());
         assert!(get_value_merger(4.2.into(), &MergeStrategy::ShortestArray).is_err());
         assert!(get_value_merger(4.2.into(), &MergeStrategy::Concat).is_err());
@@ -664,10 +2263,25 @@ mod test {
 
         assert!(get_value_merger(true.into(), &MergeStrategy::Discard).is_ok());
         assert!(get_value_merger(true.into(), &MergeStrategy::Retain).is_ok());
-        assert!(get_value_merger(true.into(), &MergeStrategy::Sum).is_err());
-        assert!(get_value_merger(true.into(), &MergeStrategy::Max).is_err());
-        assert!(get_value_merger(true.into(), &MergeStrategy::Min).is_err());
-        assert!(get_value_merger(true.into(), &MergeStrategy::Array).is_ok());
+        assert!(get_value_merger(
+            true.into(),
+            &MergeStrategy::Sum {
+                keep_last_as: None,
+                unit_field: None,
+            }
+        )
+        .is_err());
+        assert!(get_value_merger(true.into(), &MAX).is_err());
+        assert!(get_value_merger(true.into(), &MIN).is_err());
+        assert!(get_value_merger(
+            true.into(),
+            &MergeStrategy::Array {
+                sort_by: None,
+                max_len: None,
+                on_overflow: ArrayOverflow::DropNew,
+            }
+        )
+        .is_ok());
         assert!(get_value_merger(true.into(), &MergeStrategy::LongestArray).is_err());
         assert!(get_value_merger(true.into(), &MergeStrategy::ShortestArray).is_err());
         assert!(get_value_merger(true.into(), &MergeStrategy::Concat).is_err());
@@ -677,10 +2291,25 @@ mod test {
 
         assert!(get_value_merger(Utc::now().into(), &MergeStrategy::Discard).is_ok());
         assert!(get_value_merger(Utc::now().into(), &MergeStrategy::Retain).is_ok());
-        assert!(get_value_merger(Utc::now().into(), &MergeStrategy::Sum).is_err());
-        assert!(get_value_merger(Utc::now().into(), &MergeStrategy::Max).is_err());
-        assert!(get_value_merger(Utc::now().into(), &MergeStrategy::Min).is_err());
-        assert!(get_value_merger(Utc::now().into(), &MergeStrategy::Array).is_ok());
+        assert!(get_value_merger(
+            Utc::now().into(),
+            &MergeStrategy::Sum {
+                keep_last_as: None,
+                unit_field: None,
+            }
+        )
+        .is_err());
+        assert!(get_value_merger(Utc::now().into(), &MAX).is_err());
+        assert!(get_value_merger(Utc::now().into(), &MIN).is_err());
+        assert!(get_value_merger(
+            Utc::now().into(),
+            &MergeStrategy::Array {
+                sort_by: None,
+                max_len: None,
+                on_overflow: ArrayOverflow::DropNew,
+            }
+        )
+        .is_ok());
         assert!(get_value_merger(Utc::now().into(), &MergeStrategy::LongestArray).is_err());
         assert!(get_value_merger(Utc::now().into(), &MergeStrategy::ShortestArray).is_err());
         assert!(get_value_merger(Utc::now().into(), &MergeStrategy::Concat).is_err());
@@ -691,10 +2320,25 @@ mod test {
 
         assert!(get_value_merger(json!([]).into(), &MergeStrategy::Discard).is_ok());
         assert!(get_value_merger(json!([]).into(), &MergeStrategy::Retain).is_ok());
-        assert!(get_value_merger(json!([]).into(), &MergeStrategy::Sum).is_err());
-        assert!(get_value_merger(json!([]).into(), &MergeStrategy::Max).is_err());
-        assert!(get_value_merger(json!([]).into(), &MergeStrategy::Min).is_err());
-        assert!(get_value_merger(json!([]).into(), &MergeStrategy::Array).is_ok());
+        assert!(get_value_merger(
+            json!([]).into(),
+            &MergeStrategy::Sum {
+                keep_last_as: None,
+                unit_field: None,
+            }
+        )
+        .is_err());
+        assert!(get_value_merger(json!([]).into(), &MAX).is_err());
+        assert!(get_value_merger(json!([]).into(), &MIN).is_err());
+        assert!(get_value_merger(
+            json!([]).into(),
+            &MergeStrategy::Array {
+                sort_by: None,
+                max_len: None,
+                on_overflow: ArrayOverflow::DropNew,
+            }
+        )
+        .is_ok());
         assert!(get_value_merger(json!([]).into(), &MergeStrategy::LongestArray).is_ok());
         assert!(get_value_merger(json!([]).into(), &MergeStrategy::ShortestArray).is_ok());
         assert!(get_value_merger(json!([]).into(), &MergeStrategy::Concat).is_ok());
@@ -704,10 +2348,25 @@ mod test {
 
         assert!(get_value_merger(json!({}).into(), &MergeStrategy::Discard).is_ok());
         assert!(get_value_merger(json!({}).into(), &MergeStrategy::Retain).is_ok());
-        assert!(get_value_merger(json!({}).into(), &MergeStrategy::Sum).is_err());
-        assert!(get_value_merger(json!({}).into(), &MergeStrategy::Max).is_err());
-        assert!(get_value_merger(json!({}).into(), &MergeStrategy::Min).is_err());
-        assert!(get_value_merger(json!({}).into(), &MergeStrategy::Array).is_ok());
+        assert!(get_value_merger(
+            json!({}).into(),
+            &MergeStrategy::Sum {
+                keep_last_as: None,
+                unit_field: None,
+            }
+        )
+        .is_err());
+        assert!(get_value_merger(json!({}).into(), &MAX).is_err());
+        assert!(get_value_merger(json!({}).into(), &MIN).is_err());
+        assert!(get_value_merger(
+            json!({}).into(),
+            &MergeStrategy::Array {
+                sort_by: None,
+                max_len: None,
+                on_overflow: ArrayOverflow::DropNew,
+            }
+        )
+        .is_ok());
         assert!(get_value_merger(json!({}).into(), &MergeStrategy::LongestArray).is_err());
         assert!(get_value_merger(json!({}).into(), &MergeStrategy::ShortestArray).is_err());
         assert!(get_value_merger(json!({}).into(), &MergeStrategy::Concat).is_err());
@@ -717,10 +2376,25 @@ mod test {
 
         assert!(get_value_merger(json!(null).into(), &MergeStrategy::Discard).is_ok());
         assert!(get_value_merger(json!(null).into(), &MergeStrategy::Retain).is_ok());
-        assert!(get_value_merger(json!(null).into(), &MergeStrategy::Sum).is_err());
-        assert!(get_value_merger(json!(null).into(), &MergeStrategy::Max).is_err());
-        assert!(get_value_merger(json!(null).into(), &MergeStrategy::Min).is_err());
-        assert!(get_value_merger(json!(null).into(), &MergeStrategy::Array).is_ok());
+        assert!(get_value_merger(
+            json!(null).into(),
+            &MergeStrategy::Sum {
+                keep_last_as: None,
+                unit_field: None,
+            }
+        )
+        .is_err());
+        assert!(get_value_merger(json!(null).into(), &MAX).is_err());
+        assert!(get_value_merger(json!(null).into(), &MIN).is_err());
+        assert!(get_value_merger(
+            json!(null).into(),
+            &MergeStrategy::Array {
+                sort_by: None,
+                max_len: None,
+                on_overflow: ArrayOverflow::DropNew,
+            }
+        )
+        .is_ok());
         assert!(get_value_merger(json!(null).into(), &MergeStrategy::LongestArray).is_err());
         assert!(get_value_merger(json!(null).into(), &MergeStrategy::ShortestArray).is_err());
         assert!(get_value_merger(json!(null).into(), &MergeStrategy::Concat).is_err());
@@ -740,7 +2414,15 @@ mod test {
             Ok("bar".into())
         );
         assert_eq!(
-            merge("foo".into(), "bar".into(), &MergeStrategy::Array),
+            merge(
+                "foo".into(),
+                "bar".into(),
+                &MergeStrategy::Array {
+                    sort_by: None,
+                    max_len: None,
+                    on_overflow: ArrayOverflow::DropNew,
+                }
+            ),
             Ok(json!(["foo", "bar"]).into())
         );
         assert_eq!(
@@ -769,46 +2451,36 @@ mod test {
         );
 
         assert_eq!(
-            merge(21.into(), 21.into(), &MergeStrategy::Sum),
-            Ok(42.into())
-        );
-        assert_eq!(
-            merge(41.into(), 42.into(), &MergeStrategy::Max),
-            Ok(42.into())
-        );
-        assert_eq!(
-            merge(42.into(), 41.into(), &MergeStrategy::Max),
-            Ok(42.into())
-        );
-        assert_eq!(
-            merge(42.into(), 43.into(), &MergeStrategy::Min),
-            Ok(42.into())
-        );
-        assert_eq!(
-            merge(43.into(), 42.into(), &MergeStrategy::Min),
+            merge(
+                21.into(),
+                21.into(),
+                &MergeStrategy::Sum {
+                    keep_last_as: None,
+                    unit_field: None,
+                }
+            ),
             Ok(42.into())
         );
+        assert_eq!(merge(41.into(), 42.into(), &MAX), Ok(42.into()));
+        assert_eq!(merge(42.into(), 41.into(), &MAX), Ok(42.into()));
+        assert_eq!(merge(42.into(), 43.into(), &MIN), Ok(42.into()));
+        assert_eq!(merge(43.into(), 42.into(), &MIN), Ok(42.into()));
 
         assert_eq!(
-            merge(2.1.into(), 2.1.into(), &MergeStrategy::Sum),
-            Ok(4.2.into())
-        );
-        assert_eq!(
-            merge(4.1.into(), 4.2.into(), &MergeStrategy::Max),
-            Ok(4.2.into())
-        );
-        assert_eq!(
-            merge(4.2.into(), 4.1.into(), &MergeStrategy::Max),
-            Ok(4.2.into())
-        );
-        assert_eq!(
-            merge(4.2.into(), 4.3.into(), &MergeStrategy::Min),
-            Ok(4.2.into())
-        );
-        assert_eq!(
-            merge(4.3.into(), 4.2.into(), &MergeStrategy::Min),
+            merge(
+                2.1.into(),
+                2.1.into(),
+                &MergeStrategy::Sum {
+                    keep_last_as: None,
+                    unit_field: None,
+                }
+            ),
             Ok(4.2.into())
         );
+        assert_eq!(merge(4.1.into(), 4.2.into(), &MAX), Ok(4.2.into()));
+        assert_eq!(merge(4.2.into(), 4.1.into(), &MAX), Ok(4.2.into()));
+        assert_eq!(merge(4.2.into(), 4.3.into(), &MIN), Ok(4.2.into()));
+        assert_eq!(merge(4.3.into(), 4.2.into(), &MIN), Ok(4.2.into()));
 
         assert_eq!(
             merge(
@@ -876,6 +2548,454 @@ mod test {
         }
     }
 
+    #[test]
+    fn max_min_tie_break() {
+        let with_host = |v: i64, host: &str| {
+            Value::Array(vec![v.into(), Value::Object(btreemap! { "host" => host })])
+        };
+        let merge_with_context = |strategy: &MergeStrategy, values: [Value; 3]| {
+            let [first, rest @ ..] = values;
+            let mut merger = get_value_merger(first, strategy).unwrap();
+            for v in rest {
+                merger.add(v).unwrap();
+            }
+            let mut output = LogEvent::default();
+            merger.insert_into("out".into(), &mut output).unwrap();
+            (output["out"].clone(), output["host"].clone())
+        };
+
+        for (keep, host) in [(TieBreak::First, "b"), (TieBreak::Last, "c")] {
+            let strategy = MergeStrategy::Max {
+                keep,
+                context_fields: vec!["host".to_owned()],
+            };
+            assert_eq!(
+                merge_with_context(
+                    &strategy,
+                    [with_host(1, "a"), with_host(5, "b"), with_host(5, "c")]
+                ),
+                (5.into(), host.into())
+            );
+
+            let strategy = MergeStrategy::Min {
+                keep,
+                context_fields: vec!["host".to_owned()],
+            };
+            assert_eq!(
+                merge_with_context(
+                    &strategy,
+                    [with_host(9, "a"), with_host(5, "b"), with_host(5, "c")]
+                ),
+                (5.into(), host.into())
+            );
+        }
+
+        let strategy: MergeStrategy = serde_json::from_value(
+            json!({ "strategy": "max", "keep": "last", "context_fields": ["host"] }),
+        )
+        .unwrap();
+        assert!(matches!(
+            strategy,
+            MergeStrategy::Max {
+                keep: TieBreak::Last,
+                ref context_fields,
+            } if context_fields == &["host"]
+        ));
+    }
+
+    #[test]
+    fn exponential_moving_average() {
+        let strategy = MergeStrategy::Ema { alpha: 0.5 };
+        let mut merger = get_value_merger(10.into(), &strategy).unwrap();
+        merger.add(20.into()).unwrap();
+        merger.add(30.0.into()).unwrap();
+        assert!(merger.add("foo".into()).is_err());
+
+        let mut output = LogEvent::default();
+        merger.insert_into("out".into(), &mut output).unwrap();
+        assert_eq!(output.remove("out").unwrap(), 22.5.into());
+
+        assert!(get_value_merger("foo".into(), &strategy).is_err());
+    }
+
+    #[test]
+    fn ema_alpha_validation() {
+        assert!(MergeStrategy::Ema { alpha: 0.3 }.validate().is_ok());
+        assert!(MergeStrategy::Ema { alpha: 1.0 }.validate().is_ok());
+        assert!(MergeStrategy::Ema { alpha: 0.0 }.validate().is_err());
+        assert!(MergeStrategy::Ema { alpha: 1.5 }.validate().is_err());
+    }
+
+    #[test]
+    fn average() {
+        let mut merger = get_value_merger(10.into(), &MergeStrategy::Avg { decay: 1.0 }).unwrap();
+        merger.add(20.into()).unwrap();
+        merger.add(30.0.into()).unwrap();
+        assert!(merger.add("foo".into()).is_err());
+
+        let mut output = LogEvent::default();
+        merger.insert_into("out".into(), &mut output).unwrap();
+        assert_eq!(output.remove("out").unwrap(), 20.0.into());
+    }
+
+    #[test]
+    fn recency_weighted_average() {
+        let strategy = MergeStrategy::Avg { decay: 0.5 };
+        let mut merger = get_value_merger(10.into(), &strategy).unwrap();
+        merger.add(20.into()).unwrap();
+        merger.add(40.into()).unwrap();
+
+        // (10 * 0.25 + 20 * 0.5 + 40) / (0.25 + 0.5 + 1)
+        let mut output = LogEvent::default();
+        merger.insert_into("out".into(), &mut output).unwrap();
+        assert_eq!(output.remove("out").unwrap(), 30.0.into());
+
+        assert!(get_value_merger("foo".into(), &strategy).is_err());
+    }
+
+    #[test]
+    fn avg_decay_validation() {
+        assert!(MergeStrategy::Avg { decay: 0.9 }.validate().is_ok());
+        assert!(MergeStrategy::Avg { decay: 1.0 }.validate().is_ok());
+        assert!(MergeStrategy::Avg { decay: 0.0 }.validate().is_err());
+        assert!(MergeStrategy::Avg { decay: 1.5 }.validate().is_err());
+    }
+
+    #[test]
+    fn sum_keep_last_as() {
+        let strategy = MergeStrategy::Sum {
+            keep_last_as: Some("value_last".to_owned()),
+            unit_field: None,
+        };
+        let mut merger = get_value_merger(21.into(), &strategy).unwrap();
+        merger.add(4.into()).unwrap();
+        merger.add(2.into()).unwrap();
+
+        let mut output = LogEvent::default();
+        merger.insert_into("value".into(), &mut output).unwrap();
+        assert_eq!(output["value"], 27.into());
+        assert_eq!(output["value_last"], 2.into());
+    }
+
+    #[test]
+    fn sum_unit_field() {
+        let strategy = MergeStrategy::Sum {
+            keep_last_as: None,
+            unit_field: Some("unit".to_owned()),
+        };
+        let mut merger = get_value_merger(json!([2, "MB"]).into(), &strategy).unwrap();
+        merger.add(json!([3, "MB"]).into()).unwrap();
+
+        let error = merger.add(json!([5, "GB"]).into()).unwrap_err();
+        assert_eq!(
+            error,
+            "mismatched units, expected 'MB' but found 'GB'; value not summed"
+        );
+        assert!(merger.add(json!([7, null]).into()).is_err());
+
+        let mut output = LogEvent::default();
+        merger.insert_into("bytes".into(), &mut output).unwrap();
+        assert_eq!(output["bytes"], 5.into());
+        assert_eq!(output["unit"], "MB".into());
+    }
+
+    #[test]
+    fn top_k() {
+        let strategy = MergeStrategy::TopK { k: 2 };
+        let mut merger = get_value_merger("a".into(), &strategy).unwrap();
+        for value in ["b", "c", "b", "c", "a", "b", "d"] {
+            merger.add(value.into()).unwrap();
+        }
+
+        let mut output = LogEvent::default();
+        merger.insert_into("out".into(), &mut output).unwrap();
+        assert_eq!(
+            output["out"],
+            json!([{ "value": "b", "count": 3 }, { "value": "a", "count": 2 }]).into()
+        );
+    }
+
+    #[test]
+    fn merging_groups() {
+        let merged = |strategy: &MergeStrategy, first: [Value; 2], second: [Value; 2]| {
+            let [initial, additional] = first;
+            let mut merger = get_value_merger(initial, strategy).unwrap();
+            merger.add(additional).unwrap();
+            let [initial, additional] = second;
+            let mut other = get_value_merger(initial, strategy).unwrap();
+            other.add(additional).unwrap();
+
+            merger.merge(other).unwrap();
+            let mut output = LogEvent::default();
+            merger.insert_into("out".into(), &mut output).unwrap();
+            output
+        };
+
+        let output = merged(
+            &MergeStrategy::Count,
+            ["a".into(), "b".into()],
+            ["c".into(), "d".into()],
+        );
+        assert_eq!(output["out"], 4.into());
+
+        let output = merged(
+            &MergeStrategy::Sum {
+                keep_last_as: None,
+                unit_field: None,
+            },
+            [1.into(), 2.into()],
+            [3.into(), 4.into()],
+        );
+        assert_eq!(output["out"], 10.into());
+
+        let array = MergeStrategy::Array {
+            sort_by: None,
+            max_len: None,
+            on_overflow: ArrayOverflow::DropNew,
+        };
+        let output = merged(&array, [1.into(), 2.into()], [3.into(), 4.into()]);
+        assert_eq!(output["out"], json!([1, 2, 3, 4]).into());
+
+        let output = merged(
+            &MergeStrategy::DistinctCount,
+            ["a".into(), "b".into()],
+            ["b".into(), "c".into()],
+        );
+        assert_eq!(output["out"], 4.into());
+        assert_eq!(output["out_distinct_count"], 3.into());
+
+        let output = merged(
+            &MergeStrategy::TopK { k: 1 },
+            ["a".into(), "b".into()],
+            ["b".into(), "c".into()],
+        );
+        assert_eq!(output["out"], json!([{ "value": "b", "count": 2 }]).into());
+
+        let output = merged(&MAX, [1.into(), 5.into()], [3.into(), 4.into()]);
+        assert_eq!(output["out"], 5.into());
+
+        let output = merged(
+            &MergeStrategy::Discard,
+            ["a".into(), "b".into()],
+            ["c".into(), "d".into()],
+        );
+        assert_eq!(output["out"], "a".into());
+    }
+
+    #[test]
+    fn coalesce() {
+        let mut merger = get_value_merger(Value::Null, &MergeStrategy::Coalesce).unwrap();
+        for value in ["", "real", "later"] {
+            merger.add(value.into()).unwrap();
+        }
+
+        let mut output = LogEvent::default();
+        merger.insert_into("out".into(), &mut output).unwrap();
+        assert_eq!(output["out"], "real".into());
+    }
+
+    #[test]
+    fn timestamp_strategies() {
+        let start = Utc::now();
+        let end = start + chrono::Duration::seconds(5);
+        let merge = |strategy| {
+            let mut merger = get_default_merger(start.into(), strategy);
+            merger.add(end.into()).unwrap();
+            let mut output = LogEvent::default();
+            merger.insert_into("ts".into(), &mut output).unwrap();
+            output
+        };
+
+        let output = merge(TimestampStrategy::KeepFirstWithEnd);
+        assert_eq!(output["ts"], start.into());
+        assert_eq!(output["ts_end"], end.into());
+
+        let output = merge(TimestampStrategy::KeepFirst);
+        assert_eq!(output["ts"], start.into());
+        assert!(!output.contains("ts_end"));
+
+        let output = merge(TimestampStrategy::KeepLast);
+        assert_eq!(output["ts"], end.into());
+        assert!(!output.contains("ts_end"));
+
+        let output = merge(TimestampStrategy::Range);
+        assert_eq!(output["ts.start"], start.into());
+        assert_eq!(output["ts.end"], end.into());
+        assert!(!output.contains("ts_end"));
+    }
+
+    #[test]
+    fn sorted_unique() {
+        let strategy = MergeStrategy::SortedUnique { max: 4 };
+        let mut merger = get_value_merger(7.into(), &strategy).unwrap();
+        for value in [3, 9, 3, 1, 7, 12, 5, 9, 0] {
+            merger.add(value.into()).unwrap();
+        }
+        merger.add(Value::Null).unwrap();
+        merger.add(json!([4, 1]).into()).unwrap();
+
+        let mut output = LogEvent::default();
+        merger.insert_into("out".into(), &mut output).unwrap();
+        assert_eq!(output["out"], json!([0, 1, 3, 4]).into());
+    }
+
+    #[test]
+    fn array_diff() {
+        let mut merger =
+            get_value_merger(json!([1, 2, 3]).into(), &MergeStrategy::ArrayDiff).unwrap();
+        merger.add(json!([2]).into()).unwrap();
+        // Elements that are not present are ignored.
+        merger.add(json!([4]).into()).unwrap();
+        merger.add(json!([3]).into()).unwrap();
+        assert!(merger.add(1.into()).is_err());
+
+        let mut output = LogEvent::default();
+        merger.insert_into("out".into(), &mut output).unwrap();
+        assert_eq!(output["out"], json!([1]).into());
+    }
+
+    #[test]
+    fn any_and_all() {
+        for (strategy, expected) in [(MergeStrategy::Any, true), (MergeStrategy::All, false)] {
+            let mut merger = get_value_merger(true.into(), &strategy).unwrap();
+            merger.add(false.into()).unwrap();
+            merger.add(true.into()).unwrap();
+            assert!(merger.add("true".into()).is_err());
+
+            let mut output = LogEvent::default();
+            merger.insert_into("out".into(), &mut output).unwrap();
+            assert_eq!(output["out"], expected.into());
+        }
+        assert!(get_value_merger("true".into(), &MergeStrategy::Any).is_err());
+    }
+
+    #[test]
+    fn toggle_count() {
+        let mut merger = get_value_merger(true.into(), &MergeStrategy::ToggleCount).unwrap();
+        for value in [false, false, true] {
+            merger.add(value.into()).unwrap();
+        }
+
+        let mut output = LogEvent::default();
+        merger.insert_into("out".into(), &mut output).unwrap();
+        assert_eq!(output["out"], 2.into());
+    }
+
+    #[test]
+    fn distinct_count() {
+        let mut merger = get_value_merger("a".into(), &MergeStrategy::DistinctCount).unwrap();
+        for value in ["a", "b"] {
+            merger.add(value.into()).unwrap();
+        }
+
+        let mut output = LogEvent::default();
+        merger.insert_into("out".into(), &mut output).unwrap();
+        assert_eq!(output["out"], 3.into());
+        assert_eq!(output["out_distinct_count"], 2.into());
+    }
+
+    #[test]
+    fn bounding_box() {
+        let mut merger = get_value_merger(
+            json!({ "lat": 40.7, "lon": -74.0 }).into(),
+            &MergeStrategy::BoundingBox,
+        )
+        .unwrap();
+        merger
+            .add(json!({ "lat": 51.5, "lon": -0.1 }).into())
+            .unwrap();
+        merger
+            .add(json!({ "lat": -33.9, "lon": 151 }).into())
+            .unwrap();
+        assert!(merger.add(json!({ "lat": 1.0 }).into()).is_err());
+
+        let mut output = LogEvent::default();
+        merger.insert_into("out".into(), &mut output).unwrap();
+        assert_eq!(
+            output["out"],
+            json!({
+                "min_lat": -33.9,
+                "min_lon": -74.0,
+                "max_lat": 51.5,
+                "max_lon": 151.0,
+            })
+            .into()
+        );
+    }
+
+    #[test]
+    fn array_on_overflow() {
+        let merge = |on_overflow| {
+            let strategy = MergeStrategy::Array {
+                sort_by: None,
+                max_len: Some(3),
+                on_overflow,
+            };
+            let mut merger = get_value_merger(1.into(), &strategy).unwrap();
+            let mut full = Vec::new();
+            for value in 2..=5 {
+                merger.add(value.into()).unwrap();
+                full.push(merger.is_full());
+            }
+            let mut output = LogEvent::default();
+            merger.insert_into("out".into(), &mut output).unwrap();
+            (output["out"].clone(), full)
+        };
+
+        let (output, full) = merge(ArrayOverflow::DropNew);
+        assert_eq!(output, json!([1, 2, 3]).into());
+        assert_eq!(full, [false; 4]);
+
+        let (output, full) = merge(ArrayOverflow::DropOldest);
+        assert_eq!(output, json!([3, 4, 5]).into());
+        assert_eq!(full, [false; 4]);
+
+        let (_, full) = merge(ArrayOverflow::Flush);
+        assert_eq!(full, [false, true, true, true]);
+
+        let strategy = MergeStrategy::Array {
+            sort_by: None,
+            max_len: Some(0),
+            on_overflow: ArrayOverflow::DropNew,
+        };
+        assert!(strategy.validate().is_err());
+    }
+
+    #[test]
+    fn array_sort_by() {
+        let strategy = MergeStrategy::Array {
+            sort_by: Some("timestamp".to_string()),
+            max_len: None,
+            on_overflow: ArrayOverflow::DropNew,
+        };
+        let mut merger =
+            get_value_merger(json!({ "id": 1, "timestamp": 30 }).into(), &strategy).unwrap();
+        merger.add(json!({ "id": 2 }).into()).unwrap();
+        merger
+            .add(json!({ "id": 3, "timestamp": 10 }).into())
+            .unwrap();
+        merger
+            .add(json!({ "id": 4, "timestamp": 20 }).into())
+            .unwrap();
+        merger
+            .add(json!({ "id": 5, "timestamp": 10 }).into())
+            .unwrap();
+
+        let mut output = LogEvent::default();
+        merger.insert_into("out".into(), &mut output).unwrap();
+        assert_eq!(
+            output["out"],
+            json!([
+                { "id": 3, "timestamp": 10 },
+                { "id": 5, "timestamp": 10 },
+                { "id": 4, "timestamp": 20 },
+                { "id": 1, "timestamp": 30 },
+                { "id": 2 },
+            ])
+            .into()
+        );
+    }
+
     fn merge(initial: Value, additional: Value, strategy: &MergeStrategy) -> Result<Value, String> {
         let mut merger = get_value_merger(initial, strategy)?;
         merger.add(additional)?;
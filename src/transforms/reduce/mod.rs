@@ -1,25 +1,47 @@
-use futures::Stream;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{stream, Future, Stream, StreamExt};
 use indexmap::IndexMap;
+use ordered_float::NotNan;
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use regex::Regex;
 use serde_with::serde_as;
 use std::collections::BTreeMap;
 use std::{
+    cmp::Ordering,
     collections::{hash_map, HashMap},
     num::NonZeroUsize,
+    path::PathBuf,
     pin::Pin,
-    time::{Duration, Instant},
+    sync::{Arc, Mutex},
+    time::Duration,
 };
+use tokio::{sync::mpsc, time::Instant};
+use tokio_stream::wrappers::ReceiverStream;
+use uuid::Uuid;
 use vector_lib::configurable::configurable_component;
-use vector_lib::lookup::lookup_v2::parse_target_path;
-use vector_lib::lookup::PathPrefix;
+use vector_lib::lookup::lookup_v2::{
+    parse_target_path, parse_value_path, OwnedTargetPath, OwnedValuePath,
+};
+use vector_lib::lookup::{owned_value_path, PathPrefix};
 
 use crate::config::OutputId;
 use crate::{
     conditions::{AnyCondition, Condition},
     config::{DataType, Input, TransformConfig, TransformContext, TransformOutput},
-    event::{discriminant::Discriminant, Event, EventMetadata, LogEvent},
-    internal_events::ReduceStaleEventFlushed,
+    event::{
+        discriminant::Discriminant,
+        metric::{Metric, MetricKind, MetricTags, MetricValue},
+        Event, EventMetadata, LogEvent, ObjectMap, TargetEvents, VrlTarget,
+    },
+    internal_events::{
+        ReduceFieldDropped, ReduceGroupEvicted, ReduceGroupLookup, ReduceGroupsFlushed,
+        ReduceOpenGroupAge, ReducePausedEventDropped, ReduceSlowTransactionCompleted,
+        ReduceSpillError, ReduceStaleEventFlushed,
+    },
     schema,
-    transforms::{TaskTransform, Transform},
+    template::{Template, TemplateRenderingError},
+    transforms::{MultiOutputTaskTransform, Transform},
 };
 
 mod merge_strategy;
@@ -27,8 +49,12 @@ mod merge_strategy;
 use crate::config::schema::Definition;
 use crate::event::Value;
 pub use merge_strategy::*;
-use vector_lib::config::LogNamespace;
+use vector_lib::config::{clone_input_definitions, LogNamespace};
 use vector_lib::stream::expiration_map::{map_with_expiration, Emitter};
+use vector_lib::{compile_vrl, ByteSizeOf, TimeZone};
+use vrl::compiler::{runtime::Runtime, CompilationResult, CompileConfig, Program, TypeState};
+use vrl::diagnostic::Formatter;
+use vrl::event_path;
 use vrl::value::kind::Collection;
 use vrl::value::{KeyString, Kind};
 
@@ -50,6 +76,14 @@ pub struct ReduceConfig {
     #[configurable(metadata(docs::human_name = "Expire After"))]
     pub expire_after_ms: Duration,
 
+    /// A numeric field that overrides `expire_after_ms` for the group the event belongs to, in
+    /// milliseconds.
+    ///
+    /// Each event that contains this field sets the expiry of its group to the field's value.
+    /// Groups that have not received the field use `expire_after_ms`.
+    #[configurable(metadata(docs::examples = "ttl_ms"))]
+    pub expire_after_field: Option<String>,
+
     /// The interval to check for and flush any expired events, in milliseconds.
     #[serde(default = "default_flush_period_ms")]
     #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
@@ -57,9 +91,110 @@ pub struct ReduceConfig {
     #[configurable(metadata(docs::human_name = "Flush Period"))]
     pub flush_period_ms: Duration,
 
+    /// The time after startup during which expired groups are not flushed, in milliseconds.
+    ///
+    /// This gives groups time to accumulate events after a cold start, rather than flushing small
+    /// partial groups. Groups are still flushed by `ends_when`, `starts_when`, and `max_events`
+    /// during this time.
+    #[serde(default)]
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    #[configurable(metadata(docs::human_name = "Warmup"))]
+    pub warmup_ms: Duration,
+
     /// The maximum number of events to group together.
     pub max_events: Option<NonZeroUsize>,
 
+    /// The maximum number of distinct fields to keep in each group.
+    ///
+    /// Once a group has this many fields, fields that are not yet part of the group are dropped
+    /// from subsequent events. Values of fields already in the group are still merged. This
+    /// bounds the size of groups when malformed events contain many distinct keys.
+    #[configurable(metadata(docs::examples = 100))]
+    pub max_fields_per_group: Option<usize>,
+
+    /// The duration, in milliseconds, above which a transaction is considered slow.
+    ///
+    /// When a group completed by `ends_when` or `starts_when` was open for longer than this, the
+    /// combined event is marked with a `_slow` field set to `true`. Groups flushed for other
+    /// reasons, such as expiring, are not marked.
+    #[configurable(metadata(docs::examples = 5000, docs::type_unit = "milliseconds"))]
+    #[configurable(metadata(docs::human_name = "Slow Threshold"))]
+    pub slow_threshold_ms: Option<u64>,
+
+    /// The interval at which to emit a snapshot of each open group, in milliseconds.
+    ///
+    /// A snapshot is the combined event of a group so far. The group stays open and keeps
+    /// accumulating events. Each snapshot carries a `_snapshot_seq` field, counting up from zero for
+    /// each group, so downstream components can order the snapshots of a group and detect gaps.
+    /// Snapshots are checked for every `flush_period_ms`.
+    #[configurable(metadata(docs::examples = 10000, docs::type_unit = "milliseconds"))]
+    #[configurable(metadata(docs::human_name = "Snapshot Interval"))]
+    pub snapshot_interval_ms: Option<u64>,
+
+    /// The number of events after which to emit a checkpoint of a group to the `checkpoint` output.
+    ///
+    /// A checkpoint is the combined event of a group so far, emitted every time the group has
+    /// merged a multiple of this many events. Events held for reordering are counted once they are
+    /// merged, and events merged away by `coalesce_consecutive` are not counted. The group stays
+    /// open and keeps accumulating events, which allows reporting the progress of long
+    /// transactions. Unlike `snapshot_interval_ms`, checkpoints are driven by the number of events
+    /// rather than by time.
+    #[configurable(metadata(docs::examples = 100))]
+    pub checkpoint_every: Option<NonZeroUsize>,
+
+    /// The maximum duration, in milliseconds, that a group can stay open, measured from when it
+    /// was created.
+    ///
+    /// Unlike `expire_after_ms`, this does not depend on when the last event was received, so a
+    /// group that keeps receiving events is still flushed once it reaches this age. Groups are
+    /// checked for every `flush_period_ms`.
+    #[configurable(metadata(docs::examples = 60000, docs::type_unit = "milliseconds"))]
+    #[configurable(metadata(docs::human_name = "Max Lifetime"))]
+    pub max_lifetime_ms: Option<u64>,
+
+    /// The duration, in milliseconds, after which a group that has not completed is considered
+    /// stuck, measured from when it was created.
+    ///
+    /// Stuck groups are removed and their combined event, marked with `_incomplete` set to `true`,
+    /// is sent to the `quarantine` output instead of the default one, so that they can be
+    /// inspected separately. This is meant to be longer than `expire_after_ms` and
+    /// `max_lifetime_ms`. Groups are checked for every `flush_period_ms`.
+    #[configurable(metadata(docs::examples = 3600000, docs::type_unit = "milliseconds"))]
+    #[configurable(metadata(docs::human_name = "Quarantine After"))]
+    pub quarantine_after_ms: Option<u64>,
+
+    /// The maximum approximate size, in bytes, of all groups held in memory.
+    ///
+    /// When this limit is exceeded, the oldest groups are flushed until the total size is back
+    /// under the limit.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub max_total_bytes: Option<usize>,
+
+    /// The maximum approximate size, in bytes, of the merged fields of each group.
+    ///
+    /// A group is flushed with the event that brings its size above this limit, and the events
+    /// that follow start a new group. This bounds the memory held by groups that accumulate large
+    /// fields, such as ones merged with the `array` strategy.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub max_group_bytes: Option<usize>,
+
+    /// The approximate size, in bytes, of all groups held in memory above which the oldest
+    /// groups are flushed early.
+    ///
+    /// This is the same limit as `max_total_bytes`, but it is only checked once every
+    /// `flush_period_ms` rather than after each event, so the size of the groups can exceed it
+    /// between checks. When it is exceeded, the oldest groups are flushed until the size is back
+    /// within it.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub flush_under_pressure_bytes: Option<usize>,
+
+    /// The approximate size, in bytes, above which an event is not reduced.
+    ///
+    /// Larger events skip aggregation and are sent to the default output unchanged, so that very
+    /// large events are not held in memory as part of a group.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub bypass_if_larger_than_bytes: Option<usize>,
+
     /// An ordered list of fields by which to group events.
     ///
     /// Each group with matching values for the specified keys is reduced independently, allowing
@@ -76,36 +211,666 @@ pub struct ReduceConfig {
     ))]
     pub group_by: Vec<String>,
 
+    /// The fields used to correlate events into groups, in place of `group_by`.
+    ///
+    /// This allows events to be grouped by one set of fields, such as `trace_id`, while the
+    /// combined events are labeled with another, set in `output_label_keys`. Defaults to the
+    /// `group_by` fields.
+    #[configurable(metadata(docs::examples = "trace_id"))]
+    pub correlation_keys: Option<Vec<String>>,
+
+    /// The fields whose values label each group, in place of `group_by`.
+    ///
+    /// These are the fields stored in `group_object_field` and carried by lifecycle events.
+    /// Defaults to the `group_by` fields.
+    #[configurable(metadata(docs::examples = "service"))]
+    pub output_label_keys: Option<Vec<String>>,
+
+    /// A field identifying the same entity across events whose `group_by` fields change.
+    ///
+    /// The first event with a given value of this field decides the group of all later events
+    /// with the same value, even if their `group_by` fields differ. The mapping is forgotten once
+    /// the group is no longer open.
+    #[configurable(metadata(docs::examples = "session_id"))]
+    pub sticky_by: Option<String>,
+
+    /// A field naming another group that turns out to be the same as the group of the event.
+    ///
+    /// The value of this field is an object with the values of the `group_by` fields of the other
+    /// group, where fields missing from it take the value they have in the event. If that group is
+    /// open, it is merged into the group of the event, with the values of each field combined
+    /// according to its merge strategy. For example, with `group_by = ["session"]`, an event in
+    /// session `a` with this field set to `{ "session": "b" }` merges session `b` into session `a`.
+    #[configurable(metadata(docs::examples = "alias_of"))]
+    pub alias_field: Option<String>,
+
+    /// The field to store the values of the `group_by` fields in, as a single object.
+    ///
+    /// When set, each combined event carries an object mapping each of the `group_by` fields to
+    /// the value it had for that group. Fields missing from the events of a group are omitted.
+    #[configurable(metadata(docs::examples = "group"))]
+    pub group_object_field: Option<String>,
+
+    /// Whether or not to give combined events without a message one made of their group.
+    ///
+    /// When enabled, a combined event that has no `message` field is given one listing each of
+    /// the `group_by` fields and the value it had for that group, such as `host=a region=x`.
+    /// Fields missing from the events of a group are omitted.
+    #[serde(default)]
+    pub synthesize_message_from_group: bool,
+
+    /// Whether or not to treat `group_by` fields set to an empty string as missing.
+    ///
+    /// When enabled, events where a `group_by` field is an empty string are grouped together with
+    /// events where the field is missing.
+    #[serde(default)]
+    pub treat_empty_as_missing: bool,
+
+    /// Whether or not to convert the values of the `group_by` fields to strings before grouping.
+    ///
+    /// By default, values of different types are grouped separately, so an event where `host` is
+    /// the number `1` is not grouped with an event where `host` is the string `"1"`. When enabled,
+    /// both are grouped together.
+    #[serde(default)]
+    pub group_by_coerce_to_string: bool,
+
+    /// The fraction of the events of each group to merge, to reduce the cost of very busy groups.
+    ///
+    /// Each event is merged with this probability, and the fields of the others are discarded.
+    /// When a group is flushed, the summed and counted fields, including numeric fields summed by
+    /// default, are scaled by the inverse of this rate, so that they estimate the values over all
+    /// events. Events left out still count towards `max_events`, and events that end a group are
+    /// always merged. Must be greater than `0` and at most `1`.
+    #[configurable(metadata(docs::examples = 0.1))]
+    pub sample_rate: Option<f64>,
+
+    /// A seed for the random sampling of events, when `sample_rate` is set.
+    ///
+    /// By default, sampling is randomly seeded each time the transform starts. Setting a seed
+    /// makes the sampled events reproducible across runs with the same input.
+    pub sample_seed: Option<u64>,
+
     /// A map of field names to custom merge strategies.
     ///
     /// For each field specified, the given strategy is used for combining events rather than
-    /// the default behavior.
+    /// the default behavior. Field names are interpreted as paths, so nested fields such as
+    /// `payload.counter` can be given their own strategy.
     ///
     /// The default behavior is as follows:
     ///
     /// - The first value of a string field is kept and subsequent values are discarded.
     /// - For timestamp fields the first is kept and a new field `[field-name]_end` is added with
-    ///   the last received timestamp value.
+    ///   the last received timestamp value. This can be changed with `timestamp_strategy`.
     /// - Numeric values are summed.
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_merge_strategies")]
     #[configurable(metadata(
         docs::additional_props_description = "An individual merge strategy."
     ))]
     pub merge_strategies: IndexMap<KeyString, MergeStrategy>,
 
+    #[configurable(derived)]
+    #[serde(default)]
+    pub timestamp_strategy: TimestampStrategy,
+
+    /// A map of field names to merge strategies whose results are written to separate fields.
+    ///
+    /// Each field specified is merged with every strategy in its list, and the result of each
+    /// strategy is written to the strategy's `output` field, so that a single field can yield
+    /// several aggregates. The field itself is still merged as usual.
+    #[serde(default)]
+    #[configurable(metadata(
+        docs::additional_props_description = "The merge strategies to apply to the field."
+    ))]
+    pub fan_out_strategies: IndexMap<KeyString, Vec<FanOutStrategy>>,
+
     /// A condition used to distinguish the final event of a transaction.
     ///
     /// If this condition resolves to `true` for an event, the current transaction is immediately
     /// flushed with this event.
     pub ends_when: Option<AnyCondition>,
 
+    /// A list of fields that together mark a transaction as complete.
+    ///
+    /// Once every field has been seen in at least one event of a group, the group is immediately
+    /// flushed with the event that completed it, as with `ends_when`.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "response_code"))]
+    pub ends_when_fields_present: Vec<String>,
+
+    #[configurable(derived)]
+    pub ends_when_match: Option<FieldMatchConfig>,
+
     /// A condition used to distinguish the first event of a transaction.
     ///
     /// If this condition resolves to `true` for an event, the previous transaction is flushed
     /// (without this event) and a new transaction is started.
     pub starts_when: Option<AnyCondition>,
+
+    /// A field that must exist on an event for `ends_when` or `starts_when` to be evaluated.
+    ///
+    /// Events without this field are treated as not matching the condition, without evaluating
+    /// it. This avoids the cost of evaluating the condition for every event of high-volume
+    /// streams where only events with a given field can start or end a transaction.
+    #[configurable(metadata(docs::examples = "transaction_boundary"))]
+    pub condition_hint_field: Option<String>,
+
+    /// Whether or not to coalesce consecutive identical events within a group.
+    ///
+    /// When enabled, an event that is identical to the previous event of its group, ignoring the
+    /// timestamp, is not merged into the group, so it does not contribute to any merge strategy.
+    #[serde(default)]
+    pub coalesce_consecutive: bool,
+
+    /// Whether or not to also forward each event, unchanged, to the `passthrough` output.
+    ///
+    /// Events are still reduced and sent to the default output as usual. The forwarded events
+    /// share their finalizers with the events being reduced, so when end-to-end acknowledgements
+    /// are enabled, an event is only acknowledged once both its forwarded copy and the combined
+    /// event it is part of have been delivered.
+    #[serde(default)]
+    pub tee: bool,
+
+    /// A condition used to pause the aggregation of a group.
+    ///
+    /// The event matching this condition is added to its group, and the following events of the
+    /// group are handled according to `paused_events` until an event matches `resume_when`, which
+    /// must be set as well. While paused, the group does not expire, but is still flushed once it
+    /// reaches `max_lifetime_ms` or quarantined once it reaches `quarantine_after_ms`.
+    pub pause_when: Option<AnyCondition>,
+
+    /// A condition used to resume the aggregation of a paused group.
+    ///
+    /// The event matching this condition is added to its group, along with the following events.
+    pub resume_when: Option<AnyCondition>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub paused_events: PausedEvents,
+
+    /// Adds span fields to each combined event.
+    ///
+    /// When set, the timestamps of the first and last events of a transaction, along with the
+    /// duration between them, are added to the combined event. This is most useful when
+    /// transactions are delimited with `starts_when` or `ends_when`.
+    pub span_fields: Option<SpanFieldNames>,
+
+    /// Whether or not to add the earliest and latest timestamps of each group.
+    ///
+    /// When enabled, the earliest and latest values of all top-level timestamp fields across the
+    /// events of a group are added to the combined event as `_min_timestamp` and
+    /// `_max_timestamp`.
+    #[serde(default)]
+    pub track_timestamp_bounds: bool,
+
+    /// A [Vector Remap Language][vrl] (VRL) program to run on each combined event before it is
+    /// emitted.
+    ///
+    /// This can be used to compute derived fields from the combined event or to clean it up. If
+    /// the program fails, a warning is logged and the combined event is emitted unchanged.
+    ///
+    /// [vrl]: https://vector.dev/docs/reference/vrl
+    #[configurable(metadata(docs::examples = ".average = .total / .count ?? 0"))]
+    pub flush_vrl: Option<String>,
+
+    /// A template for a short, human-readable summary of each combined event.
+    ///
+    /// The template is rendered against the combined event, and the result is stored in a
+    /// `_summary` field, so the event can be consumed both in full and as a single line. If the
+    /// template fails to render, the field is not set.
+    #[configurable(metadata(docs::examples = "{{ request_id }}: {{ count }} requests"))]
+    pub summary_template: Option<Template>,
+
+    /// The number of decimal places to round float values of combined events to.
+    ///
+    /// Merging floats, such as summing or averaging them, can produce values with long, noisy
+    /// decimals. When set, each float value of a combined event, including those nested in
+    /// objects and arrays, is rounded when the group is flushed.
+    #[configurable(metadata(docs::examples = 2))]
+    pub float_precision: Option<u32>,
+
+    #[configurable(derived)]
+    pub spill: Option<SpillConfig>,
+
+    /// Flushes a group when an event's sequence number does not follow the previous one.
+    ///
+    /// A gap in the sequence indicates a lost event or a boundary between transactions. The
+    /// group is flushed before the event that follows the gap is added to a new group.
+    pub flush_on_sequence_gap: Option<SequenceGapConfig>,
+
+    #[configurable(derived)]
+    pub flush_on_sum: Option<SumThresholdConfig>,
+
+    /// A list of fields whose values must stay the same within a group.
+    ///
+    /// The first value of each field in a group is kept, and the group is flushed before an event
+    /// with a different value is added to a new group. This avoids merging incompatible events,
+    /// such as those of different versions of a schema. Events without a field do not affect it.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "schema_version"))]
+    pub flush_on_field_change: Vec<String>,
+
+    /// A field whose value identifies the schema of each event.
+    ///
+    /// Each group is locked to the value of this field in its first event, and later events with
+    /// a different value, or without the field when the first event had it, are sent to the
+    /// `rejected` output instead of being merged. This prevents merging heterogeneous events into
+    /// a single group.
+    #[configurable(metadata(docs::examples = "schema_version"))]
+    pub require_schema_field: Option<String>,
+
+    /// A timestamp field to order the events of each group by before they are merged.
+    ///
+    /// Events are held back and merged in the order of this field, so that strategies such as
+    /// `concat` and `array` reflect the order in which events occurred rather than the order in
+    /// which they arrived. Events without a timestamp in this field are merged as they arrive.
+    #[configurable(metadata(docs::examples = "timestamp"))]
+    pub reorder_by: Option<String>,
+
+    /// How far behind the latest event of a group an event can be and still be reordered, in
+    /// milliseconds, when `reorder_by` is set.
+    ///
+    /// Held events older than this, relative to the latest timestamp seen by the group, are
+    /// merged, and the rest are merged when the group is flushed. Events that arrive later than
+    /// this are merged as they arrive.
+    #[serde(default = "default_reorder_window_ms")]
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    #[derivative(Default(value = "default_reorder_window_ms()"))]
+    #[configurable(metadata(docs::human_name = "Reorder Window"))]
+    pub reorder_window_ms: Duration,
+
+    /// A field to order the combined events flushed together by, from the highest value to the
+    /// lowest.
+    ///
+    /// When several groups expire in the same flush, their combined events are emitted in
+    /// descending order of this field, so downstream components receive the highest priority
+    /// aggregates first. Events without the field are emitted last, in no particular order.
+    #[configurable(metadata(docs::examples = "severity"))]
+    pub flush_priority_field: Option<String>,
+
+    /// Whether or not to record the time spent finding the group of each event.
+    ///
+    /// When enabled, the time spent computing the key of each event's group and looking the group
+    /// up is recorded in the `group_lookup_duration_seconds` histogram. This helps diagnose slow
+    /// grouping with many distinct groups, at the cost of timing every event.
+    #[serde(default)]
+    pub profiling: bool,
+
+    /// Whether or not to record the age of each open group on every flush.
+    ///
+    /// When enabled, every `flush_period_ms`, the time since each group still open was created is
+    /// recorded in the `open_group_age_seconds` histogram, which shows how long groups tend to
+    /// stay open. This scans all open groups on every flush.
+    #[serde(default)]
+    pub emit_age_metrics: bool,
+
+    /// Whether or not to emit the summed and counted fields of each combined event as the
+    /// difference from the previous combined event of the same group.
+    ///
+    /// The fields merged with the `sum`, `sum_duration`, or `count` strategies, and numeric fields
+    /// without a merge strategy, which are summed by default, are kept as a baseline for each
+    /// flushed group, and subtracted from the same fields of the next combined event of the group.
+    /// Other fields, including the `group_by` fields, are never changed. The first combined event
+    /// of a group, and fields missing from the baseline, are emitted as is.
+    ///
+    /// The baseline of a group is forgotten once the group has not been open for
+    /// `expire_after_ms`, and baselines count towards `max_total_bytes` and
+    /// `flush_under_pressure_bytes`.
+    #[serde(default)]
+    pub emit_delta_from_previous: bool,
+
+    /// Whether or not to record how each field of a combined event was merged.
+    ///
+    /// When enabled, each combined event carries a `_provenance` object with an entry for each
+    /// merged field, holding the number of events that contributed to the field in `events`, and
+    /// the merge strategy applied to it in `strategy`. Fields without a configured merge strategy
+    /// are reported with the `default` strategy.
+    #[serde(default)]
+    pub emit_provenance: bool,
+
+    /// Whether or not to emit lifecycle events to the `lifecycle` output when groups open and close.
+    ///
+    /// When a group is opened, an event with a `_lifecycle` field set to `start` is emitted, and
+    /// when it is flushed, one set to `end` is emitted after the combined event. Lifecycle events
+    /// also carry the values of the `group_by` fields of their group.
+    #[serde(default)]
+    pub emit_lifecycle: bool,
+
+    /// Whether or not to emit the first event of each group to the `first` output as it arrives.
+    ///
+    /// The event is emitted unchanged when its group is opened, before it is merged, so that
+    /// latency-sensitive consumers such as alerts can act on a new group right away. The combined
+    /// event is still flushed to the default output as usual.
+    #[serde(default)]
+    pub emit_first_immediately: bool,
+
+    /// Emits a counter metric for each combined event to the `metrics` output.
+    ///
+    /// The counter is incremented by one each time a group is flushed, which can be used to count
+    /// completed transactions.
+    pub emit_metric: Option<MetricConfig>,
+
+    /// A table of output names to conditions, used to route each combined event to an output.
+    ///
+    /// The conditions are evaluated in order against each combined event when its group is
+    /// flushed, and the event is sent to the output of the first matching condition, which can be
+    /// referenced as an input by other components with the name `<transform_name>.<output_name>`.
+    /// Combined events that match no condition are sent to the default output. The names of the
+    /// other outputs of this transform, such as `passthrough`, are reserved.
+    #[serde(default)]
+    #[configurable(metadata(
+        docs::additional_props_description = "The condition for routing combined events to the output."
+    ))]
+    pub route_when: IndexMap<String, AnyCondition>,
+}
+
+/// Configuration for the counter metric emitted for each combined event.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MetricConfig {
+    /// The name of the metric.
+    #[configurable(metadata(docs::examples = "transactions_completed"))]
+    pub name: String,
+
+    /// The namespace of the metric.
+    #[configurable(metadata(docs::examples = "service"))]
+    pub namespace: Option<String>,
+
+    /// The `group_by` fields to add as tags to the metric.
+    ///
+    /// Each tag is named after its field and set to the field's value in the combined event.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "host"))]
+    pub tags: Vec<String>,
+}
+
+impl MetricConfig {
+    fn build(&self, event: &LogEvent) -> Metric {
+        let mut tags = MetricTags::default();
+        for field in &self.tags {
+            if let Ok(Some(value)) = event.parse_path_and_get_value(field.as_str()) {
+                tags.replace(field.clone(), value.to_string_lossy().into_owned());
+            }
+        }
+
+        Metric::new(
+            self.name.clone(),
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        )
+        .with_namespace(self.namespace.clone())
+        .with_tags(Some(tags))
+        .with_timestamp(Some(Utc::now()))
+    }
+}
+
+/// A merge strategy whose result is written to a separate field.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct FanOutStrategy {
+    /// The field to write the result of the merge strategy to.
+    #[configurable(metadata(docs::examples = "requests_sum"))]
+    pub output: KeyString,
+
+    #[serde(flatten)]
+    #[configurable(derived)]
+    pub strategy: MergeStrategy,
+}
+
+/// What to do with the events of a group received while it is paused.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PausedEvents {
+    /// Drop the events.
+    #[default]
+    Drop,
+
+    /// Forward the events, unchanged, to the `passthrough` output.
+    Passthrough,
+}
+
+/// Configuration for flushing groups on gaps in a sequence.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SequenceGapConfig {
+    /// The integer field containing the sequence number of each event.
+    ///
+    /// Each event is expected to have a sequence number one greater than the previous event of
+    /// its group. Events without the field do not affect the expected sequence number.
+    #[configurable(metadata(docs::examples = "seq"))]
+    pub field: String,
+}
+
+/// Configuration for flushing groups once the sum of a field exceeds a threshold.
+///
+/// The values of the field are summed across the events of each group, and a group is flushed
+/// with the event that brings the sum above `threshold`. This allows batching events by volume,
+/// such as by the number of bytes they describe.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SumThresholdConfig {
+    /// The numeric field to sum.
+    ///
+    /// Events without the field, or with a non-numeric value, do not affect the sum.
+    #[configurable(metadata(docs::examples = "bytes"))]
+    pub field: String,
+
+    /// The sum above which a group is flushed.
+    #[configurable(metadata(docs::examples = 1048576.0))]
+    pub threshold: f64,
+}
+
+/// Configuration for flushing groups when a field matches a regular expression.
+///
+/// A group is flushed with the event whose field matches `pattern`, as with `ends_when`. This is
+/// a lightweight alternative to a condition for simple end markers, such as a `status` field set
+/// to `done`.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct FieldMatchConfig {
+    /// The field to match.
+    ///
+    /// Events without the field do not match. Values that are not strings are matched in their
+    /// string form.
+    #[configurable(metadata(docs::examples = "status"))]
+    pub field: String,
+
+    /// The regular expression that the value of the field is matched against.
+    #[configurable(metadata(docs::examples = "^(done|failed)$"))]
+    pub pattern: String,
+}
+
+/// Configuration for writing a large field of combined events to files.
+///
+/// When the field of a combined event is larger than `threshold_bytes`, its value is written to a
+/// new file, and the field is replaced with the path of the file, so that downstream components
+/// only handle a reference to it. Snapshots and checkpoints of open groups are never written to
+/// files. Unless `remove_after_secs` is set, Vector does not remove the files; whatever reads them
+/// is responsible for deleting them once they are no longer needed.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SpillConfig {
+    /// The field to write to a file.
+    ///
+    /// String values are written as they are, and other values are written as JSON.
+    #[configurable(metadata(docs::examples = "body"))]
+    pub field: String,
+
+    /// The size, in bytes, above which the field is written to a file.
+    #[configurable(metadata(docs::examples = 1048576, docs::type_unit = "bytes"))]
+    pub threshold_bytes: usize,
+
+    /// The directory to write the files to.
+    ///
+    /// If not set, the temporary directory of the system is used.
+    #[configurable(metadata(docs::examples = "/var/lib/vector/reduce"))]
+    pub dir: Option<PathBuf>,
+
+    /// The number of seconds after which Vector removes the files it wrote.
+    ///
+    /// Files that are still waiting to be removed when Vector stops are left in place.
+    #[configurable(metadata(docs::examples = 3600, docs::type_unit = "seconds"))]
+    pub remove_after_secs: Option<u64>,
+}
+
+/// Writes the field of combined events to files when they are too large, when `spill` is set.
+#[derive(Debug)]
+struct Spill {
+    field: OwnedTargetPath,
+    threshold_bytes: usize,
+    dir: PathBuf,
+    writes: SpillWrites,
+}
+
+impl Spill {
+    /// Replaces the field of the event with the path of a file containing its value, if the value
+    /// is larger than the threshold. The file is written by `SpillWrites` before the event leaves
+    /// the transform.
+    fn spill(&self, event: &mut LogEvent) {
+        let bytes = match event.get(&self.field) {
+            Some(Value::Bytes(bytes)) => bytes.clone(),
+            Some(value) => match serde_json::to_vec(value) {
+                Ok(json) => Bytes::from(json),
+                Err(_) => return,
+            },
+            None => return,
+        };
+        if bytes.len() <= self.threshold_bytes {
+            return;
+        }
+
+        let path = self.dir.join(format!("reduce-{}", Uuid::new_v4()));
+        event.insert(&self.field, path.to_string_lossy().into_owned());
+        self.writes
+            .pending
+            .lock()
+            .expect("spill lock poisoned")
+            .push((path, bytes));
+    }
+}
+
+/// The files queued by `Spill`, written asynchronously off the flush path.
+#[derive(Clone, Debug)]
+struct SpillWrites {
+    pending: Arc<Mutex<Vec<(PathBuf, Bytes)>>>,
+    remove_after: Option<Duration>,
+}
+
+impl SpillWrites {
+    /// Returns a future writing the files queued so far. It is awaited before each output event is
+    /// sent on, so the files of an event exist by the time it leaves the transform.
+    fn write_pending(&self) -> impl Future<Output = ()> + Send + 'static {
+        let pending = std::mem::take(&mut *self.pending.lock().expect("spill lock poisoned"));
+        let remove_after = self.remove_after;
+        async move {
+            for (path, bytes) in pending {
+                if let Err(error) = tokio::fs::write(&path, bytes).await {
+                    emit!(ReduceSpillError { error, path: &path });
+                    continue;
+                }
+                if let Some(remove_after) = remove_after {
+                    tokio::spawn(async move {
+                        tokio::time::sleep(remove_after).await;
+                        if let Err(error) = tokio::fs::remove_file(&path).await {
+                            warn!(message = "Failed to remove spilled file.", path = ?path, %error);
+                        }
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Field names used for the span fields of a combined event.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct SpanFieldNames {
+    /// The field to store the timestamp of the first event of the transaction in.
+    #[serde(default = "default_span_start_time")]
+    #[derivative(Default(value = "default_span_start_time()"))]
+    pub start_time: String,
+
+    /// The field to store the timestamp of the last event of the transaction in.
+    #[serde(default = "default_span_end_time")]
+    #[derivative(Default(value = "default_span_end_time()"))]
+    pub end_time: String,
+
+    /// The field to store the duration between the first and last event of the transaction in,
+    /// in milliseconds.
+    #[serde(default = "default_span_duration_ms")]
+    #[derivative(Default(value = "default_span_duration_ms()"))]
+    pub duration_ms: String,
+}
+
+fn default_span_start_time() -> String {
+    "start_time".to_string()
+}
+
+fn default_span_end_time() -> String {
+    "end_time".to_string()
+}
+
+fn default_span_duration_ms() -> String {
+    "duration_ms".to_string()
+}
+
+impl SpanFieldNames {
+    fn insert_into(&self, start: DateTime<Utc>, end: DateTime<Utc>, event: &mut LogEvent) {
+        let duration_ms = (end - start).num_milliseconds();
+        let fields = [
+            (&self.start_time, Value::Timestamp(start)),
+            (&self.end_time, Value::Timestamp(end)),
+            (&self.duration_ms, Value::Integer(duration_ms)),
+        ];
+        for (field, value) in fields {
+            if let Err(error) = event.parse_path_and_insert(field, value) {
+                warn!(message = "Failed to insert span field.", field = %field, %error);
+            }
+        }
+    }
 }
 
+/// The name of the output that events are forwarded to when `tee` is enabled.
+pub const PASSTHROUGH_OUTPUT: &str = "passthrough";
+
+/// The name of the output that metrics are sent to when `emit_metric` is set.
+pub const METRICS_OUTPUT: &str = "metrics";
+
+/// The name of the output that lifecycle events are sent to when `emit_lifecycle` is set.
+pub const LIFECYCLE_OUTPUT: &str = "lifecycle";
+
+/// The name of the output that checkpoints are sent to when `checkpoint_every` is set.
+pub const CHECKPOINT_OUTPUT: &str = "checkpoint";
+
+/// The name of the output that the first event of each group is sent to when
+/// `emit_first_immediately` is set.
+pub const FIRST_OUTPUT: &str = "first";
+
+/// The name of the output that stuck groups are sent to when `quarantine_after_ms` is set.
+pub const QUARANTINE_OUTPUT: &str = "quarantine";
+
+/// The names of the outputs of the transform, which can't be used as `route_when` outputs.
+const RESERVED_OUTPUTS: [&str; 7] = [
+    PASSTHROUGH_OUTPUT,
+    METRICS_OUTPUT,
+    LIFECYCLE_OUTPUT,
+    CHECKPOINT_OUTPUT,
+    REJECTED_OUTPUT,
+    FIRST_OUTPUT,
+    QUARANTINE_OUTPUT,
+];
+
+/// The name of the output that events not matching the schema of their group are sent to when
+/// `require_schema_field` is set.
+pub const REJECTED_OUTPUT: &str = "rejected";
+
 const fn default_expire_after_ms() -> Duration {
     Duration::from_millis(30000)
 }
@@ -114,19 +879,37 @@ const fn default_flush_period_ms() -> Duration {
     Duration::from_millis(1000)
 }
 
+const fn default_reorder_window_ms() -> Duration {
+    Duration::from_millis(1000)
+}
+
 impl_generate_config_from_default!(ReduceConfig);
 
 #[async_trait::async_trait]
 #[typetag::serde(name = "reduce")]
 impl TransformConfig for ReduceConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Reduce::new(self, &context.enrichment_tables).map(Transform::event_task)
+        Reduce::new(self, &context.enrichment_tables).map(Transform::multi_output_task)
     }
 
     fn input(&self) -> Input {
         Input::log()
     }
 
+    fn validate(&self, _: &schema::Definition) -> Result<(), Vec<String>> {
+        let errors: Vec<_> = self
+            .route_when
+            .keys()
+            .filter(|name| RESERVED_OUTPUTS.contains(&name.as_str()))
+            .map(|name| format!("cannot have a `route_when` output with reserved name: `{name}`"))
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     fn outputs(
         &self,
         _: vector_lib::enrichment::TableRegistry,
@@ -141,26 +924,121 @@ impl TransformConfig for ReduceConfig {
             .reduce(Definition::merge)
             .unwrap_or_else(Definition::any);
 
-        let mut schema_definition = merged_definition;
+        let mut schema_definition = merged_definition.clone();
 
-        for (key, merge_strategy) in self.merge_strategies.iter() {
-            let key = if let Ok(key) = parse_target_path(key) {
-                key
-            } else {
-                continue;
+        // timestamps without a merge strategy are replaced with an object holding their range
+        if self.timestamp_strategy == TimestampStrategy::Range {
+            let range_kind = Kind::object(Collection::from(BTreeMap::from([
+                ("start".into(), Kind::timestamp()),
+                ("end".into(), Kind::timestamp()),
+            ])));
+            let fields: Vec<_> = schema_definition
+                .event_kind()
+                .as_object()
+                .map(|object| {
+                    object
+                        .known()
+                        .iter()
+                        .map(|(field, kind)| (field.to_string(), kind))
+                        .filter(|(field, kind)| {
+                            kind.contains_timestamp()
+                                && !self
+                                    .merge_strategies
+                                    .contains_key(&KeyString::from(field.as_str()))
+                        })
+                        .map(|(field, kind)| (field, kind.clone()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            for (field, mut kind) in fields {
+                kind.remove_timestamp();
+                schema_definition = schema_definition.with_event_field(
+                    &owned_value_path!(field.as_str()),
+                    kind.union(range_kind.clone()),
+                    None,
+                );
+            }
+        }
+
+        // Each strategy reads the field it is applied to and writes its result to an output field,
+        // which is the same field unless the strategy fans out to a separate one.
+        let strategies = self
+            .merge_strategies
+            .iter()
+            .map(|(key, strategy)| (key, key, strategy))
+            .chain(
+                self.fan_out_strategies
+                    .iter()
+                    .flat_map(|(key, strategies)| {
+                        strategies
+                            .iter()
+                            .map(move |fan_out| (key, &fan_out.output, &fan_out.strategy))
+                    }),
+            );
+
+        for (source, output, merge_strategy) in strategies {
+            let (source, key) = match (parse_target_path(source), parse_target_path(output)) {
+                (Ok(source), Ok(key)) => (source, key),
+                _ => continue,
             };
 
-            let input_kind = match key.prefix {
-                PathPrefix::Event => schema_definition.event_kind().at_path(&key.path),
-                PathPrefix::Metadata => schema_definition.metadata_kind().at_path(&key.path),
+            let input_kind = match source.prefix {
+                PathPrefix::Event => schema_definition.event_kind().at_path(&source.path),
+                PathPrefix::Metadata => schema_definition.metadata_kind().at_path(&source.path),
             };
 
             let new_kind = match merge_strategy {
-                MergeStrategy::Discard | MergeStrategy::Retain => {
+                MergeStrategy::Discard
+                | MergeStrategy::Retain
+                | MergeStrategy::Coalesce
+                | MergeStrategy::LastByTimestamp { .. } => {
                     /* does not change the type */
                     input_kind.clone()
                 }
-                MergeStrategy::Sum | MergeStrategy::Max | MergeStrategy::Min => {
+                MergeStrategy::Ema { .. } | MergeStrategy::Avg { .. } => {
+                    // always produces a float value
+                    if input_kind.contains_integer() || input_kind.contains_float() {
+                        Kind::float()
+                    } else {
+                        Kind::undefined()
+                    }
+                }
+                MergeStrategy::TopK { .. } => {
+                    let entry = Collection::from(BTreeMap::from([
+                        ("value".into(), input_kind.without_undefined()),
+                        ("count".into(), Kind::integer()),
+                    ]));
+                    Kind::array(Collection::empty().with_unknown(Kind::object(entry)))
+                }
+                MergeStrategy::BoundingBox => {
+                    let bbox = ["min_lat", "min_lon", "max_lat", "max_lon"]
+                        .into_iter()
+                        .map(|field| (field.into(), Kind::float()))
+                        .collect::<BTreeMap<_, _>>();
+                    Kind::object(Collection::from(bbox))
+                }
+                MergeStrategy::GroupSum { key, value } => {
+                    let entry = Collection::from(BTreeMap::from([
+                        (key.as_str().into(), Kind::any()),
+                        (
+                            format!("{}_total", value).into(),
+                            Kind::integer().or_float(),
+                        ),
+                    ]));
+                    Kind::array(Collection::empty().with_unknown(Kind::object(entry)))
+                }
+                MergeStrategy::SumDuration { .. } => {
+                    // always produces an integer number of milliseconds
+                    Kind::integer()
+                }
+                MergeStrategy::Count
+                | MergeStrategy::DistinctCount
+                | MergeStrategy::ToggleCount => Kind::integer(),
+                MergeStrategy::Any | MergeStrategy::All => Kind::boolean(),
+                MergeStrategy::Sum { .. }
+                | MergeStrategy::Max { .. }
+                | MergeStrategy::Min { .. }
+                | MergeStrategy::ZscoreFlag { .. } => {
                     // only keeps integer / float values
                     match (input_kind.contains_integer(), input_kind.contains_float()) {
                         (true, true) => Kind::float().or_integer(),
@@ -169,7 +1047,7 @@ impl TransformConfig for ReduceConfig {
                         (false, false) => Kind::undefined(),
                     }
                 }
-                MergeStrategy::Array => {
+                MergeStrategy::Array { .. } => {
                     let unknown_kind = input_kind.clone();
                     Kind::array(Collection::empty().with_unknown(unknown_kind))
                 }
@@ -195,7 +1073,9 @@ impl TransformConfig for ReduceConfig {
                         Kind::undefined()
                     }
                 }
-                MergeStrategy::ShortestArray | MergeStrategy::LongestArray => {
+                MergeStrategy::ShortestArray
+                | MergeStrategy::LongestArray
+                | MergeStrategy::ArrayDiff => {
                     if let Some(array) = input_kind.as_array() {
                         Kind::array(array.clone())
                     } else {
@@ -212,6 +1092,14 @@ impl TransformConfig for ReduceConfig {
                     }
                     Kind::array(Collection::empty().with_unknown(array_elements))
                 }
+                MergeStrategy::SortedUnique { .. } => {
+                    // arrays are flattened into their elements
+                    let mut array_elements = input_kind.without_array();
+                    if let Some(array) = input_kind.as_array() {
+                        array_elements = array_elements.union(array.reduced_kind());
+                    }
+                    Kind::array(Collection::empty().with_unknown(array_elements))
+                }
             };
 
             // all of the merge strategies are optional. They won't produce a value unless a value actually exists
@@ -221,99 +1109,929 @@ impl TransformConfig for ReduceConfig {
                 new_kind
             };
 
-            schema_definition = schema_definition.with_field(&key, new_kind, None);
-        }
+            if let MergeStrategy::Sum {
+                keep_last_as: Some(field),
+                ..
+            } = merge_strategy
+            {
+                // the last value has the same kind as the sum
+                schema_definition = schema_definition.with_event_field(
+                    &owned_value_path!(field.as_str()),
+                    new_kind.clone(),
+                    None,
+                );
+            }
 
-        // the same schema definition is used for all inputs
-        let mut output_definitions = HashMap::new();
+            if let MergeStrategy::DistinctCount = merge_strategy {
+                schema_definition = schema_definition.with_event_field(
+                    &owned_value_path!(format!("{}_distinct_count", output).as_str()),
+                    new_kind.clone(),
+                    None,
+                );
+            }
+
+            if let MergeStrategy::ZscoreFlag { .. } = merge_strategy {
+                // the flag is written alongside the field whenever the field is
+                let flag_kind = if new_kind.contains_undefined() {
+                    Kind::boolean().or_undefined()
+                } else {
+                    Kind::boolean()
+                };
+                schema_definition = schema_definition.with_event_field(
+                    &owned_value_path!(format!("{}_anomaly", output).as_str()),
+                    flag_kind,
+                    None,
+                );
+            }
+
+            schema_definition = schema_definition.with_field(&key, new_kind, None);
+        }
+
+        // a spilled field is replaced with the path of its file
+        if let Some(field) = self
+            .spill
+            .as_ref()
+            .and_then(|spill| parse_target_path(&spill.field).ok())
+        {
+            let kind = match field.prefix {
+                PathPrefix::Event => schema_definition.event_kind().at_path(&field.path),
+                PathPrefix::Metadata => schema_definition.metadata_kind().at_path(&field.path),
+            };
+            schema_definition = schema_definition.with_field(&field, kind.or_bytes(), None);
+        }
+
+        // the span fields are only added to groups with a timestamp
+        if let Some(span_fields) = &self.span_fields {
+            let fields = [
+                (&span_fields.start_time, Kind::timestamp()),
+                (&span_fields.end_time, Kind::timestamp()),
+                (&span_fields.duration_ms, Kind::integer()),
+            ];
+            for (field, kind) in fields {
+                if let Ok(field) = parse_target_path(field) {
+                    schema_definition =
+                        schema_definition.with_field(&field, kind.or_undefined(), None);
+                }
+            }
+        }
+
+        // the bounds are only added to groups with a timestamp
+        if self.track_timestamp_bounds {
+            for field in ["_min_timestamp", "_max_timestamp"] {
+                schema_definition = schema_definition.with_event_field(
+                    &owned_value_path!(field),
+                    Kind::timestamp().or_undefined(),
+                    None,
+                );
+            }
+        }
+
+        // the group object holds the values of the label fields as they were received, leaving
+        // out the missing ones
+        if let Some(field) = self
+            .group_object_field
+            .as_ref()
+            .and_then(|field| parse_target_path(field).ok())
+        {
+            let label_keys = self.output_label_keys.as_ref().unwrap_or(&self.group_by);
+            let labels = label_keys
+                .iter()
+                .map(|key| {
+                    let kind = match parse_target_path(key) {
+                        Ok(path) => match path.prefix {
+                            PathPrefix::Event => merged_definition.event_kind().at_path(&path.path),
+                            PathPrefix::Metadata => {
+                                merged_definition.metadata_kind().at_path(&path.path)
+                            }
+                        },
+                        Err(_) => Kind::undefined(),
+                    };
+                    (key.as_str().into(), kind)
+                })
+                .collect::<BTreeMap<_, _>>();
+            schema_definition =
+                schema_definition.with_field(&field, Kind::object(Collection::from(labels)), None);
+        }
+
+        // only snapshots carry a sequence number
+        if self.snapshot_interval_ms.is_some() {
+            schema_definition = schema_definition.with_event_field(
+                &owned_value_path!("_snapshot_seq"),
+                Kind::integer().or_undefined(),
+                None,
+            );
+        }
+
+        // only groups open for longer than the threshold are marked
+        if self.slow_threshold_ms.is_some() {
+            schema_definition = schema_definition.with_event_field(
+                &owned_value_path!("_slow"),
+                Kind::boolean().or_undefined(),
+                None,
+            );
+        }
+
+        // the summary is left out when the template fails to render
+        if self.summary_template.is_some() {
+            schema_definition = schema_definition.with_event_field(
+                &owned_value_path!("_summary"),
+                Kind::bytes().or_undefined(),
+                None,
+            );
+        }
+
+        // the provenance maps each merged field to the number of events and the strategy it was
+        // merged with
+        if self.emit_provenance {
+            let entry = Collection::from(BTreeMap::from([
+                ("events".into(), Kind::integer()),
+                ("strategy".into(), Kind::bytes()),
+            ]));
+            schema_definition = schema_definition.with_event_field(
+                &owned_value_path!("_provenance"),
+                Kind::object(Collection::empty().with_unknown(Kind::object(entry))),
+                None,
+            );
+        }
+
+        // the same schema definition is used for all inputs
+        let mut output_definitions = HashMap::new();
         for (output, _input) in input_definitions {
             output_definitions.insert(output.clone(), schema_definition.clone());
         }
 
-        vec![TransformOutput::new(DataType::Log, output_definitions)]
+        let checkpoint_definitions = self
+            .checkpoint_every
+            .is_some()
+            .then(|| output_definitions.clone());
+        let quarantine_definitions = self
+            .quarantine_after_ms
+            .is_some()
+            .then(|| output_definitions.clone());
+        let route_outputs: Vec<_> = self
+            .route_when
+            .keys()
+            .map(|name| {
+                TransformOutput::new(DataType::Log, output_definitions.clone()).with_port(name)
+            })
+            .collect();
+        let mut outputs = vec![TransformOutput::new(DataType::Log, output_definitions)];
+        if self.tee || self.paused_events == PausedEvents::Passthrough {
+            outputs.push(
+                TransformOutput::new(DataType::Log, clone_input_definitions(input_definitions))
+                    .with_port(PASSTHROUGH_OUTPUT),
+            );
+        }
+        if self.emit_metric.is_some() {
+            outputs.push(
+                TransformOutput::new(DataType::Metric, HashMap::new()).with_port(METRICS_OUTPUT),
+            );
+        }
+        if self.emit_lifecycle {
+            let lifecycle_definitions = input_definitions
+                .iter()
+                .map(|(output, _)| (output.clone(), Definition::any()))
+                .collect();
+            outputs.push(
+                TransformOutput::new(DataType::Log, lifecycle_definitions)
+                    .with_port(LIFECYCLE_OUTPUT),
+            );
+        }
+        if self.emit_first_immediately {
+            outputs.push(
+                TransformOutput::new(DataType::Log, clone_input_definitions(input_definitions))
+                    .with_port(FIRST_OUTPUT),
+            );
+        }
+        if let Some(quarantine_definitions) = quarantine_definitions {
+            outputs.push(
+                TransformOutput::new(DataType::Log, quarantine_definitions)
+                    .with_port(QUARANTINE_OUTPUT),
+            );
+        }
+        if self.require_schema_field.is_some() {
+            outputs.push(
+                TransformOutput::new(DataType::Log, clone_input_definitions(input_definitions))
+                    .with_port(REJECTED_OUTPUT),
+            );
+        }
+        if let Some(checkpoint_definitions) = checkpoint_definitions {
+            outputs.push(
+                TransformOutput::new(DataType::Log, checkpoint_definitions)
+                    .with_port(CHECKPOINT_OUTPUT),
+            );
+        }
+        outputs.extend(route_outputs);
+        outputs
     }
 }
 
 #[derive(Debug)]
 struct ReduceState {
     events: usize,
+    // The number of events left out by `sample_rate`, which still count towards `max_events`.
+    skipped: usize,
     fields: HashMap<KeyString, Box<dyn ReduceValueMerger>>,
+    creation: Instant,
+    // The position of the group in `Reduce::creation_order`, among groups with the same creation.
+    creation_seq: u64,
     stale_since: Instant,
     metadata: EventMetadata,
+    first_timestamp: Option<DateTime<Utc>>,
+    last_timestamp: Option<DateTime<Utc>>,
+    // The earliest and latest values of all top-level timestamp fields.
+    timestamp_bounds: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    expire_after: Duration,
+    // The previous event added to this state, without its timestamp, when coalescing.
+    last_value: Option<Value>,
+    // Approximate size, in bytes, of the values merged into this state.
+    size: usize,
+    // The values of the `group_by` fields, when `group_object_field` or
+    // `synthesize_message_from_group` is set.
+    group: Option<ObjectMap>,
+    // The sequence number of the last event with one, when `flush_on_sequence_gap` is set.
+    last_sequence: Option<i64>,
+    // The running sum of the `flush_on_sum` field.
+    sum: f64,
+    // The first value of each `flush_on_field_change` field seen by the group.
+    field_values: Vec<Option<Value>>,
+    // Whether each `ends_when_fields_present` field has been seen by the group.
+    fields_present: Vec<bool>,
+    // The value of the `require_schema_field` field in the first event of the group.
+    schema: Option<Value>,
+    // Events held back to be merged in the order of their `reorder_by` timestamp.
+    held: Vec<(DateTime<Utc>, LogEvent)>,
+    // The latest `reorder_by` timestamp seen by the group.
+    latest_reorder_time: Option<DateTime<Utc>>,
+    // Whether aggregation is paused, between events matching `pause_when` and `resume_when`.
+    paused: bool,
+    // The maximum number of fields to keep, when `max_fields_per_group` is set.
+    max_fields: Option<usize>,
+    // How to merge timestamp fields without a merge strategy.
+    timestamp_strategy: TimestampStrategy,
+    // The number of events merged into each field, and the name of its strategy, when
+    // `emit_provenance` is set.
+    provenance: Option<HashMap<KeyString, (usize, &'static str)>>,
+    // The sequence number of the next snapshot, and when the last one was taken.
+    snapshot_seq: u64,
+    last_snapshot: Instant,
+    // The number of events merged into the group at its last checkpoint.
+    last_checkpoint: usize,
 }
 
 impl ReduceState {
-    fn new() -> Self {
+    fn new(
+        expire_after: Duration,
+        group: Option<ObjectMap>,
+        max_fields: Option<usize>,
+        timestamp_strategy: TimestampStrategy,
+        provenance: bool,
+    ) -> Self {
         let fields = HashMap::new();
         let metadata = EventMetadata::default();
+        let now = Instant::now();
 
         Self {
             events: 0,
-            stale_since: Instant::now(),
+            skipped: 0,
+            creation: now,
+            creation_seq: 0,
+            stale_since: now,
             fields,
             metadata,
+            first_timestamp: None,
+            last_timestamp: None,
+            timestamp_bounds: None,
+            expire_after,
+            last_value: None,
+            size: 0,
+            group,
+            last_sequence: None,
+            sum: 0.0,
+            field_values: Vec::new(),
+            fields_present: Vec::new(),
+            schema: None,
+            held: Vec::new(),
+            latest_reorder_time: None,
+            paused: false,
+            max_fields,
+            timestamp_strategy,
+            provenance: provenance.then(HashMap::new),
+            snapshot_seq: 0,
+            last_snapshot: now,
+            last_checkpoint: 0,
         }
     }
 
     fn add_event(&mut self, e: LogEvent, strategies: &IndexMap<KeyString, MergeStrategy>) {
-        let (value, metadata) = e.into_parts();
+        if let Some(Value::Timestamp(timestamp)) = e.get_timestamp() {
+            self.first_timestamp.get_or_insert(*timestamp);
+            self.last_timestamp = Some(*timestamp);
+        }
+
+        let (mut value, metadata) = e.into_parts();
         self.metadata.merge(metadata);
 
+        // Derived values are read before nested fields are taken out, as they may be read from
+        // nested fields.
+        let derived: Vec<_> = strategies
+            .iter()
+            .filter(|(_, s)| s.is_derived())
+            .filter_map(|(k, strategy)| {
+                strategy
+                    .derive_value(k.as_str(), &value)
+                    .map(|v| (k.clone(), v, strategy))
+            })
+            .collect();
+
+        // Nested fields with a strategy are taken out of the event, so that they are merged on
+        // their own rather than as part of their parent.
+        let mut nested = Vec::new();
+        for (k, strategy) in strategies {
+            if let Some(path) = nested_path(k) {
+                if let Some(v) = value.remove(&path, false) {
+                    if !strategy.is_derived() {
+                        nested.push((k.clone(), v, strategy));
+                    }
+                }
+            }
+        }
+
         let fields = if let Value::Object(fields) = value {
             fields
         } else {
             BTreeMap::new()
         };
 
+        for (k, v, strategy) in derived {
+            self.merge_value(k, v, Some(strategy));
+        }
+
+        for (k, v, strategy) in nested {
+            self.merge_value(k, v, Some(strategy));
+        }
+
         for (k, v) in fields.into_iter() {
             let strategy = strategies.get(&k);
-            match self.fields.entry(k) {
-                hash_map::Entry::Vacant(entry) => {
-                    if let Some(strat) = strategy {
-                        match get_value_merger(v, strat) {
-                            Ok(m) => {
-                                entry.insert(m);
-                            }
-                            Err(error) => {
-                                warn!(message = "Failed to merge value.", %error);
-                            }
+            if strategy.is_some_and(MergeStrategy::is_derived) {
+                continue;
+            }
+            // Context fields are taken from the event holding the value they are the context of.
+            if strategies
+                .values()
+                .any(|s| s.context_fields().iter().any(|field| *field == k.as_str()))
+            {
+                continue;
+            }
+            if let Value::Timestamp(timestamp) = v {
+                self.timestamp_bounds = Some(match self.timestamp_bounds {
+                    Some((min, max)) => (min.min(timestamp), max.max(timestamp)),
+                    None => (timestamp, timestamp),
+                });
+            }
+            self.merge_value(k, v, strategy);
+        }
+        self.events += 1;
+        self.stale_since = Instant::now();
+    }
+
+    /// Merges another group into this one, when both turn out to be the same group.
+    ///
+    /// The fields of both groups are combined by their mergers, and the rest of the state is
+    /// combined as if this group had received the events of both.
+    fn absorb(&mut self, other: ReduceState) {
+        self.events += other.events;
+        self.skipped += other.skipped;
+        self.size += other.size;
+        if other.creation < self.creation {
+            self.creation = other.creation;
+            self.creation_seq = other.creation_seq;
+        }
+        self.stale_since = self.stale_since.max(other.stale_since);
+        self.metadata.merge(other.metadata);
+        self.first_timestamp = match (self.first_timestamp, other.first_timestamp) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.last_timestamp = match (self.last_timestamp, other.last_timestamp) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        self.timestamp_bounds = match (self.timestamp_bounds, other.timestamp_bounds) {
+            (Some((min, max)), Some((other_min, other_max))) => {
+                Some((min.min(other_min), max.max(other_max)))
+            }
+            (a, b) => a.or(b),
+        };
+        self.sum += other.sum;
+        self.schema = self.schema.take().or(other.schema);
+        self.held.extend(other.held);
+        self.latest_reorder_time = self.latest_reorder_time.max(other.latest_reorder_time);
+
+        let len = self.field_values.len().max(other.field_values.len());
+        self.field_values.resize(len, None);
+        for (established, value) in self.field_values.iter_mut().zip(other.field_values) {
+            if established.is_none() {
+                *established = value;
+            }
+        }
+        let len = self.fields_present.len().max(other.fields_present.len());
+        self.fields_present.resize(len, false);
+        for (present, other_present) in self.fields_present.iter_mut().zip(other.fields_present) {
+            *present |= other_present;
+        }
+        if let (Some(provenance), Some(other_provenance)) =
+            (self.provenance.as_mut(), other.provenance)
+        {
+            for (k, (events, strategy)) in other_provenance {
+                provenance.entry(k).or_insert((0, strategy)).0 += events;
+            }
+        }
+
+        for (k, merger) in other.fields {
+            if let Some(existing) = self.fields.get_mut(&k) {
+                if let Err(error) = existing.merge(merger) {
+                    warn!(message = "Failed to merge value.", %error);
+                }
+                continue;
+            }
+            if let Some(max_fields) = self
+                .max_fields
+                .filter(|max_fields| self.fields.len() >= *max_fields)
+            {
+                emit!(ReduceFieldDropped {
+                    field: k.as_str(),
+                    max_fields,
+                });
+                continue;
+            }
+            self.fields.insert(k, merger);
+        }
+    }
+
+    /// Whether a merged field is full, in which case the group is flushed.
+    fn is_full(&self) -> bool {
+        self.fields.values().any(|merger| merger.is_full())
+    }
+
+    /// Keeps only the metadata of an event that was not sampled, so that it is still finalized
+    /// when the group is flushed.
+    fn skip_event(&mut self, e: LogEvent) {
+        let (_, metadata) = e.into_parts();
+        self.metadata.merge(metadata);
+        self.skipped += 1;
+        self.stale_since = Instant::now();
+    }
+
+    fn merge_value(&mut self, k: KeyString, v: Value, strategy: Option<&MergeStrategy>) {
+        if let Some(max_fields) = self.max_fields {
+            if self.fields.len() >= max_fields && !self.fields.contains_key(&k) {
+                emit!(ReduceFieldDropped {
+                    field: k.as_str(),
+                    max_fields,
+                });
+                return;
+            }
+        }
+
+        self.size += k.as_str().len() + v.size_of();
+        if let Some(provenance) = self.provenance.as_mut() {
+            provenance
+                .entry(k.clone())
+                .or_insert((0, strategy.map_or("default", MergeStrategy::name)))
+                .0 += 1;
+        }
+        match self.fields.entry(k) {
+            hash_map::Entry::Vacant(entry) => {
+                if let Some(strat) = strategy {
+                    match get_value_merger(v, strat) {
+                        Ok(m) => {
+                            entry.insert(m);
+                        }
+                        Err(error) => {
+                            warn!(message = "Failed to merge value.", %error);
                         }
-                    } else {
-                        entry.insert(v.clone().into());
                     }
+                } else {
+                    entry.insert(get_default_merger(v, self.timestamp_strategy));
                 }
-                hash_map::Entry::Occupied(mut entry) => {
-                    if let Err(error) = entry.get_mut().add(v.clone()) {
-                        warn!(message = "Failed to merge value.", %error);
-                    }
+            }
+            hash_map::Entry::Occupied(mut entry) => {
+                if let Err(error) = entry.get_mut().add(v) {
+                    warn!(message = "Failed to merge value.", %error);
                 }
             }
         }
-        self.events += 1;
-        self.stale_since = Instant::now();
+    }
+
+    /// Adds the event, unless it is identical to the previous event added to this state, ignoring
+    /// the timestamp, in which case only its metadata is kept.
+    fn add_event_coalesced(
+        &mut self,
+        e: LogEvent,
+        strategies: &IndexMap<KeyString, MergeStrategy>,
+    ) {
+        let mut without_timestamp = e.clone();
+        without_timestamp.remove_timestamp();
+        let (value, _) = without_timestamp.into_parts();
+
+        if self.last_value.as_ref() == Some(&value) {
+            self.metadata.merge(e.into_parts().1);
+            self.stale_since = Instant::now();
+        } else {
+            self.last_value = Some(value);
+            self.add_event(e, strategies);
+        }
+    }
+
+    /// Adds the event, holding it back to be merged in order of its timestamp when `reorder_by` is
+    /// set.
+    fn push_event(
+        &mut self,
+        e: LogEvent,
+        strategies: &IndexMap<KeyString, MergeStrategy>,
+        coalesce: bool,
+        reorder: Option<&(OwnedTargetPath, Duration)>,
+    ) {
+        let Some((field, window)) = reorder else {
+            self.add(e, strategies, coalesce);
+            return;
+        };
+        let Some(timestamp) = e.get(field).and_then(Value::as_timestamp).copied() else {
+            self.add(e, strategies, coalesce);
+            return;
+        };
+
+        let latest = self
+            .latest_reorder_time
+            .map_or(timestamp, |latest| latest.max(timestamp));
+        self.latest_reorder_time = Some(latest);
+        self.held.push((timestamp, e));
+        if let Some(until) = chrono::Duration::from_std(*window)
+            .ok()
+            .and_then(|window| latest.checked_sub_signed(window))
+        {
+            self.release_held(Some(until), strategies, coalesce);
+        }
+    }
+
+    /// Merges the held events with a timestamp up to `until`, or all of them, in chronological
+    /// order.
+    fn release_held(
+        &mut self,
+        until: Option<DateTime<Utc>>,
+        strategies: &IndexMap<KeyString, MergeStrategy>,
+        coalesce: bool,
+    ) {
+        if self.held.is_empty() {
+            return;
+        }
+        // The sort is stable, so events with the same timestamp keep their arrival order.
+        self.held.sort_by_key(|(timestamp, _)| *timestamp);
+        let count = match until {
+            Some(until) => self
+                .held
+                .partition_point(|(timestamp, _)| *timestamp <= until),
+            None => self.held.len(),
+        };
+        let released: Vec<_> = self.held.drain(..count).collect();
+        for (_, e) in released {
+            self.add(e, strategies, coalesce);
+        }
+    }
+
+    fn add(
+        &mut self,
+        e: LogEvent,
+        strategies: &IndexMap<KeyString, MergeStrategy>,
+        coalesce: bool,
+    ) {
+        if coalesce {
+            self.add_event_coalesced(e, strategies);
+        } else {
+            self.add_event(e, strategies);
+        }
+    }
+
+    /// Returns a copy of this state to flush as a snapshot, without the finalizers of its events.
+    fn snapshot(&mut self) -> Self {
+        let snapshot = self.partial();
+        self.snapshot_seq += 1;
+        self.last_snapshot = Instant::now();
+        snapshot
+    }
+
+    /// Returns a copy of this state to flush while the group stays open, without the finalizers
+    /// of its events.
+    fn partial(&self) -> Self {
+        let mut metadata = self.metadata.clone();
+        drop(metadata.take_finalizers());
+        Self {
+            events: self.events,
+            skipped: self.skipped,
+            fields: self.fields.clone(),
+            creation: self.creation,
+            creation_seq: self.creation_seq,
+            stale_since: self.stale_since,
+            metadata,
+            first_timestamp: self.first_timestamp,
+            last_timestamp: self.last_timestamp,
+            timestamp_bounds: self.timestamp_bounds,
+            expire_after: self.expire_after,
+            last_value: None,
+            size: self.size,
+            group: self.group.clone(),
+            last_sequence: self.last_sequence,
+            sum: self.sum,
+            field_values: self.field_values.clone(),
+            fields_present: self.fields_present.clone(),
+            schema: self.schema.clone(),
+            held: Vec::new(),
+            latest_reorder_time: self.latest_reorder_time,
+            paused: self.paused,
+            max_fields: self.max_fields,
+            timestamp_strategy: self.timestamp_strategy,
+            provenance: self.provenance.clone(),
+            snapshot_seq: self.snapshot_seq,
+            last_snapshot: self.last_snapshot,
+            last_checkpoint: self.last_checkpoint,
+        }
     }
 
     fn flush(mut self) -> LogEvent {
         let mut event = LogEvent::new_with_metadata(self.metadata);
+        let mut nested = Vec::new();
         for (k, v) in self.fields.drain() {
+            if let Some(path) = nested_path(&k) {
+                nested.push((k, path, v));
+                continue;
+            }
             if let Err(error) = v.insert_into(k, &mut event) {
                 warn!(message = "Failed to merge values for field.", %error);
             }
         }
+
+        // Nested fields are inserted last, so they are not overwritten by their parent.
+        for (k, path, v) in nested {
+            let mut merged = LogEvent::default();
+            if let Err(error) = v.insert_into(k.clone(), &mut merged) {
+                warn!(message = "Failed to merge values for field.", %error);
+                continue;
+            }
+            if let Some(value) = merged.remove(event_path!(k.as_str())) {
+                event.insert((PathPrefix::Event, &path), value);
+            }
+            // Fields written alongside the merged value, such as its unit, are kept as well.
+            for (field, value) in merged.all_event_fields().into_iter().flatten() {
+                if let Err(error) = event.parse_path_and_insert(field.as_str(), value.clone()) {
+                    warn!(message = "Failed to merge values for field.", %error);
+                }
+            }
+        }
+        if let Some(provenance) = self.provenance.take() {
+            let provenance = provenance
+                .into_iter()
+                .map(|(k, (events, strategy))| {
+                    let entry = ObjectMap::from([
+                        ("events".into(), Value::Integer(events as i64)),
+                        ("strategy".into(), Value::from(strategy)),
+                    ]);
+                    (k, Value::Object(entry))
+                })
+                .collect();
+            event.insert(event_path!("_provenance"), Value::Object(provenance));
+        }
         self.events = 0;
         event
     }
 }
 
+/// Returns the path of a `merge_strategies` key that refers to a nested field.
+fn nested_path(key: &str) -> Option<OwnedValuePath> {
+    if !key.contains(['.', '[']) {
+        return None;
+    }
+    parse_value_path(key)
+        .ok()
+        .filter(|path| path.segments.len() > 1)
+}
+
+/// Returns the value of a field named by a `merge_strategies` key or option, which refers to a
+/// nested field if it is a path.
+fn field_value<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match nested_path(key) {
+        Some(path) => value.get(&path),
+        None => value.as_object()?.get(key),
+    }
+}
+
+/// Returns a numeric value as a float.
+fn number_as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(i) => Some(*i as f64),
+        Value::Float(f) => Some(f.into_inner()),
+        _ => None,
+    }
+}
+
+/// Rounds each float in a value, including those nested in objects and arrays, to the given
+/// number of decimal places.
+fn round_floats(value: &mut Value, precision: u32) {
+    match value {
+        Value::Float(f) => {
+            let factor = 10f64.powi(i32::try_from(precision).unwrap_or(i32::MAX));
+            let rounded = (f.into_inner() * factor).round() / factor;
+            // very high precisions overflow the factor, leaving the value as is
+            if rounded.is_finite() {
+                *f = NotNan::new(rounded).expect("finite float is not NaN");
+            }
+        }
+        Value::Object(map) => map
+            .values_mut()
+            .for_each(|value| round_floats(value, precision)),
+        Value::Array(array) => array
+            .iter_mut()
+            .for_each(|value| round_floats(value, precision)),
+        _ => {}
+    }
+}
+
+/// The value of the `_lifecycle` field of the event emitted when a group is opened.
+const LIFECYCLE_START: &str = "start";
+
+/// The value of the `_lifecycle` field of the event emitted when a group is flushed.
+const LIFECYCLE_END: &str = "end";
+
+/// Emits a lifecycle event to the `lifecycle` output, carrying the values of the `group_by`
+/// fields of the group.
+fn emit_lifecycle_event(
+    emitter: &mut Emitter<(Option<String>, Event)>,
+    stage: &str,
+    group: Option<ObjectMap>,
+) {
+    let mut event = LogEvent::from_map(group.unwrap_or_default(), EventMetadata::default());
+    event.insert(event_path!("_lifecycle"), stage);
+    emitter.emit((Some(LIFECYCLE_OUTPUT.to_owned()), event.into()));
+}
+
+/// Returns the values of the `group_by` fields of an event, keyed by field.
+fn group_values(event: &LogEvent, group_by: &[String]) -> ObjectMap {
+    group_by
+        .iter()
+        .filter_map(|field| {
+            event
+                .parse_path_and_get_value(field.as_str())
+                .ok()
+                .flatten()
+                .map(|value| (field.as_str().into(), value.clone()))
+        })
+        .collect()
+}
+
+fn compile_flush_vrl(
+    source: &str,
+    enrichment_tables: &vector_lib::enrichment::TableRegistry,
+) -> crate::Result<Program> {
+    let functions = vrl::stdlib::all()
+        .into_iter()
+        .chain(vector_lib::enrichment::vrl_functions())
+        .chain(vector_vrl_functions::all())
+        .collect::<Vec<_>>();
+
+    let mut config = CompileConfig::default();
+    config.set_custom(enrichment_tables.clone());
+
+    let CompilationResult {
+        program,
+        warnings,
+        config: _,
+    } = compile_vrl(source, &functions, &TypeState::default(), config).map_err(|diagnostics| {
+        format!(
+            "invalid `flush_vrl`: {}",
+            Formatter::new(source, diagnostics).colored()
+        )
+    })?;
+
+    if !warnings.is_empty() {
+        let warnings = Formatter::new(source, warnings).colored().to_string();
+        warn!(message = "VRL compilation warning.", %warnings);
+    }
+
+    Ok(program)
+}
+
+/// Runs the `flush_vrl` program on a combined event, returning the event unchanged if the program
+/// fails.
+fn run_flush_vrl(program: &Program, event: LogEvent) -> Event {
+    let log_namespace = event.namespace();
+    let original = event.clone();
+    let mut target = VrlTarget::new(Event::from(event), program.info(), false);
+    match Runtime::default().resolve(&mut target, program, &TimeZone::default()) {
+        Ok(_) => match target.into_events(log_namespace) {
+            TargetEvents::One(event) => event,
+            TargetEvents::Logs(_) | TargetEvents::Traces(_) => {
+                warn!(message = "Flush VRL program must produce a single event.");
+                Event::from(original)
+            }
+        },
+        Err(error) => {
+            warn!(message = "Failed to run flush VRL program.", %error);
+            Event::from(original)
+        }
+    }
+}
+
+/// The summed and counted fields of the last combined event of a group, when
+/// `emit_delta_from_previous` is set.
+struct Baseline {
+    fields: ObjectMap,
+    size: usize,
+    flushed_at: Instant,
+}
+
+/// A command sent to a running `Reduce` transform through its control channel.
+#[derive(Clone, Debug)]
+pub enum ReduceControl {
+    /// Flushes all groups.
+    FlushAll,
+
+    /// Flushes the group with the given key, as returned by [`Reduce::group_key`].
+    FlushGroup(Discriminant),
+}
+
+/// An input of the transform loop: either an event or a command from the control channel.
+enum Message {
+    Event(Event),
+    Control(ReduceControl),
+    End,
+}
+
 pub struct Reduce {
     expire_after: Duration,
+    expire_after_field: Option<OwnedTargetPath>,
+    sticky_by: Option<OwnedTargetPath>,
+    sticky_groups: HashMap<String, Discriminant>,
+    alias_field: Option<OwnedTargetPath>,
     flush_period: Duration,
+    slow_threshold: Option<Duration>,
+    snapshot_interval: Option<Duration>,
+    max_lifetime: Option<Duration>,
+    quarantine_after: Option<Duration>,
+    warmup_until: Instant,
     group_by: Vec<String>,
+    output_label_keys: Vec<String>,
+    group_object_field: Option<String>,
+    synthesize_message_from_group: bool,
+    treat_empty_as_missing: bool,
+    group_by_coerce_to_string: bool,
     merge_strategies: IndexMap<KeyString, MergeStrategy>,
+    timestamp_strategy: TimestampStrategy,
+    fan_out: Vec<(OwnedTargetPath, Vec<OwnedTargetPath>)>,
     reduce_merge_states: HashMap<Discriminant, ReduceState>,
+    // The open groups, oldest first, so that the oldest can be evicted without scanning them all.
+    creation_order: BTreeMap<(Instant, u64), Discriminant>,
+    next_creation_seq: u64,
     ends_when: Option<Condition>,
     starts_when: Option<Condition>,
+    pause_when: Option<Condition>,
+    resume_when: Option<Condition>,
+    paused_events: PausedEvents,
+    condition_hint_field: Option<OwnedTargetPath>,
+    coalesce_consecutive: bool,
+    tee: bool,
+    span_fields: Option<SpanFieldNames>,
+    track_timestamp_bounds: bool,
+    flush_vrl: Option<Program>,
+    summary_template: Option<Template>,
+    float_precision: Option<u32>,
+    spill: Option<Spill>,
+    sequence_field: Option<OwnedTargetPath>,
+    sum_threshold: Option<(OwnedTargetPath, f64)>,
+    reorder: Option<(OwnedTargetPath, Duration)>,
+    flush_priority_field: Option<OwnedTargetPath>,
+    change_fields: Vec<OwnedTargetPath>,
+    required_fields: Vec<OwnedTargetPath>,
+    ends_when_match: Option<(OwnedTargetPath, Regex)>,
+    schema_field: Option<OwnedTargetPath>,
+    emit_metric: Option<MetricConfig>,
+    emit_lifecycle: bool,
+    emit_first_immediately: bool,
+    emit_provenance: bool,
     max_events: Option<usize>,
+    checkpoint_every: Option<usize>,
+    max_fields_per_group: Option<usize>,
+    max_total_bytes: Option<usize>,
+    max_group_bytes: Option<usize>,
+    flush_under_pressure_bytes: Option<usize>,
+    bypass_if_larger_than_bytes: Option<usize>,
+    total_bytes: usize,
+    control: Option<mpsc::Receiver<ReduceControl>>,
+    baselines: Option<HashMap<Discriminant, Baseline>>,
+    profiling: bool,
+    emit_age_metrics: bool,
+    sampler: Option<(f64, SmallRng)>,
+    routes: Vec<(String, Condition)>,
+    flush_when_full: bool,
 }
 
 impl Reduce {
@@ -335,142 +2053,1274 @@ impl Reduce {
             .as_ref()
             .map(|c| c.build(enrichment_tables))
             .transpose()?;
-        let group_by = config.group_by.clone().into_iter().collect();
+        let pause_when = config
+            .pause_when
+            .as_ref()
+            .map(|c| c.build(enrichment_tables))
+            .transpose()?;
+        let resume_when = config
+            .resume_when
+            .as_ref()
+            .map(|c| c.build(enrichment_tables))
+            .transpose()?;
+        if pause_when.is_some() && resume_when.is_none() {
+            return Err("`pause_when` requires `resume_when` to be set".into());
+        }
+        if let Some(rate) = config.sample_rate {
+            if !(rate > 0.0 && rate <= 1.0) {
+                return Err(format!(
+                    "`sample_rate` must be greater than 0 and at most 1, found: {}",
+                    rate
+                )
+                .into());
+            }
+        }
+
+        let routes = config
+            .route_when
+            .iter()
+            .map(|(name, condition)| {
+                condition
+                    .build(enrichment_tables)
+                    .map(|condition| (name.clone(), condition))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+        for (field, strategy) in &config.merge_strategies {
+            strategy.validate().map_err(|error| {
+                format!("invalid merge strategy for field `{}`: {}", field, error)
+            })?;
+        }
+
+        let mut merge_strategies = config.merge_strategies.clone();
+        let mut fan_out = Vec::with_capacity(config.fan_out_strategies.len());
+        for (field, strategies) in &config.fan_out_strategies {
+            let source = parse_target_path(field)
+                .map_err(|error| format!("invalid `fan_out_strategies` field: {}", error))?;
+            let mut outputs = Vec::with_capacity(strategies.len());
+            for FanOutStrategy { output, strategy } in strategies {
+                strategy.validate().map_err(|error| {
+                    format!("invalid merge strategy for field `{}`: {}", output, error)
+                })?;
+                if merge_strategies.contains_key(output) {
+                    return Err(format!(
+                        "`fan_out_strategies` output `{}` already has a merge strategy",
+                        output
+                    )
+                    .into());
+                }
+                outputs.push(
+                    parse_target_path(output).map_err(|error| {
+                        format!("invalid `fan_out_strategies` output: {}", error)
+                    })?,
+                );
+                merge_strategies.insert(output.clone(), strategy.clone());
+            }
+            fan_out.push((source, outputs));
+        }
+
+        let expire_after_field = config
+            .expire_after_field
+            .as_deref()
+            .map(parse_target_path)
+            .transpose()
+            .map_err(|error| format!("invalid `expire_after_field`: {}", error))?;
+        let sticky_by = config
+            .sticky_by
+            .as_deref()
+            .map(parse_target_path)
+            .transpose()
+            .map_err(|error| format!("invalid `sticky_by`: {}", error))?;
+        let alias_field = config
+            .alias_field
+            .as_deref()
+            .map(parse_target_path)
+            .transpose()
+            .map_err(|error| format!("invalid `alias_field`: {}", error))?;
+        let condition_hint_field = config
+            .condition_hint_field
+            .as_deref()
+            .map(parse_target_path)
+            .transpose()
+            .map_err(|error| format!("invalid `condition_hint_field`: {}", error))?;
+        let sequence_field = config
+            .flush_on_sequence_gap
+            .as_ref()
+            .map(|gap| parse_target_path(&gap.field))
+            .transpose()
+            .map_err(|error| format!("invalid `flush_on_sequence_gap.field`: {}", error))?;
+        let sum_threshold = config
+            .flush_on_sum
+            .as_ref()
+            .map(|sum| parse_target_path(&sum.field).map(|field| (field, sum.threshold)))
+            .transpose()
+            .map_err(|error| format!("invalid `flush_on_sum.field`: {}", error))?;
+        let reorder = config
+            .reorder_by
+            .as_ref()
+            .map(|field| parse_target_path(field))
+            .transpose()
+            .map_err(|error| format!("invalid `reorder_by`: {}", error))?
+            .map(|field| (field, config.reorder_window_ms));
+        let spill = config
+            .spill
+            .as_ref()
+            .map(|spill| {
+                parse_target_path(&spill.field).map(|field| Spill {
+                    field,
+                    threshold_bytes: spill.threshold_bytes,
+                    dir: spill.dir.clone().unwrap_or_else(std::env::temp_dir),
+                    writes: SpillWrites {
+                        pending: Arc::default(),
+                        remove_after: spill.remove_after_secs.map(Duration::from_secs),
+                    },
+                })
+            })
+            .transpose()
+            .map_err(|error| format!("invalid `spill.field`: {}", error))?;
+        let flush_priority_field = config
+            .flush_priority_field
+            .as_ref()
+            .map(|field| parse_target_path(field))
+            .transpose()
+            .map_err(|error| format!("invalid `flush_priority_field`: {}", error))?;
+        let change_fields = config
+            .flush_on_field_change
+            .iter()
+            .map(|field| parse_target_path(field))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| format!("invalid `flush_on_field_change`: {}", error))?;
+        let schema_field = config
+            .require_schema_field
+            .as_ref()
+            .map(|field| parse_target_path(field))
+            .transpose()
+            .map_err(|error| format!("invalid `require_schema_field`: {}", error))?;
+        let required_fields = config
+            .ends_when_fields_present
+            .iter()
+            .map(|field| parse_target_path(field))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| format!("invalid `ends_when_fields_present`: {}", error))?;
+        let ends_when_match = config
+            .ends_when_match
+            .as_ref()
+            .map(|matcher| {
+                let field = parse_target_path(&matcher.field)
+                    .map_err(|error| format!("invalid `ends_when_match.field`: {}", error))?;
+                let pattern = Regex::new(&matcher.pattern)
+                    .map_err(|error| format!("invalid `ends_when_match.pattern`: {}", error))?;
+                Ok::<_, String>((field, pattern))
+            })
+            .transpose()?;
+
+        if let Some(metric) = &config.emit_metric {
+            if let Some(tag) = metric
+                .tags
+                .iter()
+                .find(|tag| !config.group_by.contains(tag))
+            {
+                return Err(format!(
+                    "`emit_metric.tags` must only contain `group_by` fields, found: `{}`",
+                    tag
+                )
+                .into());
+            }
+        }
+
+        let flush_vrl = config
+            .flush_vrl
+            .as_deref()
+            .map(|source| compile_flush_vrl(source, enrichment_tables))
+            .transpose()?;
+
+        let group_by = config
+            .correlation_keys
+            .clone()
+            .unwrap_or_else(|| config.group_by.clone());
+        let output_label_keys = config
+            .output_label_keys
+            .clone()
+            .unwrap_or_else(|| config.group_by.clone());
         let max_events = config.max_events.map(|max| max.into());
 
+        let flush_when_full = merge_strategies
+            .values()
+            .any(MergeStrategy::flushes_when_full);
+
         Ok(Reduce {
             expire_after: config.expire_after_ms,
+            expire_after_field,
+            sticky_by,
+            sticky_groups: HashMap::new(),
+            alias_field,
             flush_period: config.flush_period_ms,
+            slow_threshold: config.slow_threshold_ms.map(Duration::from_millis),
+            snapshot_interval: config.snapshot_interval_ms.map(Duration::from_millis),
+            max_lifetime: config.max_lifetime_ms.map(Duration::from_millis),
+            quarantine_after: config.quarantine_after_ms.map(Duration::from_millis),
+            warmup_until: Instant::now() + config.warmup_ms,
             group_by,
-            merge_strategies: config.merge_strategies.clone(),
+            output_label_keys,
+            group_object_field: config.group_object_field.clone(),
+            synthesize_message_from_group: config.synthesize_message_from_group,
+            treat_empty_as_missing: config.treat_empty_as_missing,
+            group_by_coerce_to_string: config.group_by_coerce_to_string,
+            merge_strategies,
+            timestamp_strategy: config.timestamp_strategy,
+            fan_out,
             reduce_merge_states: HashMap::new(),
+            creation_order: BTreeMap::new(),
+            next_creation_seq: 0,
             ends_when,
             starts_when,
+            pause_when,
+            resume_when,
+            paused_events: config.paused_events,
+            condition_hint_field,
+            coalesce_consecutive: config.coalesce_consecutive,
+            tee: config.tee,
+            span_fields: config.span_fields.clone(),
+            track_timestamp_bounds: config.track_timestamp_bounds,
+            flush_vrl,
+            summary_template: config.summary_template.clone(),
+            float_precision: config.float_precision,
+            spill,
+            sequence_field,
+            sum_threshold,
+            reorder,
+            flush_priority_field,
+            change_fields,
+            required_fields,
+            ends_when_match,
+            schema_field,
+            emit_metric: config.emit_metric.clone(),
+            emit_lifecycle: config.emit_lifecycle,
+            emit_first_immediately: config.emit_first_immediately,
+            emit_provenance: config.emit_provenance,
             max_events,
+            checkpoint_every: config.checkpoint_every.map(NonZeroUsize::get),
+            max_fields_per_group: config.max_fields_per_group,
+            max_total_bytes: config.max_total_bytes,
+            max_group_bytes: config.max_group_bytes,
+            flush_under_pressure_bytes: config.flush_under_pressure_bytes,
+            bypass_if_larger_than_bytes: config.bypass_if_larger_than_bytes,
+            total_bytes: 0,
+            control: None,
+            baselines: config.emit_delta_from_previous.then(HashMap::new),
+            profiling: config.profiling,
+            emit_age_metrics: config.emit_age_metrics,
+            sampler: config.sample_rate.map(|rate| {
+                let rng = config
+                    .sample_seed
+                    .map_or_else(SmallRng::from_entropy, SmallRng::seed_from_u64);
+                (rate, rng)
+            }),
+            routes,
+            flush_when_full,
         })
     }
 
-    fn flush_into(&mut self, emitter: &mut Emitter<Event>) {
-        let mut flush_discriminants = Vec::new();
-        let now = Instant::now();
-        for (k, t) in &self.reduce_merge_states {
-            if (now - t.stale_since) >= self.expire_after {
-                flush_discriminants.push(k.clone());
-            }
+    /// Creates a control channel, which allows groups to be flushed on demand rather than waiting
+    /// for them to expire.
+    ///
+    /// This is meant for applications embedding the transform, for example to flush all groups
+    /// when the host is under memory pressure. Only the last channel created is used.
+    pub fn control_channel(&mut self, buffer: usize) -> mpsc::Sender<ReduceControl> {
+        let (tx, rx) = mpsc::channel(buffer);
+        self.control = Some(rx);
+        tx
+    }
+
+    /// Returns the key of the group the event belongs to.
+    pub fn group_key(&self, event: &LogEvent) -> Discriminant {
+        let mut discriminant = Discriminant::from_log_event(event, &self.group_by);
+        if self.group_by_coerce_to_string {
+            discriminant = discriminant.with_values_as_strings();
         }
-        for k in &flush_discriminants {
-            if let Some(t) = self.reduce_merge_states.remove(k) {
-                emit!(ReduceStaleEventFlushed);
-                emitter.emit(Event::from(t.flush()));
-            }
+        if self.treat_empty_as_missing {
+            discriminant = discriminant.with_empty_as_missing();
         }
+        discriminant
     }
 
-    fn flush_all_into(&mut self, emitter: &mut Emitter<Event>) {
-        self.reduce_merge_states
-            .drain()
-            .for_each(|(_, s)| emitter.emit(Event::from(s.flush())));
+    fn handle_control(
+        &mut self,
+        emitter: &mut Emitter<(Option<String>, Event)>,
+        control: ReduceControl,
+    ) {
+        match control {
+            ReduceControl::FlushAll => self.flush_all_into(emitter),
+            ReduceControl::FlushGroup(discriminant) => {
+                if let Some(state) = self.remove_state(&discriminant) {
+                    self.emit_state(emitter, &discriminant, state);
+                }
+            }
+        }
     }
 
-    fn push_or_new_reduce_state(&mut self, event: LogEvent, discriminant: Discriminant) {
-        match self.reduce_merge_states.entry(discriminant) {
+    /// Gives the event a message made of the values of its group, unless it has one already.
+    fn synthesize_message(&self, group: &ObjectMap, event: &mut LogEvent) {
+        if event.get_message().is_some() {
+            return;
+        }
+        let Some(path) = event.message_path().cloned() else {
+            return;
+        };
+        let message = self
+            .output_label_keys
+            .iter()
+            .filter_map(|key| {
+                group
+                    .get(key.as_str())
+                    .map(|value| format!("{}={}", key, value.to_string_lossy()))
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        event.insert(&path, message);
+    }
+
+    fn remove_state(&mut self, discriminant: &Discriminant) -> Option<ReduceState> {
+        let state = self.reduce_merge_states.remove(discriminant)?;
+        self.creation_order
+            .remove(&(state.creation, state.creation_seq));
+        self.total_bytes = self.total_bytes.saturating_sub(state.size);
+        Some(state)
+    }
+
+    /// Flushes a group into its combined event.
+    ///
+    /// The discriminant of the group is given for complete groups, as opposed to snapshots, so
+    /// that it can be used as the baseline of the next group when `emit_delta_from_previous` is
+    /// enabled.
+    fn flush_state(
+        &mut self,
+        mut state: ReduceState,
+        discriminant: Option<&Discriminant>,
+    ) -> Event {
+        state.release_held(None, &self.merge_strategies, self.coalesce_consecutive);
+        let span = state.first_timestamp.zip(state.last_timestamp);
+        let group = state.group.take();
+        let timestamp_bounds = state.timestamp_bounds;
+        let mut event = state.flush();
+        self.scale_sampled(&mut event);
+        if let Some(discriminant) = discriminant {
+            self.subtract_baseline(discriminant, &mut event);
+        }
+        if let Some(precision) = self.float_precision {
+            round_floats(event.value_mut(), precision);
+        }
+        if let (Some(span_fields), Some((start, end))) = (&self.span_fields, span) {
+            span_fields.insert_into(start, end, &mut event);
+        }
+        if let (true, Some((min, max))) = (self.track_timestamp_bounds, timestamp_bounds) {
+            event.insert(event_path!("_min_timestamp"), Value::Timestamp(min));
+            event.insert(event_path!("_max_timestamp"), Value::Timestamp(max));
+        }
+        if let (true, Some(group)) = (self.synthesize_message_from_group, &group) {
+            self.synthesize_message(group, &mut event);
+        }
+        if let (Some(field), Some(group)) = (&self.group_object_field, group) {
+            if let Err(error) = event.parse_path_and_insert(field, Value::Object(group)) {
+                warn!(message = "Failed to insert group object field.", field = %field, %error);
+            }
+        }
+        // Snapshots and checkpoints are partial, so only complete groups are spilled.
+        if let (Some(spill), Some(_)) = (&self.spill, discriminant) {
+            spill.spill(&mut event);
+        }
+        if let Some(template) = &self.summary_template {
+            match template.render_string(&event) {
+                Ok(summary) => {
+                    event.insert(event_path!("_summary"), summary);
+                }
+                Err(error) => emit!(TemplateRenderingError {
+                    error,
+                    field: Some("summary_template"),
+                    drop_event: false,
+                }),
+            }
+        }
+        match &self.flush_vrl {
+            Some(program) => run_flush_vrl(program, event),
+            None => Event::from(event),
+        }
+    }
+
+    /// Scales the summed and counted fields of a combined event by the inverse of the sample rate,
+    /// when `sample_rate` is set.
+    fn scale_sampled(&self, event: &mut LogEvent) {
+        let Some((rate, _)) = &self.sampler else {
+            return;
+        };
+        // Nested fields are only summed with a merge strategy, while top-level numeric fields are
+        // also summed by default.
+        let mut paths: Vec<OwnedValuePath> = self
+            .merge_strategies
+            .iter()
+            .filter(|(_, strategy)| strategy.accumulates())
+            .filter_map(|(k, _)| nested_path(k))
+            .collect();
+        if let Some(fields) = event.as_map() {
+            paths.extend(
+                fields
+                    .iter()
+                    .filter(|(k, v)| self.is_summed(k.as_str(), v))
+                    .map(|(k, _)| owned_value_path!(k.as_str())),
+            );
+        }
+        for path in paths {
+            match event.get_mut((PathPrefix::Event, &path)) {
+                Some(Value::Integer(i)) => *i = (*i as f64 / rate).round() as i64,
+                Some(Value::Float(f)) => {
+                    if let Ok(scaled) = NotNan::new(f.into_inner() / rate) {
+                        *f = scaled;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether a top-level field of a combined event holds a sum or a count: fields merged with
+    /// the `sum`, `sum_duration` or `count` strategies, and numeric fields without a merge
+    /// strategy, which are summed by default. The `group_by` fields, and the fields written by the
+    /// strategies of other fields, such as `keep_last_as`, never do.
+    fn is_summed(&self, key: &str, value: &Value) -> bool {
+        if self.group_by.iter().any(|field| field == key) {
+            return false;
+        }
+        let written_by_other = self
+            .merge_strategies
+            .iter()
+            .any(|(k, strategy)| match strategy {
+                MergeStrategy::Sum {
+                    keep_last_as: Some(last),
+                    ..
+                } => last == key,
+                MergeStrategy::DistinctCount => {
+                    key.strip_suffix("_distinct_count") == Some(k.as_str())
+                }
+                _ => false,
+            });
+        if written_by_other {
+            return false;
+        }
+        match self.merge_strategies.get(key) {
+            Some(strategy) => strategy.accumulates(),
+            None => matches!(value, Value::Integer(_) | Value::Float(_)),
+        }
+    }
+
+    /// Replaces the summed and counted fields of a combined event with their difference from the
+    /// baseline of its group, and makes the fields the new baseline, when
+    /// `emit_delta_from_previous` is set.
+    fn subtract_baseline(&mut self, discriminant: &Discriminant, event: &mut LogEvent) {
+        if self.baselines.is_none() {
+            return;
+        }
+        let Some(fields) = event.as_map_mut() else {
+            return;
+        };
+
+        let current: ObjectMap = fields
+            .iter()
+            .filter(|(key, value)| {
+                matches!(value, Value::Integer(_) | Value::Float(_))
+                    && self.is_summed(key.as_str(), value)
+            })
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        let size = current
+            .iter()
+            .map(|(key, value)| key.len() + value.size_of())
+            .sum();
+        let baseline = Baseline {
+            fields: current,
+            size,
+            flushed_at: Instant::now(),
+        };
+        self.total_bytes += size;
+        let Some(baseline) = self
+            .baselines
+            .as_mut()
+            .and_then(|baselines| baselines.insert(discriminant.clone(), baseline))
+        else {
+            return;
+        };
+        self.total_bytes = self.total_bytes.saturating_sub(baseline.size);
+
+        for (key, value) in fields.iter_mut() {
+            let delta = match (&*value, baseline.fields.get(key)) {
+                (Value::Integer(current), Some(Value::Integer(previous))) => {
+                    Some(Value::Integer(current.saturating_sub(*previous)))
+                }
+                (current, Some(previous)) => {
+                    match (number_as_f64(current), number_as_f64(previous)) {
+                        (Some(current), Some(previous)) => {
+                            NotNan::new(current - previous).ok().map(Value::Float)
+                        }
+                        _ => None,
+                    }
+                }
+                (_, None) => None,
+            };
+            if let Some(delta) = delta {
+                *value = delta;
+            }
+        }
+    }
+
+    /// Emits the combined event of a group, along with its metric when `emit_metric` is set.
+    fn emit_state(
+        &mut self,
+        emitter: &mut Emitter<(Option<String>, Event)>,
+        discriminant: &Discriminant,
+        state: ReduceState,
+    ) {
+        let group = self.emit_lifecycle.then(|| state.group.clone());
+        let event = self.flush_state(state, Some(discriminant));
+        self.emit_event(emitter, event);
+        if let Some(group) = group {
+            emit_lifecycle_event(emitter, LIFECYCLE_END, group);
+        }
+    }
+
+    /// Emits the combined event of a group completed by `ends_when` or `starts_when`, marking it
+    /// as slow if it was open for longer than `slow_threshold_ms`.
+    fn emit_completed_state(
+        &mut self,
+        emitter: &mut Emitter<(Option<String>, Event)>,
+        discriminant: &Discriminant,
+        state: ReduceState,
+    ) {
+        let duration = state.creation.elapsed();
+        let group = self.emit_lifecycle.then(|| state.group.clone());
+        let mut event = self.flush_state(state, Some(discriminant));
+        if let Some(threshold) = self
+            .slow_threshold
+            .filter(|threshold| duration > *threshold)
+        {
+            emit!(ReduceSlowTransactionCompleted {
+                duration,
+                threshold,
+            });
+            event.as_mut_log().insert(event_path!("_slow"), true);
+        }
+        self.emit_event(emitter, event);
+        if let Some(group) = group {
+            emit_lifecycle_event(emitter, LIFECYCLE_END, group);
+        }
+    }
+
+    fn emit_event(&self, emitter: &mut Emitter<(Option<String>, Event)>, mut event: Event) {
+        if let Some(metric) = &self.emit_metric {
+            let metric = metric.build(event.as_log());
+            emitter.emit((Some(METRICS_OUTPUT.to_owned()), metric.into()));
+        }
+        for (name, condition) in &self.routes {
+            let (matched, checked) = condition.check(event);
+            event = checked;
+            if matched {
+                emitter.emit((Some(name.clone()), event));
+                return;
+            }
+        }
+        emitter.emit((None, event));
+    }
+
+    fn flush_into(&mut self, emitter: &mut Emitter<(Option<String>, Event)>) {
+        let mut flush_discriminants = Vec::new();
+        let mut quarantine_discriminants = Vec::new();
+        let now = Instant::now();
+        if now >= self.warmup_until {
+            for (k, t) in &self.reduce_merge_states {
+                if self
+                    .quarantine_after
+                    .is_some_and(|quarantine_after| (now - t.creation) >= quarantine_after)
+                {
+                    quarantine_discriminants.push(k.clone());
+                    continue;
+                }
+                // Paused groups don't expire, but are still bound by `max_lifetime_ms`.
+                let stale = !t.paused && (now - t.stale_since) >= t.expire_after;
+                let too_old = self
+                    .max_lifetime
+                    .is_some_and(|max_lifetime| (now - t.creation) >= max_lifetime);
+                if stale || too_old {
+                    flush_discriminants.push((k.clone(), stale));
+                }
+            }
+        }
+        let mut flushed = Vec::new();
+        for (k, stale) in &flush_discriminants {
+            if let Some(t) = self.remove_state(k) {
+                if *stale {
+                    emit!(ReduceStaleEventFlushed);
+                }
+                let group = self.emit_lifecycle.then(|| t.group.clone());
+                flushed.push((self.flush_state(t, Some(k)), group));
+            }
+        }
+        if !flushed.is_empty() {
+            emit!(ReduceGroupsFlushed {
+                count: flushed.len(),
+            });
+        }
+        if let Some(field) = &self.flush_priority_field {
+            // highest priority first, with events missing the field last
+            flushed.sort_by(|(a, _), (b, _)| {
+                let priority = |event: &Event| {
+                    event
+                        .as_log()
+                        .get(field)
+                        .filter(|value| !value.is_null())
+                        .cloned()
+                };
+                match (priority(a), priority(b)) {
+                    (Some(a), Some(b)) => compare_values(&b, &a),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+            });
+        }
+        for (event, group) in flushed {
+            self.emit_event(emitter, event);
+            if let Some(group) = group {
+                emit_lifecycle_event(emitter, LIFECYCLE_END, group);
+            }
+        }
+        for k in &quarantine_discriminants {
+            if let Some(t) = self.remove_state(k) {
+                let group = self.emit_lifecycle.then(|| t.group.clone());
+                let mut event = self.flush_state(t, Some(k));
+                event.as_mut_log().insert(event_path!("_incomplete"), true);
+                emitter.emit((Some(QUARANTINE_OUTPUT.to_owned()), event));
+                if let Some(group) = group {
+                    emit_lifecycle_event(emitter, LIFECYCLE_END, group);
+                }
+            }
+        }
+
+        self.expire_baselines(now);
+        self.evict_oldest_into(emitter, self.flush_under_pressure_bytes);
+        self.snapshot_into(emitter, now);
+
+        if self.emit_age_metrics {
+            for state in self.reduce_merge_states.values() {
+                emit!(ReduceOpenGroupAge {
+                    age: now - state.creation,
+                });
+            }
+        }
+
+        let states = &self.reduce_merge_states;
+        self.sticky_groups
+            .retain(|_, discriminant| states.contains_key(discriminant));
+    }
+
+    /// Emits a snapshot of each open group whose last snapshot is older than
+    /// `snapshot_interval_ms`, if set.
+    fn snapshot_into(&mut self, emitter: &mut Emitter<(Option<String>, Event)>, now: Instant) {
+        let Some(interval) = self.snapshot_interval else {
+            return;
+        };
+
+        let mut snapshots = Vec::new();
+        for state in self.reduce_merge_states.values_mut() {
+            if (now - state.last_snapshot) >= interval {
+                snapshots.push(state.snapshot());
+            }
+        }
+        for snapshot in snapshots {
+            let seq = snapshot.snapshot_seq;
+            let mut event = self.flush_state(snapshot, None);
+            event
+                .as_mut_log()
+                .insert(event_path!("_snapshot_seq"), Value::Integer(seq as i64));
+            emitter.emit((None, event));
+        }
+    }
+
+    /// Forgets the baselines of groups that have not been open for `expire_after_ms`.
+    fn expire_baselines(&mut self, now: Instant) {
+        let Some(baselines) = self.baselines.as_mut() else {
+            return;
+        };
+        let states = &self.reduce_merge_states;
+        let expire_after = self.expire_after;
+        let mut freed = 0;
+        baselines.retain(|discriminant, baseline| {
+            let keep =
+                states.contains_key(discriminant) || (now - baseline.flushed_at) < expire_after;
+            if !keep {
+                freed += baseline.size;
+            }
+            keep
+        });
+        self.total_bytes = self.total_bytes.saturating_sub(freed);
+    }
+
+    fn flush_all_into(&mut self, emitter: &mut Emitter<(Option<String>, Event)>) {
+        self.total_bytes = 0;
+        self.creation_order.clear();
+        let states: Vec<_> = self.reduce_merge_states.drain().collect();
+        for (discriminant, state) in states {
+            self.emit_state(emitter, &discriminant, state);
+        }
+    }
+
+    /// Flushes the oldest groups until the approximate size of all groups is within
+    /// `max_total_bytes`, if given.
+    fn evict_oldest_into(
+        &mut self,
+        emitter: &mut Emitter<(Option<String>, Event)>,
+        max_total_bytes: Option<usize>,
+    ) {
+        let max_total_bytes = match max_total_bytes {
+            Some(max_total_bytes) => max_total_bytes,
+            None => return,
+        };
+
+        while self.total_bytes > max_total_bytes {
+            let oldest = self.creation_order.values().next().cloned();
+
+            match oldest.and_then(|discriminant| {
+                self.remove_state(&discriminant)
+                    .map(|state| (discriminant, state))
+            }) {
+                Some((discriminant, state)) => {
+                    emit!(ReduceGroupEvicted {
+                        total_bytes: self.total_bytes + state.size,
+                        max_total_bytes,
+                    });
+                    self.emit_state(emitter, &discriminant, state);
+                }
+                None => {
+                    // Only baselines are left, so they are forgotten as well.
+                    if let Some(baselines) = self.baselines.as_mut() {
+                        baselines.clear();
+                    }
+                    self.total_bytes = 0;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Returns the group of the first event with the same `sticky_by` value as this event, if
+    /// there is one, or else the given group, which later events with this value then stick to.
+    fn sticky_group_key(&mut self, event: &LogEvent, discriminant: Discriminant) -> Discriminant {
+        let Some(value) = self.sticky_by.as_ref().and_then(|field| event.get(field)) else {
+            return discriminant;
+        };
+        self.sticky_groups
+            .entry(value.to_string_lossy().into_owned())
+            .or_insert(discriminant)
+            .clone()
+    }
+
+    /// Merges the group named by the `alias_field` of the event, if it is open, into the group of
+    /// the event.
+    fn consolidate_alias(&mut self, event: &LogEvent, discriminant: &Discriminant) {
+        let Some(Value::Object(alias)) =
+            self.alias_field.as_ref().and_then(|field| event.get(field))
+        else {
+            return;
+        };
+        let mut aliased = event.clone();
+        for field in &self.group_by {
+            if let Some(value) = alias.get(field.as_str()) {
+                // Fields that are not valid paths are missing from every group key anyway.
+                let _ = aliased.parse_path_and_insert(field.as_str(), value.clone());
+            }
+        }
+        let alias_key = self.group_key(&aliased);
+        if alias_key == *discriminant {
+            return;
+        }
+        let Some(mut other) = self.remove_state(&alias_key) else {
+            return;
+        };
+
+        self.total_bytes += other.size;
+        for group in self.sticky_groups.values_mut() {
+            if *group == alias_key {
+                *group = discriminant.clone();
+            }
+        }
+        let state = match self.reduce_merge_states.get_mut(discriminant) {
+            Some(state) => {
+                // Absorbing an older group makes this one as old as it.
+                self.creation_order
+                    .remove(&(state.creation, state.creation_seq));
+                state.absorb(other);
+                state
+            }
+            None => {
+                if other.group.is_some() {
+                    other.group = Some(group_values(event, &self.output_label_keys));
+                }
+                self.reduce_merge_states
+                    .entry(discriminant.clone())
+                    .or_insert(other)
+            }
+        };
+        self.creation_order
+            .insert((state.creation, state.creation_seq), discriminant.clone());
+    }
+
+    /// Reads the expiry hint from `expire_after_field`, if present on the event.
+    fn expire_after_hint(&self, event: &LogEvent) -> Option<Duration> {
+        let field = self.expire_after_field.as_ref()?;
+        match event.get(field)? {
+            Value::Integer(ms) => u64::try_from(*ms).ok().map(Duration::from_millis),
+            Value::Float(ms) => Duration::try_from_secs_f64(ms.into_inner() / 1000.0).ok(),
+            _ => None,
+        }
+    }
+
+    fn sequence_of(&self, event: &LogEvent) -> Option<i64> {
+        let field = self.sequence_field.as_ref()?;
+        event.get(field)?.as_integer()
+    }
+
+    /// Whether the sequence number of the event does not follow the last one of its group.
+    fn is_sequence_gap(&self, event: &LogEvent, discriminant: &Discriminant) -> bool {
+        let last = self
+            .reduce_merge_states
+            .get(discriminant)
+            .and_then(|state| state.last_sequence);
+        match (last, self.sequence_of(event)) {
+            (Some(last), Some(sequence)) => last.checked_add(1) != Some(sequence),
+            _ => false,
+        }
+    }
+
+    /// The value of the `flush_on_sum` field of the event, if it is numeric.
+    fn sum_value_of(&self, event: &LogEvent) -> Option<f64> {
+        let (field, _) = self.sum_threshold.as_ref()?;
+        event.get(field).and_then(number_as_f64)
+    }
+
+    /// Whether the event brings the `flush_on_sum` sum of its group above the threshold.
+    fn crosses_sum_threshold(&self, event: &LogEvent, discriminant: &Discriminant) -> bool {
+        let (Some((_, threshold)), Some(value)) = (&self.sum_threshold, self.sum_value_of(event))
+        else {
+            return false;
+        };
+        let sum = self
+            .reduce_merge_states
+            .get(discriminant)
+            .map_or(0.0, |state| state.sum);
+        sum + value > *threshold
+    }
+
+    /// Whether a `flush_on_field_change` field of the event differs from the value established by
+    /// its group.
+    fn is_field_change(&self, event: &LogEvent, discriminant: &Discriminant) -> bool {
+        let Some(state) = self.reduce_merge_states.get(discriminant) else {
+            return false;
+        };
+        state
+            .field_values
+            .iter()
+            .zip(&self.change_fields)
+            .any(
+                |(established, field)| match (established, event.get(field)) {
+                    (Some(established), Some(value)) => established != value,
+                    _ => false,
+                },
+            )
+    }
+
+    /// Whether the value of the `require_schema_field` field of the event differs from the one
+    /// its group is locked to.
+    fn is_schema_mismatch(&self, event: &LogEvent, discriminant: &Discriminant) -> bool {
+        let (Some(field), Some(state)) = (
+            &self.schema_field,
+            self.reduce_merge_states.get(discriminant),
+        ) else {
+            return false;
+        };
+        event.get(field) != state.schema.as_ref()
+    }
+
+    /// Whether the event provides the last of the `ends_when_fields_present` fields not yet seen
+    /// by its group.
+    fn completes_required_fields(&self, event: &LogEvent, discriminant: &Discriminant) -> bool {
+        if self.required_fields.is_empty() {
+            return false;
+        }
+        let present = self
+            .reduce_merge_states
+            .get(discriminant)
+            .map(|state| state.fields_present.as_slice())
+            .unwrap_or_default();
+        self.required_fields
+            .iter()
+            .enumerate()
+            .all(|(i, field)| present.get(i).copied().unwrap_or(false) || event.contains(field))
+    }
+
+    /// Whether the `ends_when_match` field of the event matches its pattern.
+    fn matches_end_pattern(&self, event: &LogEvent) -> bool {
+        let Some((field, pattern)) = &self.ends_when_match else {
+            return false;
+        };
+        event
+            .get(field)
+            .is_some_and(|value| pattern.is_match(&value.to_string_lossy()))
+    }
+
+    /// Copies each field with fan-out strategies to the output fields of its strategies, so that
+    /// each is merged on its own.
+    fn fan_out_fields(&self, event: &mut LogEvent) {
+        for (source, outputs) in &self.fan_out {
+            if let Some(value) = event.get(source).cloned() {
+                for output in outputs {
+                    event.insert(output, value.clone());
+                }
+            }
+        }
+    }
+
+    fn push_or_new_reduce_state(
+        &mut self,
+        emitter: &mut Emitter<(Option<String>, Event)>,
+        event: LogEvent,
+        discriminant: Discriminant,
+    ) {
+        let expire_after_hint = self.expire_after_hint(&event);
+        let sequence = self.sequence_of(&event);
+        let sum_value = self.sum_value_of(&event);
+        let emit_lifecycle = self.emit_lifecycle;
+        let max_group_bytes = self.max_group_bytes;
+        let full_key =
+            (self.flush_when_full || max_group_bytes.is_some()).then(|| discriminant.clone());
+        let state = match self.reduce_merge_states.entry(discriminant) {
+            hash_map::Entry::Occupied(entry) => entry.into_mut(),
             hash_map::Entry::Vacant(entry) => {
-                let mut state = ReduceState::new();
-                state.add_event(event, &self.merge_strategies);
-                entry.insert(state);
+                let mut state = ReduceState::new(
+                    self.expire_after,
+                    (self.group_object_field.is_some()
+                        || self.synthesize_message_from_group
+                        || emit_lifecycle)
+                        .then(|| group_values(&event, &self.output_label_keys)),
+                    self.max_fields_per_group,
+                    self.timestamp_strategy,
+                    self.emit_provenance,
+                );
+                state.creation_seq = self.next_creation_seq;
+                self.next_creation_seq += 1;
+                self.creation_order
+                    .insert((state.creation, state.creation_seq), entry.key().clone());
+                state.schema = self
+                    .schema_field
+                    .as_ref()
+                    .and_then(|field| event.get(field).cloned());
+                if emit_lifecycle {
+                    emit_lifecycle_event(emitter, LIFECYCLE_START, state.group.clone());
+                }
+                if self.emit_first_immediately {
+                    emitter.emit((Some(FIRST_OUTPUT.to_owned()), event.clone().into()));
+                }
+                entry.insert(state)
             }
-            hash_map::Entry::Occupied(mut entry) => {
-                entry.get_mut().add_event(event, &self.merge_strategies);
+        };
+        if let Some(expire_after) = expire_after_hint {
+            state.expire_after = expire_after;
+        }
+        if sequence.is_some() {
+            state.last_sequence = sequence;
+        }
+        if let Some(value) = sum_value {
+            state.sum += value;
+        }
+        if !self.change_fields.is_empty() {
+            state.field_values.resize(self.change_fields.len(), None);
+            for (established, field) in state.field_values.iter_mut().zip(&self.change_fields) {
+                if established.is_none() {
+                    *established = event.get(field).cloned();
+                }
+            }
+        }
+        if !self.required_fields.is_empty() {
+            state
+                .fields_present
+                .resize(self.required_fields.len(), false);
+            for (present, field) in state.fields_present.iter_mut().zip(&self.required_fields) {
+                *present |= event.contains(field);
+            }
+        }
+        if let Some((rate, rng)) = self.sampler.as_mut() {
+            if !rng.gen_bool(*rate) {
+                state.skip_event(event);
+                return;
+            }
+        }
+        let size = state.size;
+        state.push_event(
+            event,
+            &self.merge_strategies,
+            self.coalesce_consecutive,
+            self.reorder.as_ref(),
+        );
+        self.total_bytes += state.size - size;
+
+        // Only checkpoint once the number of merged events reaches a new multiple, since it does
+        // not advance for coalesced events, and held events are not part of the partial state.
+        let checkpoint = self
+            .checkpoint_every
+            .filter(|every| state.events / every > state.last_checkpoint / every)
+            .map(|_| {
+                state.last_checkpoint = state.events;
+                state.partial()
+            });
+        let full = state.is_full() || max_group_bytes.is_some_and(|max| state.size > max);
+        if let Some(checkpoint) = checkpoint {
+            let event = self.flush_state(checkpoint, None);
+            emitter.emit((Some(CHECKPOINT_OUTPUT.to_owned()), event));
+        }
+
+        if let Some(discriminant) = full_key.filter(|_| full) {
+            if let Some(state) = self.remove_state(&discriminant) {
+                self.emit_state(emitter, &discriminant, state);
             }
         }
     }
 
-    pub(crate) fn transform_one(&mut self, emitter: &mut Emitter<Event>, event: Event) {
+    pub(crate) fn transform_one(
+        &mut self,
+        emitter: &mut Emitter<(Option<String>, Event)>,
+        event: Event,
+    ) {
+        if self.tee {
+            emitter.emit((Some(PASSTHROUGH_OUTPUT.to_owned()), event.clone()));
+        }
+
+        if self
+            .bypass_if_larger_than_bytes
+            .is_some_and(|max_bytes| event.size_of() > max_bytes)
+        {
+            emitter.emit((None, event));
+            return;
+        }
+
+        let check_conditions = self
+            .condition_hint_field
+            .as_ref()
+            .map_or(true, |field| event.as_log().contains(field));
+
         let (starts_here, event) = match &self.starts_when {
+            Some(condition) if check_conditions => condition.check(event),
+            _ => (false, event),
+        };
+
+        let (mut ends_here, event) = match &self.ends_when {
+            Some(condition) if check_conditions => condition.check(event),
+            _ => (false, event),
+        };
+
+        let (pauses_here, event) = match &self.pause_when {
             Some(condition) => condition.check(event),
             None => (false, event),
         };
 
-        let (mut ends_here, event) = match &self.ends_when {
+        let (resumes_here, event) = match &self.resume_when {
             Some(condition) => condition.check(event),
             None => (false, event),
         };
 
-        let event = event.into_log();
-        let discriminant = Discriminant::from_log_event(&event, &self.group_by);
+        let mut event = event.into_log();
+        let lookup_start = self.profiling.then(Instant::now);
+        let discriminant = self.group_key(&event);
+        let discriminant = self.sticky_group_key(&event, discriminant);
+        self.consolidate_alias(&event, &discriminant);
+        let mut group_events = self
+            .reduce_merge_states
+            .get(&discriminant)
+            .map(|state| state.events + state.held.len() + state.skipped);
+        if let Some(start) = lookup_start {
+            emit!(ReduceGroupLookup {
+                duration: start.elapsed(),
+            });
+        }
+
+        if let Some(state) = self.reduce_merge_states.get_mut(&discriminant) {
+            if state.paused && !resumes_here {
+                // Paused groups are kept open until they are resumed.
+                state.stale_since = Instant::now();
+                match self.paused_events {
+                    PausedEvents::Drop => emit!(ReducePausedEventDropped),
+                    // The event was already forwarded by `tee`.
+                    PausedEvents::Passthrough if self.tee => {}
+                    PausedEvents::Passthrough => {
+                        emitter.emit((Some(PASSTHROUGH_OUTPUT.to_owned()), event.into()))
+                    }
+                }
+                return;
+            }
+            state.paused = false;
+        }
+
+        if self.is_sequence_gap(&event, &discriminant)
+            || self.is_field_change(&event, &discriminant)
+        {
+            if let Some(state) = self.remove_state(&discriminant) {
+                self.emit_state(emitter, &discriminant, state);
+            }
+            group_events = None;
+        }
+
+        // An event starting a new group is not held to the schema of the group it completes.
+        if !starts_here && self.is_schema_mismatch(&event, &discriminant) {
+            emitter.emit((Some(REJECTED_OUTPUT.to_owned()), event.into()));
+            return;
+        }
 
+        self.fan_out_fields(&mut event);
+
+        ends_here |= self.completes_required_fields(&event, &discriminant);
+        ends_here |= self.matches_end_pattern(&event);
+        let ended_by_condition = ends_here;
+        if self.crosses_sum_threshold(&event, &discriminant) {
+            ends_here = true;
+        }
         if let Some(max_events) = self.max_events {
             if max_events == 1 {
                 ends_here = true;
-            } else if let Some(entry) = self.reduce_merge_states.get(&discriminant) {
+            } else if let Some(events) = group_events {
                 // The current event will finish this set
-                if entry.events + 1 == max_events {
+                if events + 1 == max_events {
                     ends_here = true;
                 }
             }
         }
 
+        let paused_group = pauses_here.then(|| discriminant.clone());
         if starts_here {
-            if let Some(state) = self.reduce_merge_states.remove(&discriminant) {
-                emitter.emit(state.flush().into());
+            if let Some(state) = self.remove_state(&discriminant) {
+                self.emit_completed_state(emitter, &discriminant, state);
             }
 
-            self.push_or_new_reduce_state(event, discriminant)
+            self.push_or_new_reduce_state(emitter, event, discriminant);
+            self.evict_oldest_into(emitter, self.max_total_bytes);
         } else if ends_here {
-            emitter.emit(match self.reduce_merge_states.remove(&discriminant) {
-                Some(mut state) => {
-                    state.add_event(event, &self.merge_strategies);
-                    state.flush().into()
+            let mut state = self.remove_state(&discriminant).unwrap_or_else(|| {
+                let state = ReduceState::new(
+                    self.expire_after,
+                    (self.group_object_field.is_some()
+                        || self.synthesize_message_from_group
+                        || self.emit_lifecycle)
+                        .then(|| group_values(&event, &self.output_label_keys)),
+                    self.max_fields_per_group,
+                    self.timestamp_strategy,
+                    self.emit_provenance,
+                );
+                if self.emit_lifecycle {
+                    emit_lifecycle_event(emitter, LIFECYCLE_START, state.group.clone());
                 }
-                None => {
-                    let mut state = ReduceState::new();
-                    state.add_event(event, &self.merge_strategies);
-                    state.flush().into()
+                if self.emit_first_immediately {
+                    emitter.emit((Some(FIRST_OUTPUT.to_owned()), event.clone().into()));
                 }
-            })
+                state
+            });
+            state.push_event(
+                event,
+                &self.merge_strategies,
+                self.coalesce_consecutive,
+                self.reorder.as_ref(),
+            );
+            if ended_by_condition {
+                self.emit_completed_state(emitter, &discriminant, state);
+            } else {
+                self.emit_state(emitter, &discriminant, state);
+            }
         } else {
-            self.push_or_new_reduce_state(event, discriminant)
+            self.push_or_new_reduce_state(emitter, event, discriminant);
+            self.evict_oldest_into(emitter, self.max_total_bytes);
+        }
+
+        if let Some(state) =
+            paused_group.and_then(|discriminant| self.reduce_merge_states.get_mut(&discriminant))
+        {
+            state.paused = true;
         }
     }
 }
 
-impl TaskTransform<Event> for Reduce {
+impl MultiOutputTaskTransform for Reduce {
     fn transform(
-        self: Box<Self>,
+        mut self: Box<Self>,
         input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
-    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
-    where
-        Self: 'static,
-    {
+    ) -> Pin<Box<dyn Stream<Item = (Option<String>, Event)> + Send>> {
         let flush_period = self.flush_period;
 
-        Box::pin(map_with_expiration(
+        // Control commands are selected on along with the events. The input ends with an `End`
+        // message, so the transform stops when its input does, even if the control channel is
+        // still open.
+        let input: Pin<Box<dyn Stream<Item = Message> + Send>> = match self.control.take() {
+            Some(control) => Box::pin(
+                stream::select(
+                    input_rx
+                        .map(Message::Event)
+                        .chain(stream::once(async { Message::End })),
+                    ReceiverStream::new(control).map(Message::Control),
+                )
+                .take_while(|message| futures::future::ready(!matches!(message, Message::End))),
+            ),
+            None => Box::pin(input_rx.map(Message::Event)),
+        };
+
+        let spill_writes = self.spill.as_ref().map(|spill| spill.writes.clone());
+
+        let output = map_with_expiration(
             self,
-            input_rx,
+            input,
             flush_period,
-            |me: &mut Box<Reduce>, event, emitter: &mut Emitter<Event>| {
-                // called for each event
-                me.transform_one(emitter, event);
+            |me: &mut Box<Reduce>, message, emitter: &mut Emitter<(Option<String>, Event)>| {
+                // called for each event or control command
+                match message {
+                    Message::Event(event) => me.transform_one(emitter, event),
+                    Message::Control(control) => me.handle_control(emitter, control),
+                    Message::End => {}
+                }
             },
-            |me: &mut Box<Reduce>, emitter: &mut Emitter<Event>| {
+            |me: &mut Box<Reduce>, emitter: &mut Emitter<(Option<String>, Event)>| {
                 // called periodically to check for expired events
                 me.flush_into(emitter);
             },
-            |me: &mut Box<Reduce>, emitter: &mut Emitter<Event>| {
+            |me: &mut Box<Reduce>, emitter: &mut Emitter<(Option<String>, Event)>| {
                 // called when the input stream ends
                 me.flush_all_into(emitter);
             },
-        ))
+        );
+
+        match spill_writes {
+            Some(spill_writes) => Box::pin(output.then(move |output| {
+                let writes = spill_writes.write_pending();
+                async move {
+                    writes.await;
+                    output
+                }
+            })),
+            None => Box::pin(output),
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
+    use chrono::TimeZone;
+    use futures::StreamExt;
     use serde_json::json;
     use std::sync::Arc;
     use tokio::sync::mpsc;
     use tokio_stream::wrappers::ReceiverStream;
     use vector_lib::enrichment::TableRegistry;
+    use vector_lib::event::{BatchNotifier, BatchStatus, EventStatus, Finalizable};
+    use vrl::btreemap;
     use vrl::value::Kind;
 
     use super::*;
@@ -485,6 +3335,19 @@ mod test {
         crate::test_util::test_generate_config::<ReduceConfig>();
     }
 
+    fn output_definition(reduce_config: &ReduceConfig, input_definition: Definition) -> Definition {
+        reduce_config.outputs(
+            TableRegistry::default(),
+            &[(OutputId::from("in"), input_definition)],
+            LogNamespace::Legacy,
+        )[0]
+        .clone()
+        .log_schema_definitions
+        .get(&OutputId::from("in"))
+        .unwrap()
+        .clone()
+    }
+
     #[tokio::test]
     async fn reduce_from_condition() {
         let reduce_config = toml::from_str::<ReduceConfig>(
@@ -747,6 +3610,42 @@ group_by = [ "request_id" ]
         .await;
     }
 
+    #[tokio::test]
+    async fn null_group_by_positions() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "host", "region" ]
+"#,
+        )
+        .unwrap();
+
+        let groups = [
+            (Value::Null, Value::from("x")),
+            (Value::from("x"), Value::Null),
+            (Value::Null, Value::from(false)),
+            (Value::from(false), Value::Null),
+        ];
+        let events = groups.iter().chain(groups.iter()).map(|(host, region)| {
+            let mut event = LogEvent::from("test message");
+            event.insert("host", host.clone());
+            event.insert("region", region.clone());
+            event.insert("counter", 1);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // Nulls in different positions are kept in separate groups.
+        assert_eq!(output.len(), groups.len());
+        for (_, event) in &output {
+            assert_eq!(event.as_log()["counter"], 2.into());
+        }
+    }
+
     #[tokio::test]
     async fn max_events_0() {
         let reduce_config = toml::from_str::<ReduceConfig>(
@@ -873,6 +3772,3116 @@ max_events = 3
         .await
     }
 
+    #[tokio::test]
+    async fn max_events_with_ends_when() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id" ]
+merge_strategies.id = "retain"
+merge_strategies.message = "array"
+max_events = 3
+
+[ends_when]
+  type = "vrl"
+  source = "exists(.test_end)"
+"#,
+        )
+        .unwrap();
+
+        assert_transform_compliance(async move {
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), reduce_config).await;
+
+            for (i, end) in [false, true, false, false, true, false]
+                .into_iter()
+                .enumerate()
+            {
+                let mut event = LogEvent::from(format!("test {}", i + 1));
+                event.insert("id", "1");
+                if end {
+                    event.insert("test_end", "yep");
+                }
+                tx.send(event.into()).await.unwrap();
+            }
+
+            // Ended by `ends_when` before reaching `max_events`.
+            let output_1 = out.recv().await.unwrap().into_log();
+            assert_eq!(output_1["message"], vec!["test 1", "test 2"].into());
+
+            // The end event is also the last event allowed by `max_events`, so it is merged and
+            // the group is flushed once.
+            let output_2 = out.recv().await.unwrap().into_log();
+            assert_eq!(
+                output_2["message"],
+                vec!["test 3", "test 4", "test 5"].into()
+            );
+
+            drop(tx);
+            let output_3 = out.recv().await.unwrap().into_log();
+            assert_eq!(output_3["message"], vec!["test 6"].into());
+
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn ema_strategy() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+
+merge_strategies.rate = { strategy = "ema", alpha = 0.5 }
+
+[ends_when]
+  type = "vrl"
+  source = "exists(.test_end)"
+"#,
+        )
+        .unwrap();
+
+        assert_transform_compliance(async move {
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), reduce_config).await;
+
+            let mut e_1 = LogEvent::from("test message 1");
+            e_1.insert("rate", 10);
+            e_1.insert("request_id", "1");
+            tx.send(e_1.into()).await.unwrap();
+
+            let mut e_2 = LogEvent::from("test message 2");
+            e_2.insert("rate", 20);
+            e_2.insert("request_id", "1");
+            tx.send(e_2.into()).await.unwrap();
+
+            let mut e_3 = LogEvent::from("test message 3");
+            e_3.insert("rate", 30);
+            e_3.insert("request_id", "1");
+            e_3.insert("test_end", "yep");
+            tx.send(e_3.into()).await.unwrap();
+
+            let output_1 = out.recv().await.unwrap().into_log();
+            assert_eq!(output_1["rate"], 22.5.into());
+
+            drop(tx);
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
+    #[test]
+    fn ema_invalid_alpha() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+merge_strategies.rate = { strategy = "ema", alpha = 1.5 }
+"#,
+        )
+        .unwrap();
+
+        assert!(Reduce::new(&reduce_config, &TableRegistry::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn max_total_bytes_evicts_oldest_group() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id" ]
+merge_strategies.message = "concat"
+max_total_bytes = 1500
+"#,
+        )
+        .unwrap();
+
+        assert_transform_compliance(async move {
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), reduce_config).await;
+
+            let mut e_1 = LogEvent::from("a".repeat(1000));
+            e_1.insert("id", "1");
+            tx.send(e_1.into()).await.unwrap();
+
+            // Adding a second group pushes the total over the limit, so the oldest group is
+            // flushed right away.
+            let mut e_2 = LogEvent::from("b".repeat(1000));
+            e_2.insert("id", "2");
+            tx.send(e_2.into()).await.unwrap();
+
+            let output_1 = out.recv().await.unwrap().into_log();
+            assert_eq!(output_1["id"], "1".into());
+            assert_eq!(output_1["message"], "a".repeat(1000).into());
+
+            drop(tx);
+            let output_2 = out.recv().await.unwrap().into_log();
+            assert_eq!(output_2["id"], "2".into());
+
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn max_total_bytes_evicts_aliased_group_by_age() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "session" ]
+alias_field = "alias_of"
+max_total_bytes = 1500
+"#,
+        )
+        .unwrap();
+
+        let mut events = [("a", 1), ("b", 1), ("d", 1), ("b", 1), ("c", 2000)]
+            .into_iter()
+            .map(|(session, len)| {
+                let mut e = LogEvent::from("x".repeat(len));
+                e.insert("session", session);
+                e.insert("counter", 1);
+                e
+            })
+            .collect::<Vec<_>>();
+        // Session `b` absorbs session `a`, which makes it older than session `d`.
+        events[3].insert("alias_of", Value::Object(btreemap! { "session" => "a" }));
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(
+                events.into_iter().map(Event::from),
+            )))
+            .collect()
+            .await;
+
+        let sessions: Vec<_> = output
+            .iter()
+            .map(|(_, event)| event.as_log()["session"].clone())
+            .collect();
+        assert_eq!(sessions, vec!["b".into(), "d".into(), "c".into()]);
+        assert_eq!(output[0].1.as_log()["counter"], 3.into());
+    }
+
+    #[tokio::test]
+    async fn bypass_if_larger_than_bytes() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id" ]
+merge_strategies.message = "concat"
+bypass_if_larger_than_bytes = 1000
+"#,
+        )
+        .unwrap();
+
+        let large = "b".repeat(2000);
+        let events = ["a", large.as_str(), "c"]
+            .into_iter()
+            .map(|message| {
+                let mut event = LogEvent::from(message);
+                event.insert("id", "1");
+                event.into()
+            })
+            .collect::<Vec<Event>>();
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // The oversized event is emitted right away, and the others are combined.
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0].0, None);
+        assert_eq!(output[0].1.as_log()["message"], large.into());
+        assert_eq!(output[1].0, None);
+        assert_eq!(output[1].1.as_log()["message"], "a c".into());
+    }
+
+    #[tokio::test]
+    async fn max_group_bytes() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id" ]
+merge_strategies.message = "array"
+max_group_bytes = 400
+"#,
+        )
+        .unwrap();
+
+        let messages = ["a", "b", "c"].map(|c| c.repeat(200));
+        let events = messages
+            .iter()
+            .map(|message| {
+                let mut event = LogEvent::from(message.as_str());
+                event.insert("id", "1");
+                event.into()
+            })
+            .collect::<Vec<Event>>();
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // The second event brings the group above the limit, so it is flushed with it, and the
+        // last event forms a group of its own.
+        assert_eq!(output.len(), 2);
+        assert_eq!(
+            output[0].1.as_log()["message"],
+            messages[..2].to_vec().into()
+        );
+        assert_eq!(
+            output[1].1.as_log()["message"],
+            messages[2..].to_vec().into()
+        );
+    }
+
+    #[tokio::test]
+    async fn span_fields() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+span_fields = {}
+
+[ends_when]
+  type = "vrl"
+  source = "exists(.test_end)"
+"#,
+        )
+        .unwrap();
+
+        assert_transform_compliance(async move {
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), reduce_config).await;
+
+            let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+            let end = start + chrono::Duration::milliseconds(1500);
+
+            let mut e_1 = LogEvent::from("test message 1");
+            e_1.insert("request_id", "1");
+            e_1.insert("timestamp", start);
+            tx.send(e_1.into()).await.unwrap();
+
+            let mut e_2 = LogEvent::from("test message 2");
+            e_2.insert("request_id", "1");
+            e_2.insert("timestamp", end);
+            e_2.insert("test_end", "yep");
+            tx.send(e_2.into()).await.unwrap();
+
+            let output = out.recv().await.unwrap().into_log();
+            assert_eq!(output["start_time"], Value::Timestamp(start));
+            assert_eq!(output["end_time"], Value::Timestamp(end));
+            assert_eq!(
+                output["duration_ms"],
+                Value::Integer((end - start).num_milliseconds())
+            );
+            assert_eq!(output["duration_ms"], 1500.into());
+
+            drop(tx);
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
+    #[test]
+    fn span_fields_schema() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+span_fields = { duration_ms = "span.duration_ms" }
+"#,
+        )
+        .unwrap();
+
+        let definition = output_definition(&reduce_config, Definition::default_legacy_namespace());
+        let kind_at = |path: OwnedValuePath| definition.event_kind().at_path(&path);
+
+        assert_eq!(
+            kind_at(owned_value_path!("start_time")),
+            Kind::timestamp().or_undefined()
+        );
+        assert_eq!(
+            kind_at(owned_value_path!("end_time")),
+            Kind::timestamp().or_undefined()
+        );
+        assert_eq!(
+            kind_at(owned_value_path!("span", "duration_ms")),
+            Kind::integer().or_undefined()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn expire_after_field_extends_group() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+expire_after_ms = 50
+flush_period_ms = 10
+expire_after_field = "ttl_ms"
+"#,
+        )
+        .unwrap();
+
+        assert_transform_compliance(async move {
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), reduce_config).await;
+
+            let mut e_1 = LogEvent::from("test message 1");
+            e_1.insert("request_id", "1");
+            e_1.insert("ttl_ms", 60_000);
+            tx.send(e_1.into()).await.unwrap();
+
+            let mut e_2 = LogEvent::from("test message 2");
+            e_2.insert("request_id", "2");
+            tx.send(e_2.into()).await.unwrap();
+
+            // Only the group without a hint expires after the default period.
+            let output_1 = out.recv().await.unwrap().into_log();
+            assert_eq!(output_1["request_id"], "2".into());
+            assert!(tokio::time::timeout(Duration::from_millis(200), out.recv())
+                .await
+                .is_err());
+
+            drop(tx);
+            let output_2 = out.recv().await.unwrap().into_log();
+            assert_eq!(output_2["request_id"], "1".into());
+
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn flush_priority_field() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+expire_after_ms = 10
+flush_period_ms = 200
+flush_priority_field = "severity"
+
+[merge_strategies]
+severity = "max"
+"#,
+        )
+        .unwrap();
+
+        assert_transform_compliance(async move {
+            let (tx, rx) = mpsc::channel(4);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), reduce_config).await;
+
+            // All groups are sent well within `flush_period_ms`, so they expire in the same flush.
+            for (request_id, severity) in
+                [("1", Some(2)), ("2", None), ("3", Some(7)), ("4", Some(4))]
+            {
+                let mut e = LogEvent::from("test message");
+                e.insert("request_id", request_id);
+                if let Some(severity) = severity {
+                    e.insert("severity", severity);
+                }
+                tx.send(e.into()).await.unwrap();
+            }
+
+            let mut request_ids = Vec::new();
+            for _ in 0..4 {
+                let output = out.recv().await.unwrap().into_log();
+                request_ids.push(output["request_id"].to_string_lossy().into_owned());
+            }
+            assert_eq!(request_ids, ["3", "4", "1", "2"]);
+
+            drop(tx);
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn max_lifetime_ms() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+expire_after_ms = 200
+flush_period_ms = 10
+max_lifetime_ms = 100
+"#,
+        )
+        .unwrap();
+
+        assert_transform_compliance(async move {
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), reduce_config).await;
+
+            // Events keep arriving well within `expire_after_ms`, so only the lifetime cap can
+            // flush the group before the input ends.
+            for _ in 0..10 {
+                let mut e = LogEvent::from("test message");
+                e.insert("request_id", "1");
+                e.insert("counter", 1);
+                tx.send(e.into()).await.unwrap();
+                tokio::time::sleep(Duration::from_millis(30)).await;
+            }
+            drop(tx);
+
+            let mut outputs = 0;
+            let mut total = 0;
+            while total < 10 {
+                let output = out.recv().await.unwrap().into_log();
+                outputs += 1;
+                total += output["counter"].as_integer().unwrap();
+            }
+            assert!(outputs >= 2);
+            assert_eq!(total, 10);
+
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn quarantine_after_ms() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id" ]
+expire_after_ms = 10000
+flush_period_ms = 10
+quarantine_after_ms = 50
+
+[ends_when]
+  type = "vrl"
+  source = ".done == true"
+"#,
+        )
+        .unwrap();
+
+        let events = [("1", false), ("2", false), ("2", true), ("1", false)]
+            .into_iter()
+            .map(|(id, done)| {
+                let mut e = LogEvent::from("test message");
+                e.insert("id", id);
+                e.insert("done", done);
+                e.insert("counter", 1);
+                Event::from(e)
+            })
+            .collect::<Vec<_>>();
+        // Keep the input open past `quarantine_after_ms`, so that the group that never completes
+        // gets stuck.
+        let input = futures::stream::iter(events).chain(
+            futures::stream::once(tokio::time::sleep(Duration::from_millis(200)))
+                .filter_map(|()| futures::future::ready(None)),
+        );
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce).transform(Box::pin(input)).collect().await;
+
+        assert_eq!(output.len(), 2);
+        let (port, completed) = &output[0];
+        assert_eq!(port, &None);
+        assert_eq!(completed.as_log()["id"], "2".into());
+        assert!(!completed.as_log().contains("_incomplete"));
+        let (port, stuck) = &output[1];
+        assert_eq!(port.as_deref(), Some(QUARANTINE_OUTPUT));
+        assert_eq!(stuck.as_log()["id"], "1".into());
+        assert_eq!(stuck.as_log()["counter"], 2.into());
+        assert_eq!(stuck.as_log()["_incomplete"], true.into());
+    }
+
+    #[tokio::test]
+    async fn coalesce_consecutive() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+coalesce_consecutive = true
+merge_strategies.message = "array"
+"#,
+        )
+        .unwrap();
+
+        assert_transform_compliance(async move {
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), reduce_config).await;
+
+            for message in ["A", "A", "B"] {
+                tx.send(LogEvent::from(message).into()).await.unwrap();
+            }
+
+            drop(tx);
+            let output = out.recv().await.unwrap().into_log();
+            assert_eq!(output["message"], vec!["A", "B"].into());
+
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn sum_duration_strategy() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+merge_strategies.active_ms = { strategy = "sum_duration", start = "started_at", end = "ended_at" }
+"#,
+        )
+        .unwrap();
+
+        assert_transform_compliance(async move {
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), reduce_config).await;
+
+            let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+            let mut e_1 = LogEvent::from("test message 1");
+            e_1.insert("request_id", "1");
+            e_1.insert("started_at", start);
+            e_1.insert("ended_at", start + chrono::Duration::milliseconds(250));
+            tx.send(e_1.into()).await.unwrap();
+
+            let mut e_2 = LogEvent::from("test message 2");
+            e_2.insert("request_id", "1");
+            e_2.insert("started_at", start + chrono::Duration::seconds(10));
+            e_2.insert("ended_at", start + chrono::Duration::milliseconds(11_500));
+            tx.send(e_2.into()).await.unwrap();
+
+            drop(tx);
+            let output = out.recv().await.unwrap().into_log();
+            assert_eq!(output["active_ms"], 1750.into());
+
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn nested_merge_strategies() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+merge_strategies."payload.counter" = "sum"
+"#,
+        )
+        .unwrap();
+
+        assert_transform_compliance(async move {
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), reduce_config).await;
+
+            let mut e_1 = LogEvent::from("test message 1");
+            e_1.insert("request_id", "1");
+            e_1.insert("payload.counter", 1);
+            e_1.insert("payload.name", "first");
+            tx.send(e_1.into()).await.unwrap();
+
+            let mut e_2 = LogEvent::from("test message 2");
+            e_2.insert("request_id", "1");
+            e_2.insert("payload.counter", 2);
+            e_2.insert("payload.name", "second");
+            tx.send(e_2.into()).await.unwrap();
+
+            drop(tx);
+            let output = out.recv().await.unwrap().into_log();
+            assert_eq!(output["payload.counter"], 3.into());
+            assert_eq!(output["payload.name"], "first".into());
+
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn tee_forwards_events_to_passthrough() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+tee = true
+"#,
+        )
+        .unwrap();
+
+        let mut e_1 = LogEvent::from("test message 1");
+        e_1.insert("counter", 1);
+        e_1.insert("request_id", "1");
+
+        let mut e_2 = LogEvent::from("test message 2");
+        e_2.insert("counter", 2);
+        e_2.insert("request_id", "1");
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let input = futures::stream::iter([Event::from(e_1), Event::from(e_2)]);
+        let output: Vec<_> = Box::new(reduce).transform(Box::pin(input)).collect().await;
+
+        let passthrough: Vec<_> = output
+            .iter()
+            .filter(|(port, _)| port.as_deref() == Some(PASSTHROUGH_OUTPUT))
+            .map(|(_, event)| event.as_log())
+            .collect();
+        assert_eq!(passthrough.len(), 2);
+        assert_eq!(passthrough[0]["message"], "test message 1".into());
+        assert_eq!(passthrough[0]["counter"], 1.into());
+        assert_eq!(passthrough[1]["message"], "test message 2".into());
+        assert_eq!(passthrough[1]["counter"], 2.into());
+
+        let reduced: Vec<_> = output
+            .iter()
+            .filter(|(port, _)| port.is_none())
+            .map(|(_, event)| event.as_log())
+            .collect();
+        assert_eq!(reduced.len(), 1);
+        assert_eq!(reduced[0]["message"], "test message 1".into());
+        assert_eq!(reduced[0]["counter"], 3.into());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn flush_under_pressure_bytes_flushes_oldest_groups() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id" ]
+flush_period_ms = 10
+flush_under_pressure_bytes = 1500
+"#,
+        )
+        .unwrap();
+
+        let (tx, rx) = mpsc::channel(3);
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let mut out = Box::new(reduce).transform(Box::pin(ReceiverStream::new(rx)));
+
+        for id in ["1", "2", "3"] {
+            let mut event = LogEvent::from("a".repeat(1000));
+            event.insert("id", id);
+            tx.send(event.into()).await.unwrap();
+        }
+
+        // Consume slowly, so the groups pile up before the first flush.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // The oldest groups are flushed long before they expire.
+        let (_, output_1) = out.next().await.unwrap();
+        assert_eq!(output_1.as_log()["id"], "1".into());
+        let (_, output_2) = out.next().await.unwrap();
+        assert_eq!(output_2.as_log()["id"], "2".into());
+        assert!(tokio::time::timeout(Duration::from_millis(100), out.next())
+            .await
+            .is_err());
+
+        drop(tx);
+        let (_, output_3) = out.next().await.unwrap();
+        assert_eq!(output_3.as_log()["id"], "3".into());
+        assert!(out.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn group_object_field() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id", "host" ]
+group_object_field = "group"
+"#,
+        )
+        .unwrap();
+
+        let mut e_1 = LogEvent::from("test message 1");
+        e_1.insert("request_id", "1");
+        e_1.insert("host", "a");
+        let mut e_2 = LogEvent::from("test message 2");
+        e_2.insert("request_id", "1");
+        e_2.insert("host", "a");
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(vec![
+                e_1.into(),
+                e_2.into(),
+            ])))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let (_, output) = &output[0];
+        assert_eq!(
+            output.as_log()["group"],
+            Value::Object(btreemap! {
+                "request_id" => "1",
+                "host" => "a",
+            })
+        );
+    }
+
+    #[test]
+    fn group_object_field_schema() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "host", "region" ]
+group_object_field = "group"
+"#,
+        )
+        .unwrap();
+
+        let input_definition = Definition::default_legacy_namespace()
+            .with_event_field(&owned_value_path!("host"), Kind::bytes(), None)
+            .with_event_field(
+                &owned_value_path!("region"),
+                Kind::bytes().or_undefined(),
+                None,
+            );
+        let definition = output_definition(&reduce_config, input_definition);
+
+        assert_eq!(
+            definition.event_kind().at_path(&owned_value_path!("group")),
+            Kind::object(Collection::from(BTreeMap::from([
+                ("host".into(), Kind::bytes()),
+                ("region".into(), Kind::bytes().or_undefined()),
+            ])))
+        );
+    }
+
+    #[tokio::test]
+    async fn synthesize_message_from_group() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "host", "region" ]
+synthesize_message_from_group = true
+"#,
+        )
+        .unwrap();
+
+        let events = [("a", "x"), ("a", "x"), ("b", "y")]
+            .into_iter()
+            .map(|(host, region)| {
+                let mut event = LogEvent::default();
+                event.insert("host", host);
+                event.insert("region", region);
+                event.insert("counter", 1);
+                Event::from(event)
+            });
+        let mut with_message = LogEvent::from("test message");
+        with_message.insert("host", "c");
+        with_message.insert("region", "z");
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let mut output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(
+                events.chain(std::iter::once(with_message.into())),
+            )))
+            .map(|(_, event)| event.into_log())
+            .collect()
+            .await;
+        output.sort_by_key(|log| log["host"].to_string_lossy().into_owned());
+
+        assert_eq!(output.len(), 3);
+        assert_eq!(output[0]["message"], "host=a region=x".into());
+        assert_eq!(output[0]["counter"], 2.into());
+        assert_eq!(output[1]["message"], "host=b region=y".into());
+        assert_eq!(output[2]["message"], "test message".into());
+    }
+
+    #[tokio::test]
+    async fn float_precision() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+float_precision = 2
+
+[merge_strategies]
+latency = { strategy = "ema", alpha = 0.3 }
+"#,
+        )
+        .unwrap();
+
+        let events = [(1.0, 0.1), (2.0, 0.2), (2.0, 0.0)]
+            .into_iter()
+            .map(|(latency, cost)| {
+                let mut event = LogEvent::from("test message");
+                event.insert("request_id", "1");
+                event.insert("latency", latency);
+                event.insert("cost", cost);
+                event.into()
+            })
+            .collect::<Vec<Event>>();
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let (_, output) = &output[0];
+        // 0.3 * 2 + 0.7 * (0.3 * 2 + 0.7 * 1) = 1.51, which is 1.5099999999999998 as a float
+        assert_eq!(output.as_log()["latency"], 1.51.into());
+        // 0.1 + 0.2 is 0.30000000000000004 as a float
+        assert_eq!(output.as_log()["cost"], 0.3.into());
+    }
+
+    #[tokio::test]
+    async fn correlation_keys() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+correlation_keys = [ "trace_id" ]
+output_label_keys = [ "service" ]
+group_object_field = "labels"
+"#,
+        )
+        .unwrap();
+
+        let events =
+            [("1", "api"), ("2", "api"), ("1", "api")]
+                .into_iter()
+                .map(|(trace_id, service)| {
+                    let mut event = LogEvent::from("test message");
+                    event.insert("trace_id", trace_id);
+                    event.insert("service", service);
+                    event.insert("counter", 1);
+                    Event::from(event)
+                });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 2);
+        let mut outputs: Vec<_> = output.iter().map(|(_, event)| event.as_log()).collect();
+        outputs.sort_by_key(|log| log["trace_id"].to_string_lossy().into_owned());
+        assert_eq!(outputs[0]["trace_id"], "1".into());
+        assert_eq!(outputs[0]["counter"], 2.into());
+        assert_eq!(outputs[1]["trace_id"], "2".into());
+        assert_eq!(outputs[1]["counter"], 1.into());
+        for log in outputs {
+            assert_eq!(
+                log["labels"],
+                Value::Object(btreemap! {
+                    "service" => "api",
+                })
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn summary_template() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+summary_template = "request {{ request_id }} took {{ duration_ms }}ms"
+
+[merge_strategies]
+duration_ms = "sum"
+"#,
+        )
+        .unwrap();
+
+        let events = [10, 25].into_iter().map(|duration_ms| {
+            let mut event = LogEvent::from("test message");
+            event.insert("request_id", "1");
+            event.insert("duration_ms", duration_ms);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let (_, output) = &output[0];
+        assert_eq!(output.as_log()["duration_ms"], 35.into());
+        assert_eq!(output.as_log()["_summary"], "request 1 took 35ms".into());
+    }
+
+    #[test]
+    fn summary_template_schema() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+summary_template = "request {{ request_id }}"
+"#,
+        )
+        .unwrap();
+
+        let definition = output_definition(&reduce_config, Definition::default_legacy_namespace());
+
+        assert_eq!(
+            definition
+                .event_kind()
+                .at_path(&owned_value_path!("_summary")),
+            Kind::bytes().or_undefined()
+        );
+    }
+
+    #[tokio::test]
+    async fn pause_when_resume_when() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+paused_events = "passthrough"
+
+[pause_when]
+  type = "vrl"
+  source = ".marker == \"pause\""
+
+[resume_when]
+  type = "vrl"
+  source = ".marker == \"resume\""
+
+[merge_strategies]
+counter = "sum"
+"#,
+        )
+        .unwrap();
+
+        let markers = [None, Some("pause"), None, None, Some("resume"), None];
+        let events = markers.into_iter().map(|marker| {
+            let mut event = LogEvent::from("test message");
+            event.insert("request_id", "1");
+            event.insert("counter", 1);
+            if let Some(marker) = marker {
+                event.insert("marker", marker);
+            }
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        let (passthrough, reduced): (Vec<_>, Vec<_>) = output
+            .iter()
+            .partition(|(port, _)| port.as_deref() == Some(PASSTHROUGH_OUTPUT));
+        assert_eq!(passthrough.len(), 2);
+        assert_eq!(reduced.len(), 1);
+        assert_eq!(reduced[0].1.as_log()["counter"], 4.into());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn paused_group_does_not_expire() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+expire_after_ms = 20
+flush_period_ms = 10
+
+[pause_when]
+  type = "vrl"
+  source = ".marker == \"pause\""
+
+[resume_when]
+  type = "vrl"
+  source = ".marker == \"resume\""
+
+[merge_strategies]
+counter = "sum"
+"#,
+        )
+        .unwrap();
+
+        assert_transform_compliance(async move {
+            let (tx, rx) = mpsc::channel(4);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), reduce_config).await;
+
+            for marker in [None, Some("pause")] {
+                let mut e = LogEvent::from("test message");
+                e.insert("request_id", "1");
+                e.insert("counter", 1);
+                if let Some(marker) = marker {
+                    e.insert("marker", marker);
+                }
+                tx.send(e.into()).await.unwrap();
+            }
+
+            // Well past `expire_after_ms`, but the group is paused.
+            tokio::time::sleep(Duration::from_millis(200)).await;
+
+            let mut e = LogEvent::from("test message");
+            e.insert("request_id", "1");
+            e.insert("counter", 1);
+            e.insert("marker", "resume");
+            tx.send(e.into()).await.unwrap();
+
+            let output = out.recv().await.unwrap().into_log();
+            assert_eq!(output["counter"], 3.into());
+
+            drop(tx);
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn paused_group_max_lifetime() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+expire_after_ms = 20
+max_lifetime_ms = 50
+flush_period_ms = 10
+
+[pause_when]
+  type = "vrl"
+  source = ".marker == \"pause\""
+
+[resume_when]
+  type = "vrl"
+  source = ".marker == \"resume\""
+
+[merge_strategies]
+counter = "sum"
+"#,
+        )
+        .unwrap();
+
+        assert_transform_compliance(async move {
+            let (tx, rx) = mpsc::channel(4);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), reduce_config).await;
+
+            for marker in [None, Some("pause")] {
+                let mut e = LogEvent::from("test message");
+                e.insert("request_id", "1");
+                e.insert("counter", 1);
+                if let Some(marker) = marker {
+                    e.insert("marker", marker);
+                }
+                tx.send(e.into()).await.unwrap();
+            }
+
+            // The resume marker never comes, but the group is flushed at `max_lifetime_ms`.
+            let output = out.recv().await.unwrap().into_log();
+            assert_eq!(output["counter"], 2.into());
+
+            drop(tx);
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
+    #[test]
+    fn pause_when_requires_resume_when() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+[pause_when]
+  type = "vrl"
+  source = ".marker == \"pause\""
+"#,
+        )
+        .unwrap();
+
+        assert!(Reduce::new(&reduce_config, &TableRegistry::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn flush_on_sequence_gap() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+flush_on_sequence_gap = { field = "seq" }
+"#,
+        )
+        .unwrap();
+
+        let events = [1, 2, 3, 7, 8].into_iter().map(|seq| {
+            let mut event = LogEvent::from(format!("test message {}", seq));
+            event.insert("seq", seq);
+            event.insert("counter", 1);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 2);
+        let (_, output_1) = &output[0];
+        assert_eq!(output_1.as_log()["message"], "test message 1".into());
+        assert_eq!(output_1.as_log()["counter"], 3.into());
+        let (_, output_2) = &output[1];
+        assert_eq!(output_2.as_log()["message"], "test message 7".into());
+        assert_eq!(output_2.as_log()["counter"], 2.into());
+    }
+
+    #[tokio::test]
+    async fn flush_on_sum() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+flush_on_sum = { field = "bytes", threshold = 1000 }
+
+[merge_strategies]
+bytes = "sum"
+"#,
+        )
+        .unwrap();
+
+        let events = [100, 200, 400, 800, 50, 100].into_iter().map(|bytes| {
+            let mut event = LogEvent::from("test message");
+            event.insert("bytes", bytes);
+            event.insert("counter", 1);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // The fourth event brings the sum to 1500, flushing the group with it. The remaining
+        // events stay below the threshold, so they are only flushed when the input ends.
+        assert_eq!(output.len(), 2);
+        let (_, output_1) = &output[0];
+        assert_eq!(output_1.as_log()["bytes"], 1500.into());
+        assert_eq!(output_1.as_log()["counter"], 4.into());
+        let (_, output_2) = &output[1];
+        assert_eq!(output_2.as_log()["bytes"], 150.into());
+        assert_eq!(output_2.as_log()["counter"], 2.into());
+    }
+
+    #[tokio::test]
+    async fn ends_when_match() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+ends_when_match = { field = "status", pattern = "^(done|failed)$" }
+"#,
+        )
+        .unwrap();
+
+        let statuses = [
+            "started", "running", "done", "started", "undone", "failed", "started",
+        ];
+        let events = statuses.into_iter().map(|status| {
+            let mut event = LogEvent::from("test message");
+            event.insert("request_id", "1");
+            event.insert("status", status);
+            event.insert("counter", 1);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // `undone` does not match the anchored pattern, so only `done` and `failed` end a
+        // transaction. The last event is only flushed when the input ends.
+        assert_eq!(output.len(), 3);
+        let counters = output
+            .iter()
+            .map(|(_, event)| event.as_log()["counter"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(counters, vec![3.into(), 3.into(), 1.into()]);
+    }
+
+    #[test]
+    fn ends_when_match_invalid_pattern() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+ends_when_match = { field = "status", pattern = "(done" }
+"#,
+        )
+        .unwrap();
+        assert!(Reduce::new(&reduce_config, &TableRegistry::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn ends_when_fields_present() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+ends_when_fields_present = [ "request", "response" ]
+"#,
+        )
+        .unwrap();
+
+        let fields = [
+            None,
+            Some("request"),
+            None,
+            Some("response"),
+            Some("response"),
+            None,
+        ];
+        let events = fields.into_iter().map(|field| {
+            let mut event = LogEvent::from("test message");
+            if let Some(field) = field {
+                event.insert(field, "value");
+            }
+            event.insert("counter", 1);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // The group is flushed by the fourth event, which completes the required fields. The
+        // remaining events never see `request`, so they are only flushed when the input ends.
+        assert_eq!(output.len(), 2);
+        let (_, output_1) = &output[0];
+        assert_eq!(output_1.as_log()["counter"], 4.into());
+        assert!(output_1.as_log().contains("request"));
+        assert!(output_1.as_log().contains("response"));
+        let (_, output_2) = &output[1];
+        assert_eq!(output_2.as_log()["counter"], 2.into());
+        assert!(!output_2.as_log().contains("request"));
+    }
+
+    #[tokio::test]
+    async fn spill() {
+        let dir = crate::test_util::temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let reduce_config = toml::from_str::<ReduceConfig>(&format!(
+            r#"
+group_by = [ "request_id" ]
+
+[merge_strategies]
+body = "concat"
+
+[spill]
+field = "body"
+threshold_bytes = 16
+dir = "{}"
+"#,
+            dir.display()
+        ))
+        .unwrap();
+
+        let events = [("1", "0123456789"), ("1", "abcdefghij"), ("2", "small")]
+            .into_iter()
+            .map(|(request_id, body)| {
+                let mut event = LogEvent::from("test message");
+                event.insert("request_id", request_id);
+                event.insert("body", body);
+                Event::from(event)
+            });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 2);
+        for (_, event) in output {
+            let body = event.as_log()["body"].to_string_lossy().into_owned();
+            match event.as_log()["request_id"].to_string_lossy().as_ref() {
+                "1" => {
+                    assert!(body.starts_with(dir.to_str().unwrap()));
+                    assert_eq!(
+                        std::fs::read_to_string(&body).unwrap(),
+                        "0123456789 abcdefghij"
+                    );
+                }
+                _ => assert_eq!(body, "small"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn spill_skips_checkpoints() {
+        let dir = crate::test_util::temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let reduce_config = toml::from_str::<ReduceConfig>(&format!(
+            r#"
+group_by = [ "request_id" ]
+checkpoint_every = 2
+
+[merge_strategies]
+body = "concat"
+
+[spill]
+field = "body"
+threshold_bytes = 16
+dir = "{}"
+"#,
+            dir.display()
+        ))
+        .unwrap();
+
+        let events = ["0123456789", "abcdefghij"].into_iter().map(|body| {
+            let mut event = LogEvent::from("test message");
+            event.insert("request_id", "1");
+            event.insert("body", body);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 2);
+        let (port, checkpoint) = &output[0];
+        assert_eq!(port.as_deref(), Some(CHECKPOINT_OUTPUT));
+        assert_eq!(checkpoint.as_log()["body"], "0123456789 abcdefghij".into());
+        let (port, combined) = &output[1];
+        assert_eq!(*port, None);
+        let body = combined.as_log()["body"].to_string_lossy().into_owned();
+        assert!(body.starts_with(dir.to_str().unwrap()));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn spill_remove_after() {
+        let dir = crate::test_util::temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let reduce_config = toml::from_str::<ReduceConfig>(&format!(
+            r#"
+group_by = [ "request_id" ]
+
+[spill]
+field = "body"
+threshold_bytes = 4
+dir = "{}"
+remove_after_secs = 60
+"#,
+            dir.display()
+        ))
+        .unwrap();
+
+        let mut event = LogEvent::from("test message");
+        event.insert("request_id", "1");
+        event.insert("body", "0123456789");
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter([Event::from(event)])))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let body = output[0].1.as_log()["body"].to_string_lossy().into_owned();
+        assert_eq!(std::fs::read_to_string(&body).unwrap(), "0123456789");
+
+        for _ in 0..10 {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            if !std::path::Path::new(&body).exists() {
+                return;
+            }
+        }
+        panic!("spilled file was not removed");
+    }
+
+    #[tokio::test]
+    async fn checkpoint_every() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+checkpoint_every = 2
+"#,
+        )
+        .unwrap();
+
+        let events = (0..5).map(|_| {
+            let mut event = LogEvent::from("test message");
+            event.insert("request_id", "1");
+            event.insert("counter", 1);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        let output: Vec<_> = output
+            .iter()
+            .map(|(port, event)| (port.as_deref(), event.as_log()["counter"].clone()))
+            .collect();
+        assert_eq!(
+            output,
+            [
+                (Some(CHECKPOINT_OUTPUT), 2.into()),
+                (Some(CHECKPOINT_OUTPUT), 4.into()),
+                (None, 5.into()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn checkpoint_every_coalesced() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+checkpoint_every = 2
+coalesce_consecutive = true
+"#,
+        )
+        .unwrap();
+
+        // The duplicates of the second event do not advance the number of merged events.
+        let events = [1, 2, 2, 2].into_iter().map(|counter| {
+            let mut event = LogEvent::from("test message");
+            event.insert("request_id", "1");
+            event.insert("counter", counter);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        let output: Vec<_> = output
+            .iter()
+            .map(|(port, event)| (port.as_deref(), event.as_log()["counter"].clone()))
+            .collect();
+        assert_eq!(
+            output,
+            [(Some(CHECKPOINT_OUTPUT), 3.into()), (None, 3.into())]
+        );
+    }
+
+    #[tokio::test]
+    async fn sample_rate() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+sample_rate = 0.1
+sample_seed = 42
+
+[merge_strategies]
+counter = "count"
+bytes = "sum"
+"#,
+        )
+        .unwrap();
+
+        let events = (0..10000).map(|_| {
+            let mut event = LogEvent::from("test message");
+            event.insert("counter", 1);
+            event.insert("bytes", 10);
+            event.insert("packets", 2);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // The scaled values estimate those over all events, within about three standard
+        // deviations of the number of sampled events.
+        assert_eq!(output.len(), 1);
+        let log = output[0].1.as_log();
+        let count = log["counter"].as_integer().unwrap();
+        assert!((9000..=11000).contains(&count), "count: {}", count);
+        let bytes = log["bytes"].as_integer().unwrap();
+        assert!((90000..=110000).contains(&bytes), "bytes: {}", bytes);
+        // Numeric fields without a merge strategy are summed, so they are scaled too.
+        let packets = log["packets"].as_integer().unwrap();
+        assert!((18000..=22000).contains(&packets), "packets: {}", packets);
+
+        let reduce_config = toml::from_str::<ReduceConfig>("sample_rate = 0.0").unwrap();
+        assert!(Reduce::new(&reduce_config, &TableRegistry::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn sample_rate_with_max_events() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+sample_rate = 0.1
+sample_seed = 42
+max_events = 100
+"#,
+        )
+        .unwrap();
+
+        let events = (0..1000).map(|_| {
+            let mut event = LogEvent::from("test message");
+            event.insert("counter", 1);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // Events left out by sampling still count towards `max_events`.
+        assert_eq!(output.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn route_when() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+
+[route_when.high_volume]
+type = "vrl"
+source = ".counter >= 3"
+
+[route_when.low_volume]
+type = "vrl"
+source = ".counter >= 2"
+"#,
+        )
+        .unwrap();
+
+        let events = ["1", "2", "1", "3", "2", "1"]
+            .into_iter()
+            .map(|request_id| {
+                let mut event = LogEvent::from("test message");
+                event.insert("request_id", request_id);
+                event.insert("counter", 1);
+                Event::from(event)
+            });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // Each combined event is sent to the output of the first condition it matches.
+        let mut output: Vec<_> = output
+            .iter()
+            .map(|(port, event)| {
+                let log = event.as_log();
+                (
+                    port.clone(),
+                    log["request_id"].to_string_lossy().into_owned(),
+                )
+            })
+            .collect();
+        output.sort_by(|(_, a), (_, b)| a.cmp(b));
+        assert_eq!(
+            output,
+            [
+                (Some("high_volume".to_owned()), "1".to_owned()),
+                (Some("low_volume".to_owned()), "2".to_owned()),
+                (None, "3".to_owned()),
+            ]
+        );
+
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+[route_when.passthrough]
+type = "vrl"
+source = "true"
+"#,
+        )
+        .unwrap();
+        assert!(reduce_config.validate(&Definition::any()).is_err());
+    }
+
+    #[tokio::test]
+    async fn array_on_overflow_flush() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+[merge_strategies.id]
+strategy = "array"
+max_len = 2
+on_overflow = "flush"
+"#,
+        )
+        .unwrap();
+
+        let events = (1..=5).map(|id| {
+            let mut event = LogEvent::from("test message");
+            event.insert("id", id);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // The group is flushed each time its array is full.
+        let ids: Vec<_> = output
+            .iter()
+            .map(|(_, event)| event.as_log()["id"].clone())
+            .collect();
+        assert_eq!(
+            ids,
+            [
+                Value::from(vec![Value::from(1), Value::from(2)]),
+                Value::from(vec![Value::from(3), Value::from(4)]),
+                Value::from(vec![Value::from(5)]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn emit_first_immediately() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+emit_first_immediately = true
+"#,
+        )
+        .unwrap();
+
+        let events = ["1", "1", "2", "1", "2"].into_iter().map(|request_id| {
+            let mut event = LogEvent::from("test message");
+            event.insert("request_id", request_id);
+            event.insert("counter", 1);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // The first event of each group is emitted as it arrives, before any combined event.
+        let output: Vec<_> = output
+            .iter()
+            .map(|(port, event)| {
+                let log = event.as_log();
+                (
+                    port.as_deref(),
+                    log["request_id"].clone(),
+                    log["counter"].clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            output[..2],
+            [
+                (Some(FIRST_OUTPUT), "1".into(), 1.into()),
+                (Some(FIRST_OUTPUT), "2".into(), 1.into()),
+            ]
+        );
+        let mut combined = output[2..].to_vec();
+        combined.sort_by_key(|(_, request_id, _)| request_id.to_string_lossy().into_owned());
+        assert_eq!(
+            combined,
+            [(None, "1".into(), 3.into()), (None, "2".into(), 2.into())]
+        );
+    }
+
+    #[tokio::test]
+    async fn emit_provenance() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+emit_provenance = true
+
+[merge_strategies]
+message = "concat"
+counter = "sum"
+"#,
+        )
+        .unwrap();
+
+        let events = (0..3).map(|i| {
+            let mut event = LogEvent::from("test message");
+            event.insert("request_id", "1");
+            event.insert("counter", 1);
+            if i == 1 {
+                event.insert("extra", true);
+            }
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let output = output[0].1.as_log();
+        assert_eq!(output["_provenance.message.events"], 3.into());
+        assert_eq!(output["_provenance.message.strategy"], "concat".into());
+        assert_eq!(output["_provenance.counter.events"], 3.into());
+        assert_eq!(output["_provenance.counter.strategy"], "sum".into());
+        assert_eq!(output["_provenance.extra.events"], 1.into());
+        assert_eq!(output["_provenance.extra.strategy"], "default".into());
+    }
+
+    #[test]
+    fn emit_provenance_schema() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+emit_provenance = true
+"#,
+        )
+        .unwrap();
+
+        let definition = output_definition(&reduce_config, Definition::default_legacy_namespace());
+        let entry = Collection::from(BTreeMap::from([
+            ("events".into(), Kind::integer()),
+            ("strategy".into(), Kind::bytes()),
+        ]));
+
+        assert_eq!(
+            definition
+                .event_kind()
+                .at_path(&owned_value_path!("_provenance", "host")),
+            Kind::object(entry).or_undefined()
+        );
+    }
+
+    #[tokio::test]
+    async fn require_schema_field() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+require_schema_field = "schema_version"
+"#,
+        )
+        .unwrap();
+
+        let events = [1, 1, 2, 1].into_iter().map(|version| {
+            let mut event = LogEvent::from(format!("test message {}", version));
+            event.insert("request_id", "1");
+            event.insert("schema_version", version);
+            event.insert("counter", 1);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 2);
+        let (port, rejected) = &output[0];
+        assert_eq!(port.as_deref(), Some(REJECTED_OUTPUT));
+        assert_eq!(rejected.as_log()["schema_version"], 2.into());
+        assert_eq!(rejected.as_log()["counter"], 1.into());
+        let (port, merged) = &output[1];
+        assert_eq!(port, &None);
+        assert_eq!(merged.as_log()["schema_version"], 1.into());
+        assert_eq!(merged.as_log()["counter"], 3.into());
+    }
+
+    #[tokio::test]
+    async fn require_schema_field_starts_when() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+require_schema_field = "schema_version"
+
+[starts_when]
+  type = "vrl"
+  source = "exists(.test_start)"
+"#,
+        )
+        .unwrap();
+
+        let events = [(1, false), (1, false), (2, true), (2, false)]
+            .into_iter()
+            .map(|(version, starts)| {
+                let mut event = LogEvent::from(format!("test message {}", version));
+                event.insert("request_id", "1");
+                event.insert("schema_version", version);
+                event.insert("counter", 1);
+                if starts {
+                    event.insert("test_start", "yep");
+                }
+                Event::from(event)
+            });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // The event with the new schema starts a group of its own rather than being rejected.
+        assert_eq!(output.len(), 2);
+        for (version, (port, merged)) in [1, 2].into_iter().zip(&output) {
+            assert_eq!(port, &None);
+            assert_eq!(merged.as_log()["schema_version"], version.into());
+            assert_eq!(merged.as_log()["counter"], 2.into());
+        }
+    }
+
+    #[tokio::test]
+    async fn flush_on_field_change() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+flush_on_field_change = [ "schema_version" ]
+"#,
+        )
+        .unwrap();
+
+        let events = [1, 1, 2, 2, 2].into_iter().map(|version| {
+            let mut event = LogEvent::from(format!("test message {}", version));
+            event.insert("schema_version", version);
+            event.insert("counter", 1);
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 2);
+        let (_, output_1) = &output[0];
+        assert_eq!(output_1.as_log()["schema_version"], 1.into());
+        assert_eq!(output_1.as_log()["counter"], 2.into());
+        let (_, output_2) = &output[1];
+        assert_eq!(output_2.as_log()["schema_version"], 2.into());
+        assert_eq!(output_2.as_log()["counter"], 3.into());
+    }
+
+    #[tokio::test]
+    async fn reorder_by() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+reorder_by = "ts"
+reorder_window_ms = 5000
+
+[merge_strategies]
+message = "concat"
+"#,
+        )
+        .unwrap();
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let events = [3, 1, 2].into_iter().map(|second| {
+            let mut event = LogEvent::from(format!("m{}", second));
+            event.insert("ts", start + chrono::Duration::seconds(second));
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let (_, output) = &output[0];
+        assert_eq!(output.as_log()["message"], "m1 m2 m3".into());
+    }
+
+    #[tokio::test]
+    async fn condition_hint_field() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+condition_hint_field = "test_end"
+
+[ends_when]
+  type = "vrl"
+  source = "exists(.message)"
+"#,
+        )
+        .unwrap();
+
+        let events = (1..=3).map(|n| {
+            let mut event = LogEvent::from(format!("test message {}", n));
+            event.insert("counter", 1);
+            if n == 3 {
+                event.insert("test_end", "yep");
+            }
+            Event::from(event)
+        });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // The condition matches every event, but is only evaluated for the last one.
+        assert_eq!(output.len(), 1);
+        let (_, output) = &output[0];
+        assert_eq!(output.as_log()["message"], "test message 1".into());
+        assert_eq!(output.as_log()["counter"], 3.into());
+    }
+
+    #[tokio::test]
+    async fn emit_metric() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+emit_metric = { name = "transactions_completed", tags = [ "request_id" ] }
+"#,
+        )
+        .unwrap();
+
+        let events = [("1", "a"), ("2", "b"), ("1", "c")]
+            .into_iter()
+            .map(|(id, message)| {
+                let mut event = LogEvent::from(message);
+                event.insert("request_id", id);
+                Event::from(event)
+            });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        let mut tags: Vec<_> = output
+            .iter()
+            .filter(|(port, _)| port.as_deref() == Some(METRICS_OUTPUT))
+            .map(|(_, event)| {
+                let metric = event.as_metric();
+                assert_eq!(metric.name(), "transactions_completed");
+                assert_eq!(metric.kind(), MetricKind::Incremental);
+                assert_eq!(metric.value(), &MetricValue::Counter { value: 1.0 });
+                metric.tag_value("request_id").unwrap()
+            })
+            .collect();
+        tags.sort();
+        assert_eq!(tags, vec!["1", "2"]);
+        assert_eq!(output.iter().filter(|(port, _)| port.is_none()).count(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn warmup_ms_defers_stale_flushes() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+expire_after_ms = 10
+flush_period_ms = 10
+warmup_ms = 300
+"#,
+        )
+        .unwrap();
+
+        let (tx, rx) = mpsc::channel(1);
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let mut out = Box::new(reduce).transform(Box::pin(ReceiverStream::new(rx)));
+
+        tx.send(LogEvent::from("test message 1").into())
+            .await
+            .unwrap();
+
+        // The group has expired, but is not flushed during the warmup.
+        assert!(tokio::time::timeout(Duration::from_millis(150), out.next())
+            .await
+            .is_err());
+
+        let (_, output) = tokio::time::timeout(Duration::from_secs(5), out.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(output.as_log()["message"], "test message 1".into());
+        drop(tx);
+    }
+
+    #[tokio::test]
+    async fn treat_empty_as_missing() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "host" ]
+treat_empty_as_missing = true
+"#,
+        )
+        .unwrap();
+
+        let mut e_1 = LogEvent::from("test message 1");
+        e_1.insert("host", "");
+        e_1.insert("counter", 1);
+        let mut e_2 = LogEvent::from("test message 2");
+        e_2.insert("counter", 2);
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(vec![
+                e_1.into(),
+                e_2.into(),
+            ])))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let (_, output) = &output[0];
+        assert_eq!(output.as_log()["message"], "test message 1".into());
+        assert_eq!(output.as_log()["counter"], 3.into());
+    }
+
+    #[tokio::test]
+    async fn group_by_coerce_to_string() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "host" ]
+group_by_coerce_to_string = true
+"#,
+        )
+        .unwrap();
+
+        let mut e_1 = LogEvent::from("test message 1");
+        e_1.insert("host", "1");
+        e_1.insert("counter", 1);
+        let mut e_2 = LogEvent::from("test message 2");
+        e_2.insert("host", 1);
+        e_2.insert("counter", 2);
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(vec![
+                e_1.into(),
+                e_2.into(),
+            ])))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let (_, output) = &output[0];
+        assert_eq!(output.as_log()["message"], "test message 1".into());
+        assert_eq!(output.as_log()["host"], "1".into());
+        assert_eq!(output.as_log()["counter"], 3.into());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn finalizers_resolve_after_flush() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+
+[ends_when]
+  type = "vrl"
+  source = "exists(.test_end)"
+"#,
+        )
+        .unwrap();
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let mut e_1 = LogEvent::from("test message 1").with_batch_notifier(&batch);
+        e_1.insert("request_id", "1");
+        let mut e_2 = LogEvent::from("test message 2").with_batch_notifier(&batch);
+        e_2.insert("request_id", "1");
+        e_2.insert("test_end", "yep");
+        drop(batch);
+
+        let (tx, rx) = mpsc::channel(1);
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let mut out = Box::new(reduce).transform(Box::pin(ReceiverStream::new(rx)));
+
+        tx.send(e_1.into()).await.unwrap();
+        assert!(tokio::time::timeout(Duration::from_millis(100), out.next())
+            .await
+            .is_err());
+        // The first event is held in its group, so it is not acknowledged yet.
+        assert!(receiver.try_recv().is_err());
+
+        tx.send(e_2.into()).await.unwrap();
+        let (_, mut output) = out.next().await.unwrap();
+        // The combined event carries the finalizers of both events until it is delivered.
+        assert!(receiver.try_recv().is_err());
+
+        output
+            .take_finalizers()
+            .update_status(EventStatus::Delivered);
+        drop(output);
+        assert_eq!(receiver.await, BatchStatus::Delivered);
+    }
+
+    #[tokio::test]
+    async fn track_timestamp_bounds() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+track_timestamp_bounds = true
+"#,
+        )
+        .unwrap();
+
+        let at = |seconds| Utc.timestamp_opt(seconds, 0).single().unwrap();
+        let mut e_1 = LogEvent::from("test message 1");
+        e_1.insert("timestamp", at(100));
+        e_1.insert("received_at", at(150));
+        let mut e_2 = LogEvent::from("test message 2");
+        e_2.insert("timestamp", at(120));
+        e_2.insert("sent_at", at(50));
+        e_2.insert("received_at", at(300));
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(vec![
+                e_1.into(),
+                e_2.into(),
+            ])))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let (_, output) = &output[0];
+        assert_eq!(output.as_log()["_min_timestamp"], at(50).into());
+        assert_eq!(output.as_log()["_max_timestamp"], at(300).into());
+    }
+
+    #[test]
+    fn track_timestamp_bounds_schema() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+track_timestamp_bounds = true
+"#,
+        )
+        .unwrap();
+
+        let definition = output_definition(&reduce_config, Definition::default_legacy_namespace());
+
+        for field in ["_min_timestamp", "_max_timestamp"] {
+            assert_eq!(
+                definition.event_kind().at_path(&owned_value_path!(field)),
+                Kind::timestamp().or_undefined()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn flush_vrl() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+flush_vrl = ".average = .total / .count ?? 0"
+"#,
+        )
+        .unwrap();
+
+        let events = [("1", 10), ("1", 20), ("2", 5)]
+            .into_iter()
+            .map(|(id, total)| {
+                let mut event = LogEvent::from("test message");
+                event.insert("request_id", id);
+                event.insert("total", total);
+                event.insert("count", 1);
+                Event::from(event)
+            });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let mut output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .map(|(_, event)| event.into_log())
+            .collect()
+            .await;
+        output.sort_by_key(|log| log["request_id"].to_string_lossy().into_owned());
+
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0]["average"], 15.0.into());
+        assert_eq!(output[1]["average"], 5.0.into());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn snapshot_interval_ms() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+expire_after_ms = 60000
+flush_period_ms = 10
+snapshot_interval_ms = 1
+"#,
+        )
+        .unwrap();
+
+        let (tx, rx) = mpsc::channel(1);
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let mut out = Box::new(reduce).transform(Box::pin(ReceiverStream::new(rx)));
+
+        let mut e = LogEvent::from("test message 1");
+        e.insert("counter", 1);
+        tx.send(e.into()).await.unwrap();
+
+        for seq in 0..3 {
+            let (output, snapshot) = tokio::time::timeout(Duration::from_secs(5), out.next())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(output, None);
+            let snapshot = snapshot.into_log();
+            assert_eq!(snapshot["_snapshot_seq"], seq.into());
+            assert_eq!(snapshot["message"], "test message 1".into());
+            assert_eq!(snapshot["counter"], 1.into());
+        }
+        drop(tx);
+    }
+
+    #[test]
+    fn snapshot_interval_ms_schema() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+snapshot_interval_ms = 1
+"#,
+        )
+        .unwrap();
+
+        let definition = output_definition(&reduce_config, Definition::default_legacy_namespace());
+
+        assert_eq!(
+            definition
+                .event_kind()
+                .at_path(&owned_value_path!("_snapshot_seq")),
+            Kind::integer().or_undefined()
+        );
+    }
+
+    #[tokio::test]
+    async fn last_by_timestamp_strategy() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id" ]
+merge_strategies.status = { strategy = "last_by_timestamp", timestamp_field = "updated_at" }
+"#,
+        )
+        .unwrap();
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let events = [
+            ("pending", 0),
+            ("done", 20),
+            ("running", 10),
+            ("unknown", -1),
+        ]
+        .into_iter()
+        .map(|(status, offset)| {
+            let mut e = LogEvent::from("test message");
+            e.insert("id", "1");
+            e.insert("status", status);
+            if offset >= 0 {
+                e.insert("updated_at", start + chrono::Duration::seconds(offset));
+            }
+            e.into()
+        })
+        .collect::<Vec<Event>>();
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let (_, output) = &output[0];
+        assert_eq!(output.as_log()["status"], "done".into());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn control_channel_flushes_on_demand() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+expire_after_ms = 60000
+"#,
+        )
+        .unwrap();
+
+        let (tx, rx) = mpsc::channel(2);
+        let mut reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let control = reduce.control_channel(1);
+        let mut out = Box::new(reduce).transform(Box::pin(ReceiverStream::new(rx)));
+
+        for (request_id, message) in [("1", "test message 1"), ("2", "test message 2")] {
+            let mut e = LogEvent::from(message);
+            e.insert("request_id", request_id);
+            tx.send(e.into()).await.unwrap();
+        }
+
+        // The events are reduced, but nothing is flushed until a command is sent.
+        assert!(tokio::time::timeout(Duration::from_millis(100), out.next())
+            .await
+            .is_err());
+
+        let mut key = LogEvent::default();
+        key.insert("request_id", "2");
+        let key = Reduce::new(&reduce_config, &TableRegistry::default())
+            .unwrap()
+            .group_key(&key);
+        control.send(ReduceControl::FlushGroup(key)).await.unwrap();
+        let (_, output) = tokio::time::timeout(Duration::from_secs(5), out.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(output.as_log()["message"], "test message 2".into());
+
+        control.send(ReduceControl::FlushAll).await.unwrap();
+        let (_, output) = tokio::time::timeout(Duration::from_secs(5), out.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(output.as_log()["message"], "test message 1".into());
+
+        // The transform still stops when its input ends, even though the control channel is open.
+        drop(tx);
+        assert!(tokio::time::timeout(Duration::from_secs(5), out.next())
+            .await
+            .unwrap()
+            .is_none());
+        drop(control);
+    }
+
+    #[tokio::test]
+    async fn max_fields_per_group() {
+        crate::metrics::init_test();
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+max_fields_per_group = 3
+"#,
+        )
+        .unwrap();
+
+        let events = (0..10)
+            .map(|i| {
+                let mut e = LogEvent::default();
+                e.insert("counter", 1);
+                e.insert(format!("key_{}", i).as_str(), i);
+                e.into()
+            })
+            .collect::<Vec<Event>>();
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let (_, output) = &output[0];
+        let fields = output.as_log().as_map().unwrap();
+        assert_eq!(fields.len(), 3);
+        assert_eq!(fields["counter"], 10.into());
+        assert_eq!(fields["key_0"], 0.into());
+        assert_eq!(fields["key_1"], 1.into());
+
+        let dropped = crate::metrics::Controller::get()
+            .unwrap()
+            .capture_metrics()
+            .into_iter()
+            .find(|metric| metric.name() == "fields_dropped_total")
+            .unwrap();
+        assert_eq!(dropped.value(), &MetricValue::Counter { value: 8.0 });
+    }
+
+    #[tokio::test]
+    async fn group_sum_strategy() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "order_id" ]
+merge_strategies.items = { strategy = "group_sum", key = "sku", value = "qty" }
+"#,
+        )
+        .unwrap();
+
+        let mut e_1 = LogEvent::from("test message 1");
+        e_1.insert("order_id", "1");
+        e_1.insert(
+            "items",
+            Value::from(vec![
+                Value::Object(btreemap! { "sku" => "a", "qty" => 1 }),
+                Value::Object(btreemap! { "sku" => "b", "qty" => 2 }),
+            ]),
+        );
+        let mut e_2 = LogEvent::from("test message 2");
+        e_2.insert("order_id", "1");
+        e_2.insert(
+            "items",
+            Value::from(vec![
+                Value::Object(btreemap! { "sku" => "b", "qty" => 3 }),
+                Value::Object(btreemap! { "sku" => "c", "qty" => 4 }),
+                Value::Object(btreemap! { "sku" => "a" }),
+            ]),
+        );
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(vec![
+                e_1.into(),
+                e_2.into(),
+            ])))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let (_, output) = &output[0];
+        assert_eq!(
+            output.as_log()["items"],
+            Value::from(vec![
+                Value::Object(btreemap! { "sku" => "a", "qty_total" => 1 }),
+                Value::Object(btreemap! { "sku" => "b", "qty_total" => 5 }),
+                Value::Object(btreemap! { "sku" => "c", "qty_total" => 4 }),
+            ])
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_threshold_ms() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+slow_threshold_ms = 100
+
+[ends_when]
+  type = "vrl"
+  source = "exists(.test_end)"
+"#,
+        )
+        .unwrap();
+
+        let (tx, rx) = mpsc::channel(1);
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let out = Box::new(reduce).transform(Box::pin(ReceiverStream::new(rx)));
+        // Events are reduced as they are sent, so the time between them is measured.
+        let output = tokio::spawn(out.collect::<Vec<_>>());
+
+        for (request_id, delay) in [("fast", 0), ("slow", 200)] {
+            let mut e_1 = LogEvent::from("test message 1");
+            e_1.insert("request_id", request_id);
+            tx.send(e_1.into()).await.unwrap();
+
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+
+            let mut e_2 = LogEvent::from("test message 2");
+            e_2.insert("request_id", request_id);
+            e_2.insert("test_end", "yep");
+            tx.send(e_2.into()).await.unwrap();
+        }
+
+        drop(tx);
+        let output = output.await.unwrap();
+        assert_eq!(output.len(), 2);
+
+        let (_, fast) = &output[0];
+        assert_eq!(fast.as_log()["request_id"], "fast".into());
+        assert!(!fast.as_log().contains("_slow"));
+
+        let (_, slow) = &output[1];
+        assert_eq!(slow.as_log()["request_id"], "slow".into());
+        assert_eq!(slow.as_log()["_slow"], true.into());
+    }
+
+    #[test]
+    fn slow_threshold_ms_schema() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+slow_threshold_ms = 100
+"#,
+        )
+        .unwrap();
+
+        let definition = output_definition(&reduce_config, Definition::default_legacy_namespace());
+
+        assert_eq!(
+            definition.event_kind().at_path(&owned_value_path!("_slow")),
+            Kind::boolean().or_undefined()
+        );
+    }
+
+    #[tokio::test]
+    async fn zscore_flag_strategy() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id" ]
+merge_strategies.latency = { strategy = "zscore_flag", sigma = 3.0 }
+"#,
+        )
+        .unwrap();
+
+        let groups = [
+            ("outlier", vec![10, 11, 9, 10, 100]),
+            ("normal", vec![10, 11, 9, 10, 11]),
+            ("few_samples", vec![10, 11, 100]),
+        ];
+        let events = groups
+            .iter()
+            .flat_map(|(id, latencies)| {
+                latencies.iter().map(move |latency| {
+                    let mut e = LogEvent::from("test message");
+                    e.insert("id", *id);
+                    e.insert("latency", *latency);
+                    Event::from(e)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: HashMap<String, LogEvent> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .map(|(_, event)| {
+                let log = event.into_log();
+                (log["id"].to_string_lossy().into_owned(), log)
+            })
+            .collect()
+            .await;
+
+        assert_eq!(output["outlier"]["latency"], 100.into());
+        assert_eq!(output["outlier"]["latency_anomaly"], true.into());
+        assert_eq!(output["normal"]["latency"], 11.into());
+        assert_eq!(output["normal"]["latency_anomaly"], false.into());
+        assert_eq!(output["few_samples"]["latency_anomaly"], false.into());
+    }
+
+    #[test]
+    fn zscore_flag_invalid_sigma() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+merge_strategies.latency = { strategy = "zscore_flag", sigma = 0.0 }
+"#,
+        )
+        .unwrap();
+
+        assert!(Reduce::new(&reduce_config, &TableRegistry::default()).is_err());
+    }
+
+    #[test]
+    fn zscore_flag_schema() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+merge_strategies.latency = { strategy = "zscore_flag", sigma = 3.0 }
+"#,
+        )
+        .unwrap();
+
+        let input_definition = Definition::default_legacy_namespace().with_event_field(
+            &owned_value_path!("latency"),
+            Kind::integer(),
+            None,
+        );
+        let definition = output_definition(&reduce_config, input_definition);
+
+        assert_eq!(
+            definition
+                .event_kind()
+                .at_path(&owned_value_path!("latency_anomaly")),
+            Kind::boolean()
+        );
+    }
+
+    #[test]
+    fn range_timestamp_strategy_schema() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+timestamp_strategy = "range"
+merge_strategies.received_at = "retain"
+"#,
+        )
+        .unwrap();
+
+        let input_definition = Definition::default_legacy_namespace()
+            .with_event_field(&owned_value_path!("seen_at"), Kind::timestamp(), None)
+            .with_event_field(&owned_value_path!("received_at"), Kind::timestamp(), None);
+        let definition = output_definition(&reduce_config, input_definition);
+
+        assert_eq!(
+            definition
+                .event_kind()
+                .at_path(&owned_value_path!("seen_at")),
+            Kind::object(Collection::from(BTreeMap::from([
+                ("start".into(), Kind::timestamp()),
+                ("end".into(), Kind::timestamp()),
+            ])))
+        );
+        assert_eq!(
+            definition
+                .event_kind()
+                .at_path(&owned_value_path!("received_at")),
+            Kind::timestamp()
+        );
+    }
+
+    #[tokio::test]
+    async fn fan_out_strategies() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id" ]
+
+[fan_out_strategies]
+requests = [
+  { strategy = "sum", output = "requests_sum" },
+  { strategy = "max", output = "requests_max" },
+  { strategy = "count", output = "requests_count" },
+]
+"#,
+        )
+        .unwrap();
+
+        let events = [3, 7, 5]
+            .into_iter()
+            .map(|requests| {
+                let mut e = LogEvent::from("test message");
+                e.insert("id", "a");
+                e.insert("requests", requests);
+                Event::from(e)
+            })
+            .collect::<Vec<_>>();
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let log = output[0].1.as_log();
+        assert_eq!(log["requests_sum"], 15.into());
+        assert_eq!(log["requests_max"], 7.into());
+        assert_eq!(log["requests_count"], 3.into());
+        assert_eq!(log["requests"], 15.into());
+    }
+
+    #[test]
+    fn fan_out_strategies_output_conflict() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+merge_strategies.requests_sum = "sum"
+fan_out_strategies.requests = [{ strategy = "sum", output = "requests_sum" }]
+"#,
+        )
+        .unwrap();
+
+        assert!(Reduce::new(&reduce_config, &TableRegistry::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn emit_delta_from_previous() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id", "shard" ]
+emit_delta_from_previous = true
+
+[merge_strategies]
+peak = "max"
+
+[ends_when]
+  type = "vrl"
+  source = "exists(.test_end)"
+"#,
+        )
+        .unwrap();
+
+        let events = [
+            (Value::from(3), false),
+            (Value::from(4), true),
+            (Value::from(5), false),
+            (Value::from(6.5), true),
+        ]
+        .into_iter()
+        .map(|(requests, end)| {
+            let mut e = LogEvent::from("test message");
+            e.insert("id", "a");
+            e.insert("shard", 7);
+            e.insert("peak", 10);
+            e.insert("requests", requests);
+            if end {
+                e.insert("test_end", "yes");
+            }
+            Event::from(e)
+        })
+        .collect::<Vec<_>>();
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .map(|(_, event)| event.into_log())
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 2);
+        // The first transaction has no baseline, so it is emitted as is.
+        assert_eq!(output[0]["requests"], 7.into());
+        assert_eq!(output[1]["requests"], 4.5.into());
+        // Only summed and counted fields are deltas, and `group_by` fields never are, even
+        // though numeric fields are summed by default.
+        assert_eq!(output[1]["shard"], 14.into());
+        assert_eq!(output[1]["peak"], 10.into());
+    }
+
+    #[test]
+    fn emit_delta_from_previous_expires_baselines() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id" ]
+expire_after_ms = 1000
+emit_delta_from_previous = true
+"#,
+        )
+        .unwrap();
+        let mut reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+
+        let mut event = LogEvent::from("test message");
+        event.insert("id", "a");
+        event.insert("requests", 3);
+        let discriminant = reduce.group_key(&event);
+        reduce.subtract_baseline(&discriminant, &mut event);
+        assert!(reduce.total_bytes > 0);
+
+        // The baseline is kept within the expiry window, and counted in the total size.
+        reduce.expire_baselines(Instant::now());
+        assert_eq!(reduce.baselines.as_ref().unwrap().len(), 1);
+
+        reduce.expire_baselines(Instant::now() + Duration::from_secs(1));
+        assert!(reduce.baselines.as_ref().unwrap().is_empty());
+        assert_eq!(reduce.total_bytes, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn emit_age_metrics() {
+        crate::metrics::init_test();
+
+        fn age_samples() -> (u64, f64) {
+            crate::metrics::Controller::get()
+                .unwrap()
+                .capture_metrics()
+                .into_iter()
+                .find(|metric| metric.name() == "open_group_age_seconds")
+                .map_or((0, 0.0), |metric| match metric.value() {
+                    MetricValue::AggregatedHistogram { count, sum, .. } => (*count, *sum),
+                    value => panic!("unexpected metric value: {:?}", value),
+                })
+        }
+
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id" ]
+flush_period_ms = 10
+emit_age_metrics = true
+"#,
+        )
+        .unwrap();
+        let events = (0..2)
+            .map(|i| {
+                let mut e = LogEvent::from("test message");
+                e.insert("id", i);
+                Event::from(e)
+            })
+            .collect::<Vec<_>>();
+        // Keep the input open for a while, so that the groups are open during several flushes.
+        let input = futures::stream::iter(events).chain(
+            futures::stream::once(tokio::time::sleep(Duration::from_millis(100)))
+                .filter_map(|()| futures::future::ready(None)),
+        );
+
+        let (count_before, sum_before) = age_samples();
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce).transform(Box::pin(input)).collect().await;
+        assert_eq!(output.len(), 2);
+
+        // Both groups are recorded on each flush while they are open.
+        let (count, sum) = age_samples();
+        assert!(count - count_before >= 4);
+        assert!(sum > sum_before);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn emit_groups_flushed_per_tick() {
+        crate::metrics::init_test();
+
+        fn flushed_samples() -> (u64, f64) {
+            crate::metrics::Controller::get()
+                .unwrap()
+                .capture_metrics()
+                .into_iter()
+                .find(|metric| metric.name() == "groups_flushed_per_tick")
+                .map_or((0, 0.0), |metric| match metric.value() {
+                    MetricValue::AggregatedHistogram { count, sum, .. } => (*count, *sum),
+                    value => panic!("unexpected metric value: {:?}", value),
+                })
+        }
+
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "id" ]
+flush_period_ms = 10
+expire_after_ms = 20
+"#,
+        )
+        .unwrap();
+        let events = (0..3)
+            .map(|i| {
+                let mut e = LogEvent::from("test message");
+                e.insert("id", i);
+                Event::from(e)
+            })
+            .collect::<Vec<_>>();
+        // Keep the input open until the groups have gone stale and been flushed by a tick.
+        let input = futures::stream::iter(events).chain(
+            futures::stream::once(tokio::time::sleep(Duration::from_millis(100)))
+                .filter_map(|()| futures::future::ready(None)),
+        );
+
+        let (count_before, sum_before) = flushed_samples();
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce).transform(Box::pin(input)).collect().await;
+        assert_eq!(output.len(), 3);
+
+        // The stale groups are counted by the ticks that flushed them.
+        let (count, sum) = flushed_samples();
+        assert!(count > count_before);
+        assert!(sum - sum_before >= 3.0);
+    }
+
+    #[tokio::test]
+    async fn profiling() {
+        crate::metrics::init_test();
+
+        fn lookup_samples() -> u64 {
+            crate::metrics::Controller::get()
+                .unwrap()
+                .capture_metrics()
+                .into_iter()
+                .find(|metric| metric.name() == "group_lookup_duration_seconds")
+                .map_or(0, |metric| match metric.value() {
+                    MetricValue::AggregatedHistogram { count, .. } => *count,
+                    value => panic!("unexpected metric value: {:?}", value),
+                })
+        }
+
+        async fn run(profiling: bool) {
+            let reduce_config = toml::from_str::<ReduceConfig>(&format!(
+                r#"
+group_by = [ "id" ]
+profiling = {}
+"#,
+                profiling
+            ))
+            .unwrap();
+            let events = (0..3)
+                .map(|i| {
+                    let mut e = LogEvent::from("test message");
+                    e.insert("id", i);
+                    Event::from(e)
+                })
+                .collect::<Vec<_>>();
+
+            let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+            let output: Vec<_> = Box::new(reduce)
+                .transform(Box::pin(futures::stream::iter(events)))
+                .collect()
+                .await;
+            assert_eq!(output.len(), 3);
+        }
+
+        let before = lookup_samples();
+        run(false).await;
+        assert_eq!(lookup_samples(), before);
+        run(true).await;
+        assert_eq!(lookup_samples(), before + 3);
+    }
+
+    #[tokio::test]
+    async fn sticky_by() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "user" ]
+sticky_by = "session"
+"#,
+        )
+        .unwrap();
+
+        let events = [("alice", "s1"), ("alice@example.com", "s1"), ("bob", "s2")]
+            .into_iter()
+            .map(|(user, session)| {
+                let mut e = LogEvent::from("test message");
+                e.insert("user", user);
+                e.insert("session", session);
+                e.insert("count", 1);
+                Event::from(e)
+            })
+            .collect::<Vec<_>>();
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: HashMap<String, LogEvent> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .map(|(_, event)| {
+                let log = event.into_log();
+                (log["session"].to_string_lossy().into_owned(), log)
+            })
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 2);
+        assert_eq!(output["s1"]["user"], "alice".into());
+        assert_eq!(output["s1"]["count"], 2.into());
+        assert_eq!(output["s2"]["count"], 1.into());
+    }
+
+    #[tokio::test]
+    async fn sum_unit_field() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "host" ]
+merge_strategies.bytes = { strategy = "sum", unit_field = "unit" }
+"#,
+        )
+        .unwrap();
+
+        let events = [(1, "MB"), (2, "MB"), (4, "GB")]
+            .into_iter()
+            .map(|(bytes, unit)| {
+                let mut e = LogEvent::from("test message");
+                e.insert("host", "a");
+                e.insert("bytes", bytes);
+                e.insert("unit", unit);
+                Event::from(e)
+            });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // The value in gigabytes is skipped rather than added to the megabytes.
+        assert_eq!(output.len(), 1);
+        let output = output[0].1.as_log();
+        assert_eq!(output["bytes"], 3.into());
+        assert_eq!(output["unit"], "MB".into());
+    }
+
+    #[tokio::test]
+    async fn sum_unit_field_nested() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "host" ]
+merge_strategies."usage.bytes" = { strategy = "sum", unit_field = "usage.unit" }
+"#,
+        )
+        .unwrap();
+
+        let events = [(1, "MB"), (2, "MB"), (4, "GB")]
+            .into_iter()
+            .map(|(bytes, unit)| {
+                let mut e = LogEvent::from("test message");
+                e.insert("host", "a");
+                e.insert("usage.bytes", bytes);
+                e.insert("usage.unit", unit);
+                Event::from(e)
+            });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let output = output[0].1.as_log();
+        assert_eq!(output["usage.bytes"], 3.into());
+        assert_eq!(output["usage.unit"], "MB".into());
+    }
+
+    #[tokio::test]
+    async fn max_context_fields() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+merge_strategies.latency = { strategy = "max", keep = "last", context_fields = ["host"] }
+"#,
+        )
+        .unwrap();
+
+        let events = [(1, "a"), (5, "b"), (5, "c"), (3, "d")]
+            .into_iter()
+            .map(|(latency, host)| {
+                let mut e = LogEvent::from("test message");
+                e.insert("latency", latency);
+                e.insert("host", host);
+                Event::from(e)
+            });
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        // The host is taken from the last of the events holding the maximum.
+        assert_eq!(output.len(), 1);
+        let output = output[0].1.as_log();
+        assert_eq!(output["latency"], 5.into());
+        assert_eq!(output["host"], "c".into());
+    }
+
+    #[tokio::test]
+    async fn alias_field() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "session" ]
+alias_field = "alias_of"
+merge_strategies.tag = "array"
+"#,
+        )
+        .unwrap();
+
+        let mut events = [("a", "a1"), ("b", "b1"), ("b", "b2"), ("a", "a2")]
+            .into_iter()
+            .map(|(session, tag)| {
+                let mut e = LogEvent::from("test message");
+                e.insert("session", session);
+                e.insert("tag", tag);
+                e.insert("counter", 1);
+                e
+            })
+            .collect::<Vec<_>>();
+        // The last event learns that session `b` is the same as session `a`.
+        events[3].insert("alias_of", Value::Object(btreemap! { "session" => "b" }));
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(
+                events.into_iter().map(Event::from),
+            )))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 1);
+        let output = output[0].1.as_log();
+        assert_eq!(output["session"], "a".into());
+        assert_eq!(output["counter"], 4.into());
+        assert_eq!(output["tag"], json!(["a1", "b1", "b2", "a2"]).into());
+    }
+
+    #[tokio::test]
+    async fn emit_lifecycle() {
+        let reduce_config = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+emit_lifecycle = true
+
+[ends_when]
+  type = "vrl"
+  source = "exists(.test_end)"
+"#,
+        )
+        .unwrap();
+
+        let events = [false, false, true]
+            .into_iter()
+            .map(|end| {
+                let mut e = LogEvent::from("test message");
+                e.insert("request_id", "1");
+                if end {
+                    e.insert("test_end", "yes");
+                }
+                Event::from(e)
+            })
+            .collect::<Vec<_>>();
+
+        let reduce = Reduce::new(&reduce_config, &TableRegistry::default()).unwrap();
+        let output: Vec<_> = Box::new(reduce)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await;
+
+        assert_eq!(output.len(), 3);
+        let (port, start) = &output[0];
+        assert_eq!(port.as_deref(), Some(LIFECYCLE_OUTPUT));
+        assert_eq!(start.as_log()["_lifecycle"], "start".into());
+        assert_eq!(start.as_log()["request_id"], "1".into());
+
+        let (port, aggregate) = &output[1];
+        assert_eq!(port, &None);
+        assert!(aggregate.as_log().contains("test_end"));
+
+        let (port, end) = &output[2];
+        assert_eq!(port.as_deref(), Some(LIFECYCLE_OUTPUT));
+        assert_eq!(end.as_log()["_lifecycle"], "end".into());
+        assert_eq!(end.as_log()["request_id"], "1".into());
+    }
+
     #[tokio::test]
     async fn arrays() {
         let reduce_config = toml::from_str::<ReduceConfig>(
@@ -27,6 +27,100 @@ base: components: sinks: console: configuration: {
 			type: bool: {}
 		}
 	}
+	diagnostics_to_stderr: {
+		description: """
+			Whether or not to write diagnostics about events that could not be written to STDERR.
+
+			When enabled, a JSON record describing the problem is written to STDERR for each event
+			that fails to encode or that no output could be selected for, and the sink carries on with
+			the next event. Otherwise, a failure to encode an event stops the sink.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	diff_key_field: {
+		description: """
+			The field to group events by when `diff_only` is enabled.
+
+			If not set, all events belong to a single group.
+			"""
+		required: false
+		type: string: examples: ["host"]
+	}
+	diff_only: {
+		description: """
+			Whether or not to write only the fields of each log event that changed from the previous
+			log event of the same group.
+
+			Events are grouped by the value of `diff_key_field`, which is always written. The first
+			event of each group is written in full.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	dual_encoding: {
+		description: """
+			Dual encoding configuration.
+
+			When set, each event is written twice to the same output, first encoded with the `first` codec
+			and then with the `second` one, such as a human readable line followed by a machine readable
+			one. This replaces the codec of `encoding`; its framing and field options still apply.
+			"""
+		required: false
+		type: object: options: {
+			first: {
+				description: "The codec to encode each event with first."
+				required:    true
+				type: string: {
+					enum: {
+						json: """
+							Encode events as [JSON][json].
+
+							[json]: https://www.json.org/
+							"""
+						logfmt: """
+							Encode log events as [logfmt][logfmt].
+
+							[logfmt]: https://brandur.org/logfmt
+							"""
+						text: "Encode the `message` field of log events as plain text."
+					}
+					examples: ["text"]
+				}
+			}
+			second: {
+				description: "The codec to encode each event with second."
+				required:    true
+				type: string: {
+					enum: {
+						json: """
+							Encode events as [JSON][json].
+
+							[json]: https://www.json.org/
+							"""
+						logfmt: """
+							Encode log events as [logfmt][logfmt].
+
+							[logfmt]: https://brandur.org/logfmt
+							"""
+						text: "Encode the `message` field of log events as plain text."
+					}
+					examples: ["json"]
+				}
+			}
+		}
+	}
+	emit_footer: {
+		description: """
+			Whether or not to write a footer summarizing the events written when the sink stops.
+
+			The footer contains the number of events written, by type, and the total number of bytes
+			written for them. With a templated file path, the footer is written to the file of the last
+			event, and is not written if no event was. Not written for binary encodings.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	encoding: {
 		description: "Configures how events are encoded into raw bytes."
 		required:    true
@@ -330,13 +424,357 @@ base: components: sinks: console: configuration: {
 			}
 		}
 	}
+	json_stream: {
+		description: """
+			Whether or not to write log events in the streaming form of `jq --stream`.
+
+			Each leaf value of an event is written as a `[path, value]` pair on its own line, and the
+			last element of each non-empty array or object is followed by a closing `[path]` event.
+			This allows very large events to be processed incrementally, such as with
+			`jq -c --stream`. This replaces the codec of `encoding` for log events; other event types
+			are encoded as usual. Cannot be used with binary encodings.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	line_numbers: {
+		description: """
+			Whether or not to prefix each record with its line number.
+
+			Records are numbered from `1`, in the order they are written, and the number is followed by
+			`: `, such as `1: `. The number is written first, before any other prefix, such as the one
+			of `prefix_component_id`. Not written for binary encodings.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	max_records_per_second: {
+		description: """
+			The maximum number of records to write each second.
+
+			Records over the limit are dropped, but still acknowledged. Before the next record written,
+			and when the sink stops, a `dropped N records` line reports how many were dropped since the
+			last report, except for binary encodings. This keeps a high volume of events from
+			overwhelming the terminal.
+			"""
+		required: false
+		type: uint: examples: [100]
+	}
+	prefix_component_id: {
+		description: """
+			Whether or not to prefix each record with the ID of the component the event came from.
+
+			The ID is written in brackets, such as `[my_transform] `, or as `[unknown] ` when the
+			event does not record the component it came from. Not written for binary encodings.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	pretty_depth: {
+		description: """
+			The depth at which log events written as JSON start being indented.
+
+			Objects and arrays nested at least this deep are pretty-printed, one field per line, while
+			shallower ones stay compact. The top-level object is at depth `0`, so a value of `1` keeps
+			the fields of an event on one line and indents the objects they contain. This replaces the
+			codec of `encoding` for log events; other event types are encoded as usual. Cannot be used
+			together with `json_stream`, or with binary encodings.
+			"""
+		required: false
+		type: uint: examples: [1]
+	}
+	print_when: {
+		description: """
+			A condition that events must match to be written.
+
+			Events that do not match are acknowledged without being written, which is useful to only
+			print the events of interest, such as errors, while debugging.
+			"""
+		required: false
+		type: condition: {}
+	}
+	progress_dots: {
+		description: """
+			Whether or not to write a `.` to STDERR for each event, as a compact progress indicator.
+
+			A new line is started every 80 events. The records of the events are still written to
+			the target, unless it is STDERR, in which case only the dots are written.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	redact_fields: {
+		description: """
+			Fields of log events whose values are replaced with `***` before encoding.
+
+			Only fields present in an event are replaced, so that sensitive values such as passwords
+			and tokens can be kept out of shared output without adding fields to other events.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["password", "user.token"]
+		}
+	}
+	ring: {
+		description: """
+			Ring buffer configuration.
+
+			When configured, only the most recent events are held in memory and nothing is written until
+			a dump is triggered, either by an event matching `dump_when` or, on Unix, by sending `SIGUSR1`
+			to Vector. Cannot be used with a templated file path.
+			"""
+		required: false
+		type: object: options: {
+			capacity: {
+				description: "The maximum number of events to hold."
+				required:    true
+				type: uint: {}
+			}
+			dump_when: {
+				description: """
+					A condition that triggers writing out all held events.
+
+					The event matching the condition is written last.
+					"""
+				required: false
+				type: condition: {}
+			}
+		}
+	}
+	sanitize_control_chars: {
+		description: """
+			Whether or not to escape control characters in text-based output.
+
+			When enabled, ASCII control characters other than newlines and tabs, such as those that
+			begin terminal escape sequences, are written in escaped form (for example, `\\x1b`). This
+			prevents untrusted events from manipulating the terminal.
+
+			If not set, control characters are escaped only when the target is a terminal. Binary
+			encodings are never modified.
+			"""
+		required: false
+		type: bool: {}
+	}
+	stats_interval_ms: {
+		description: """
+			The interval, in milliseconds, at which to write a line of statistics about the events
+			seen.
+
+			Each line reports the number of events and bytes written per second, and the number of
+			events of each type, since the previous line. Unlike `emit_footer`, these lines are written
+			periodically while the sink runs. With a templated file path, each line is written to the
+			file of the last event, and no line is written before the first event. Not written for binary
+			encodings.
+			"""
+		required: false
+		type: uint: {
+			examples: [10000]
+			unit:     "milliseconds"
+		}
+	}
+	statsd_metrics: {
+		description: """
+			Whether or not to write metric events as [StatsD][statsd] lines.
+
+			When enabled, each metric event is written as one or more lines such as
+			`name:value|type|#tags`, regardless of the configured encoding. Other event types are
+			encoded as usual. Metrics that StatsD cannot represent are dropped.
+
+			[statsd]: https://github.com/statsd/statsd/blob/master/docs/metric_types.md
+			"""
+		required: false
+		type: bool: default: false
+	}
+	table: {
+		description: """
+			Table configuration.
+
+			When configured, each log event is written as a row of fixed-width columns, one for each of
+			the given fields, separated by a space, instead of being encoded. Values longer than their
+			column are truncated, and shorter ones are padded with spaces. Missing fields are written as
+			blank columns. Other event types are encoded as usual. Cannot be used with binary encodings.
+			"""
+		required: false
+		type: object: options: {
+			columns: {
+				description: "The fields to write as columns, in order."
+				required:    true
+				type: array: items: type: string: examples: ["host", "message"]
+			}
+			header: {
+				description: "Whether or not to write a header row with the names of the columns before the first row."
+				required:    false
+				type: bool: default: false
+			}
+			widths: {
+				description: """
+					The width of each column, in characters.
+
+					Must have the same number of entries as `columns`.
+					"""
+				required: true
+				type: array: items: type: uint: examples: [16, 60]
+			}
+		}
+	}
 	target: {
 		description: """
-			The [standard stream][standard_streams] to write to.
+			The destination to write output to, such as a [standard stream][standard_streams].
 
 			[standard_streams]: https://en.wikipedia.org/wiki/Standard_streams
 			"""
 		required: false
+		type: object: options: {
+			file: {
+				description: "Write output to files."
+				required:    true
+				type: object: options: {
+					compression: {
+						description: "The compression to apply to files."
+						required:    false
+						type: string: {
+							default: "none"
+							enum: {
+								gzip: """
+									[Gzip][gzip] compression.
+
+									A `.gz` suffix is added to the path of each file. Each segment of a rotated file is a
+									complete gzip stream.
+
+									[gzip]: https://www.gzip.org/
+									"""
+								none: "No compression."
+							}
+						}
+					}
+					max_open_files: {
+						description: """
+							The maximum number of files to keep open at once.
+
+							When more files are written to, the least recently written file is flushed and
+							closed.
+							"""
+						required: false
+						type: uint: default: 64
+					}
+					path: {
+						description: """
+							The path of the file to write each event to.
+
+							The path is rendered for each event, so events can be written to different files.
+							"""
+						required: true
+						type: string: {
+							examples: ["/tmp/vector-%Y-%m-%d.log", "/logs/{{ tenant }}.log"]
+							syntax: "template"
+						}
+					}
+					rotate_after_bytes: {
+						description: """
+							The number of bytes after which to start a new segment of a file.
+
+							When set, each segment is written to its own file, with the time it was started
+							inserted before the extension of the path, such as `out-20240101T120000000000000.log`.
+							The limit applies to the bytes written before compression. A segment is only rotated
+							between records, so it can exceed the limit by up to one record.
+							"""
+						required: false
+						type: uint: unit: "bytes"
+					}
+					write_bom: {
+						description: """
+							Whether or not to write a UTF-8 byte order mark (BOM) at the start of each file.
+
+							Some Windows tools need the BOM to read the file as UTF-8. It is only written to files
+							that are empty when they are opened, and never for binary encodings.
+							"""
+						required: false
+						type: bool: default: false
+					}
+				}
+			}
+			syslog: {
+				description: """
+					Write output to the system logger, such as journald or a syslog daemon.
+
+					Each record is sent as its own message, so binary encodings cannot be used. Only available
+					on Unix.
+					"""
+				required: true
+				type: object: options: {
+					default_severity: {
+						description: "The severity of events without a valid severity."
+						required:    false
+						type: string: {
+							default: "info"
+							enum: {
+								alert:   "Action must be taken immediately."
+								crit:    "Critical conditions."
+								debug:   "Debug-level messages."
+								emerg:   "The system is unusable."
+								err:     "Error conditions."
+								info:    "Informational messages."
+								notice:  "Normal but significant conditions."
+								warning: "Warning conditions."
+							}
+						}
+					}
+					facility: {
+						description: "The syslog facility to send messages with."
+						required:    false
+						type: string: {
+							default: "user"
+							enum: {
+								daemon: "Messages from system daemons."
+								local0: "Reserved for local use."
+								local1: "Reserved for local use."
+								local2: "Reserved for local use."
+								local3: "Reserved for local use."
+								local4: "Reserved for local use."
+								local5: "Reserved for local use."
+								local6: "Reserved for local use."
+								local7: "Reserved for local use."
+								user:   "Messages from user-level programs."
+							}
+						}
+					}
+					path: {
+						description: "The path of the Unix socket the system logger listens on."
+						required:    false
+						type: string: default: "/dev/log"
+					}
+					severity_field: {
+						description: """
+							The field containing the severity of each event.
+
+							The field can hold the name of a severity, such as `err` or `warning`, or its number,
+							from `0` for `emerg` to `7` for `debug`. Events without a valid severity are sent with
+							`default_severity`.
+							"""
+						required: false
+						type: string: examples: ["level"]
+					}
+				}
+			}
+			tcp: {
+				description: """
+					Write output to a TCP connection.
+
+					The connection is opened on the first record, and reopened if writing to it fails.
+					"""
+				required: true
+				type: object: options: address: {
+					description: """
+						The address to connect to, including the port.
+
+						The host can be a hostname or an IP address.
+						"""
+					required: true
+					type: string: examples: ["127.0.0.1:9000", "localhost:9000"]
+				}
+			}
+		}
 		type: string: {
 			default: "stdout"
 			enum: {
@@ -353,4 +791,26 @@ base: components: sinks: console: configuration: {
 			}
 		}
 	}
+	type_separator: {
+		description: """
+			A separator to write between consecutive records of different event types.
+
+			When set, the separator is written on its own line whenever an event is of a different
+			type (log, metric, or trace) than the previous one, which makes streams mixing event types
+			easier to read. Not written for binary encodings.
+			"""
+		required: false
+		type: string: examples: ["---"]
+	}
+	wrap_field: {
+		description: """
+			A field to nest the fields of each log event under before encoding.
+
+			Some consumers require each record to be an object with a single top-level field, such as
+			`{"event": {...}}`. Other event types are written unchanged. Cannot be used with binary
+			encodings.
+			"""
+		required: false
+		type: string: examples: ["event"]
+	}
 }
@@ -747,6 +747,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		open_group_age_seconds: {
+			description:       "The time since each group still open was created, recorded on every flush when age metrics are enabled."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		protobuf_decode_errors_total: {
 			description:       "The total number of [Protocol Buffers](\(urls.protobuf)) errors thrown during communication between Vector instances."
 			type:              "counter"
@@ -816,6 +822,36 @@ components: sources: internal_metrics: {
 				}
 			}
 		}
+		fields_dropped_total: {
+			description:       "The number of fields dropped from groups that reached the configured maximum number of fields."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		groups_evicted_total: {
+			description:       "The number of groups flushed early to keep the total size of groups within the configured limit."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		groups_flushed_per_tick: {
+			description:       "The number of groups flushed by each flush of the reduce transform that flushed at least one expired group."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		group_lookup_duration_seconds: {
+			description:       "The time spent finding the group of each event, when profiling is enabled."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		slow_transactions_total: {
+			description:       "The number of transactions completed after being open for longer than the configured slow threshold."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		stale_events_flushed_total: {
 			description:       "The number of stale events that Vector has flushed."
 			type:              "counter"
@@ -1,6 +1,171 @@
 package metadata
 
 base: components: transforms: reduce: configuration: {
+	alias_field: {
+		description: """
+			A field naming another group that turns out to be the same as the group of the event.
+
+			The value of this field is an object with the values of the `group_by` fields of the other
+			group, where fields missing from it take the value they have in the event. If that group is
+			open, it is merged into the group of the event, with the values of each field combined
+			according to its merge strategy. For example, with `group_by = ["session"]`, an event in
+			session `a` with this field set to `{ "session": "b" }` merges session `b` into session `a`.
+			"""
+		required: false
+		type: string: examples: ["alias_of"]
+	}
+	bypass_if_larger_than_bytes: {
+		description: """
+			The approximate size, in bytes, above which an event is not reduced.
+
+			Larger events skip aggregation and are sent to the default output unchanged, so that very
+			large events are not held in memory as part of a group.
+			"""
+		required: false
+		type: uint: unit: "bytes"
+	}
+	checkpoint_every: {
+		description: """
+			The number of events after which to emit a checkpoint of a group to the `checkpoint` output.
+
+			A checkpoint is the combined event of a group so far, emitted every time the group has
+			merged a multiple of this many events. Events held for reordering are counted once they are
+			merged, and events merged away by `coalesce_consecutive` are not counted. The group stays
+			open and keeps accumulating events, which allows reporting the progress of long
+			transactions. Unlike `snapshot_interval_ms`, checkpoints are driven by the number of events
+			rather than by time.
+			"""
+		required: false
+		type: uint: examples: [100]
+	}
+	coalesce_consecutive: {
+		description: """
+			Whether or not to coalesce consecutive identical events within a group.
+
+			When enabled, an event that is identical to the previous event of its group, ignoring the
+			timestamp, is not merged into the group, so it does not contribute to any merge strategy.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	condition_hint_field: {
+		description: """
+			A field that must exist on an event for `ends_when` or `starts_when` to be evaluated.
+
+			Events without this field are treated as not matching the condition, without evaluating
+			it. This avoids the cost of evaluating the condition for every event of high-volume
+			streams where only events with a given field can start or end a transaction.
+			"""
+		required: false
+		type: string: examples: ["transaction_boundary"]
+	}
+	correlation_keys: {
+		description: """
+			The fields used to correlate events into groups, in place of `group_by`.
+
+			This allows events to be grouped by one set of fields, such as `trace_id`, while the
+			combined events are labeled with another, set in `output_label_keys`. Defaults to the
+			`group_by` fields.
+			"""
+		required: false
+		type: array: items: type: string: examples: ["trace_id"]
+	}
+	emit_age_metrics: {
+		description: """
+			Whether or not to record the age of each open group on every flush.
+
+			When enabled, every `flush_period_ms`, the time since each group still open was created is
+			recorded in the `open_group_age_seconds` histogram, which shows how long groups tend to
+			stay open. This scans all open groups on every flush.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	emit_delta_from_previous: {
+		description: """
+			Whether or not to emit the summed and counted fields of each combined event as the
+			difference from the previous combined event of the same group.
+
+			The fields merged with the `sum`, `sum_duration`, or `count` strategies, and numeric fields
+			without a merge strategy, which are summed by default, are kept as a baseline for each
+			flushed group, and subtracted from the same fields of the next combined event of the group.
+			Other fields, including the `group_by` fields, are never changed. The first combined event
+			of a group, and fields missing from the baseline, are emitted as is.
+
+			The baseline of a group is forgotten once the group has not been open for
+			`expire_after_ms`, and baselines count towards `max_total_bytes` and
+			`flush_under_pressure_bytes`.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	emit_first_immediately: {
+		description: """
+			Whether or not to emit the first event of each group to the `first` output as it arrives.
+
+			The event is emitted unchanged when its group is opened, before it is merged, so that
+			latency-sensitive consumers such as alerts can act on a new group right away. The combined
+			event is still flushed to the default output as usual.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	emit_lifecycle: {
+		description: """
+			Whether or not to emit lifecycle events to the `lifecycle` output when groups open and close.
+
+			When a group is opened, an event with a `_lifecycle` field set to `start` is emitted, and
+			when it is flushed, one set to `end` is emitted after the combined event. Lifecycle events
+			also carry the values of the `group_by` fields of their group.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	emit_metric: {
+		description: """
+			Emits a counter metric for each combined event to the `metrics` output.
+
+			The counter is incremented by one each time a group is flushed, which can be used to count
+			completed transactions.
+			"""
+		required: false
+		type: object: options: {
+			name: {
+				description: "The name of the metric."
+				required:    true
+				type: string: examples: ["transactions_completed"]
+			}
+			namespace: {
+				description: "The namespace of the metric."
+				required:    false
+				type: string: examples: ["service"]
+			}
+			tags: {
+				description: """
+					The `group_by` fields to add as tags to the metric.
+
+					Each tag is named after its field and set to the field's value in the combined event.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: string: examples: ["host"]
+				}
+			}
+		}
+	}
+	emit_provenance: {
+		description: """
+			Whether or not to record how each field of a combined event was merged.
+
+			When enabled, each combined event carries a `_provenance` object with an entry for each
+			merged field, holding the number of events that contributed to the field in `events`, and
+			the merge strategy applied to it in `strategy`. Fields without a configured merge strategy
+			are reported with the `default` strategy.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	ends_when: {
 		description: """
 			A condition used to distinguish the final event of a transaction.
@@ -11,6 +176,57 @@ base: components: transforms: reduce: configuration: {
 		required: false
 		type: condition: {}
 	}
+	ends_when_fields_present: {
+		description: """
+			A list of fields that together mark a transaction as complete.
+
+			Once every field has been seen in at least one event of a group, the group is immediately
+			flushed with the event that completed it, as with `ends_when`.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["response_code"]
+		}
+	}
+	ends_when_match: {
+		description: """
+			Configuration for flushing groups when a field matches a regular expression.
+
+			A group is flushed with the event whose field matches `pattern`, as with `ends_when`. This is
+			a lightweight alternative to a condition for simple end markers, such as a `status` field set
+			to `done`.
+			"""
+		required: false
+		type: object: options: {
+			field: {
+				description: """
+					The field to match.
+
+					Events without the field do not match. Values that are not strings are matched in their
+					string form.
+					"""
+				required: true
+				type: string: examples: ["status"]
+			}
+			pattern: {
+				description: "The regular expression that the value of the field is matched against."
+				required:    true
+				type: string: examples: ["^(done|failed)$"]
+			}
+		}
+	}
+	expire_after_field: {
+		description: """
+			A numeric field that overrides `expire_after_ms` for the group the event belongs to, in
+			milliseconds.
+
+			Each event that contains this field sets the expiry of its group to the field's value.
+			Groups that have not received the field use `expire_after_ms`.
+			"""
+		required: false
+		type: string: examples: ["ttl_ms"]
+	}
 	expire_after_ms: {
 		description: """
 			The maximum period of time to wait after the last event is received, in milliseconds, before
@@ -22,6 +238,381 @@ base: components: transforms: reduce: configuration: {
 			unit:    "milliseconds"
 		}
 	}
+	fan_out_strategies: {
+		description: """
+			A map of field names to merge strategies whose results are written to separate fields.
+
+			Each field specified is merged with every strategy in its list, and the result of each
+			strategy is written to the strategy's `output` field, so that a single field can yield
+			several aggregates. The field itself is still merged as usual.
+			"""
+		required: false
+		type: object: options: "*": {
+			description: "The merge strategies to apply to the field."
+			required:    true
+			type: array: items: type: object: options: {
+				alpha: {
+					description: """
+						The smoothing factor applied to each new value.
+
+						Must be greater than `0` and at most `1`. Higher values discount older values faster.
+						"""
+					relevant_when: "strategy = \"ema\""
+					required:      true
+					type: float: {}
+				}
+				context_fields: {
+					description: """
+						The top-level fields to take from the event holding the kept value.
+
+						When set, these fields are not merged on their own. Instead, the combined event carries
+						the values they had in the event holding the kept value, with `keep` choosing between
+						events holding equal values. Fields missing from that event are left out.
+						"""
+					relevant_when: "strategy = \"max\" or strategy = \"min\""
+					required:      false
+					type: array: {
+						default: []
+						items: type: string: examples: ["host"]
+					}
+				}
+				decay: {
+					description: """
+						The factor that the weights of earlier values are multiplied by for each new value.
+
+						Must be greater than `0` and at most `1`. Lower values discount older values faster, and
+						`1` weights all values equally.
+						"""
+					relevant_when: "strategy = \"avg\""
+					required:      false
+					type: float: {
+						default: 1.0
+						examples: [0.9]
+					}
+				}
+				end: {
+					description:   "The field containing the end timestamp of each event."
+					relevant_when: "strategy = \"sum_duration\""
+					required:      true
+					type: string: examples: ["ended_at"]
+				}
+				k: {
+					description: """
+						The number of values to keep.
+
+						Must be greater than `0`.
+						"""
+					relevant_when: "strategy = \"top_k\""
+					required:      true
+					type: uint: examples: [5]
+				}
+				keep: {
+					description:   "Which of several events holding equal values to keep the value and context fields of."
+					relevant_when: "strategy = \"max\" or strategy = \"min\""
+					required:      false
+					type: string: {
+						default: "first"
+						enum: {
+							first: "Keep the first of equal values seen."
+							last:  "Keep the last of equal values seen."
+						}
+					}
+				}
+				keep_last_as: {
+					description: """
+						The field to store the last numeric value seen in, alongside the sum.
+
+						When set, the sum is written to the original field and the last value is written to
+						this top-level field.
+						"""
+					relevant_when: "strategy = \"sum\""
+					required:      false
+					type: string: examples: ["value_last"]
+				}
+				key: {
+					description:   "The field of each object to group by."
+					relevant_when: "strategy = \"group_sum\""
+					required:      true
+					type: string: examples: ["sku"]
+				}
+				max: {
+					description: """
+						The maximum number of values to keep.
+
+						Must be greater than `0`.
+						"""
+					relevant_when: "strategy = \"sorted_unique\""
+					required:      true
+					type: uint: examples: [100]
+				}
+				max_len: {
+					description: """
+						The maximum number of elements to keep in the array.
+
+						What happens once the array is full is set by `on_overflow`. Must be greater than `0`.
+						"""
+					relevant_when: "strategy = \"array\""
+					required:      false
+					type: uint: examples: [100]
+				}
+				on_overflow: {
+					description:   "What to do once an array reaches its `max_len`."
+					relevant_when: "strategy = \"array\""
+					required:      false
+					type: string: {
+						default: "drop_new"
+						enum: {
+							drop_new:    "Discard new elements, keeping the oldest ones."
+							drop_oldest: "Discard the oldest elements to make room for new ones, keeping the newest ones."
+							flush:       "Flush the group with the event that fills the array."
+						}
+					}
+				}
+				output: {
+					description: "The field to write the result of the merge strategy to."
+					required:    true
+					type: string: examples: ["requests_sum"]
+				}
+				sigma: {
+					description: """
+						The number of standard deviations from the mean above which a value is an anomaly.
+
+						Must be greater than `0`.
+						"""
+					relevant_when: "strategy = \"zscore_flag\""
+					required:      true
+					type: float: examples: [3.0]
+				}
+				sort_by: {
+					description: """
+						A field to sort object elements of the array by.
+
+						Elements are sorted in ascending order when the group is flushed. Elements that are not
+						objects, or that are missing the field, are placed last. Elements with equal values
+						keep the order they were received in.
+						"""
+					relevant_when: "strategy = \"array\""
+					required:      false
+					type: string: examples: ["timestamp"]
+				}
+				start: {
+					description:   "The field containing the start timestamp of each event."
+					relevant_when: "strategy = \"sum_duration\""
+					required:      true
+					type: string: examples: ["started_at"]
+				}
+				strategy: {
+					description: "The merge strategy to apply."
+					required:    true
+					type: string: enum: {
+						all: """
+							Keep `true` only if every boolean value seen is `true`.
+
+							Values that are not booleans are skipped with a warning.
+							"""
+						any: """
+							Keep `true` if any boolean value seen is `true`.
+
+							Values that are not booleans are skipped with a warning.
+							"""
+						array: "Append each value to an array."
+						array_diff: """
+							Remove the elements of each later array from the first array seen.
+
+							The first array is the baseline, and each element of a later array removes one matching
+							element from it, as a multiset difference. Elements that are not present are ignored.
+							"""
+						avg: """
+							Compute the average of all numeric values, optionally weighted towards the most recent
+							ones.
+
+							Each time a value is received, the weights of the values before it are multiplied by
+							`decay`, so the last value has a weight of `1`, the one before it a weight of `decay`, and
+							so on.
+							"""
+						bbox: """
+							Compute the bounding box of coordinates given as objects with `lat` and `lon` fields.
+
+							The bounding box is emitted as an object with `min_lat`, `min_lon`, `max_lat`, and
+							`max_lon` fields. Values that are not coordinates are skipped.
+							"""
+						coalesce: """
+							Keep the first value found that is not `null` or an empty string.
+
+							If no such value is found, the first value is kept.
+							"""
+						concat:         "Concatenate each string value, delimited with a space."
+						concat_newline: "Concatenate each string value, delimited with a newline."
+						concat_raw:     "Concatenate each string, without a delimiter."
+						count:          "Count the number of non-null values seen."
+						discard:        "Discard all but the first value found."
+						distinct_count: """
+							Count the number of non-null values seen, along with how many of them are distinct.
+
+							The number of values is written to the field, and the number of distinct values to a
+							`[field-name]_distinct_count` field.
+							"""
+						ema: """
+							Compute an exponential moving average of all numeric values, in the order they are
+							received.
+							"""
+						flat_unique: "Create a flattened array of all unique values."
+						group_sum: """
+							Sum a numeric field of objects, grouped by another field of the objects.
+
+							Values are arrays of objects, or single objects. The sums are emitted as an array of
+							objects, in the order their keys were first seen, each containing the `key` field and the
+							sum in a field named after `value` with a `_total` suffix. Objects missing either field, or
+							with a non-numeric value, are skipped.
+							"""
+						last_by_timestamp: """
+							Keep the value from the event with the latest timestamp, regardless of the order events are
+							received in.
+
+							A value only replaces the current one if its event's timestamp is later than the timestamp
+							of the event that set the current value. Events missing the timestamp are skipped.
+							"""
+						longest_array: "Keep the longest array seen."
+						max:           "Keep the maximum numeric value seen."
+						min:           "Keep the minimum numeric value seen."
+						retain: """
+							Discard all but the last value found.
+
+							Works as a way to coalesce by not retaining `null`.
+							"""
+						shortest_array: "Keep the shortest array seen."
+						sorted_unique: """
+							Create a sorted array of the unique values seen, keeping at most `max` of them.
+
+							Arrays are flattened into their elements, and `null` values are skipped. The array is
+							sorted in ascending order, and once it is full, only the `max` smallest values are kept.
+							"""
+						sum:            "Sum all numeric values."
+						sum_duration: """
+							Sum the durations, in milliseconds, between a start and an end timestamp of each event.
+
+							The merged field itself is not read from events. Events missing either timestamp are
+							skipped.
+							"""
+						toggle_count: """
+							Count the number of times a boolean value changed from one event to the next.
+
+							Values that are not booleans are skipped with a warning.
+							"""
+						top_k: """
+							Keep the most frequent values seen, along with how many times each was seen.
+
+							The values are emitted as an array of objects with `value` and `count` fields, ordered
+							from most to least frequent. Values seen equally often are ordered by when they were first
+							seen.
+							"""
+						zscore_flag: """
+							Keep the last numeric value seen, and flag whether it is an anomaly.
+
+							The last value is compared to the mean and standard deviation of the values before it. A
+							`[field-name]_anomaly` field is added, set to `true` if the last value deviates from the
+							mean by more than `sigma` standard deviations. With fewer than three values before the last
+							one, there is too little data to judge, so it is set to `false`.
+							"""
+					}
+				}
+				timestamp_field: {
+					description:   "The field containing the timestamp of each event."
+					relevant_when: "strategy = \"last_by_timestamp\""
+					required:      true
+					type: string: examples: ["updated_at"]
+				}
+				unit_field: {
+					description: """
+						The field holding the unit of each value, such as `MB`.
+
+						When set, only values with the same unit as the first value of the group are summed,
+						with a missing unit treated as a unit of its own. Values with a different unit are skipped
+						with a warning. The unit is written to this field alongside the sum.
+						"""
+					relevant_when: "strategy = \"sum\""
+					required:      false
+					type: string: examples: ["unit"]
+				}
+				value: {
+					description:   "The numeric field of each object to sum."
+					relevant_when: "strategy = \"group_sum\""
+					required:      true
+					type: string: examples: ["qty"]
+				}
+			}
+		}
+	}
+	float_precision: {
+		description: """
+			The number of decimal places to round float values of combined events to.
+
+			Merging floats, such as summing or averaging them, can produce values with long, noisy
+			decimals. When set, each float value of a combined event, including those nested in
+			objects and arrays, is rounded when the group is flushed.
+			"""
+		required: false
+		type: uint: examples: [2]
+	}
+	flush_on_field_change: {
+		description: """
+			A list of fields whose values must stay the same within a group.
+
+			The first value of each field in a group is kept, and the group is flushed before an event
+			with a different value is added to a new group. This avoids merging incompatible events,
+			such as those of different versions of a schema. Events without a field do not affect it.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["schema_version"]
+		}
+	}
+	flush_on_sequence_gap: {
+		description: """
+			Flushes a group when an event's sequence number does not follow the previous one.
+
+			A gap in the sequence indicates a lost event or a boundary between transactions. The
+			group is flushed before the event that follows the gap is added to a new group.
+			"""
+		required: false
+		type: object: options: field: {
+			description: """
+				The integer field containing the sequence number of each event.
+
+				Each event is expected to have a sequence number one greater than the previous event of
+				its group. Events without the field do not affect the expected sequence number.
+				"""
+			required: true
+			type: string: examples: ["seq"]
+		}
+	}
+	flush_on_sum: {
+		description: """
+			Configuration for flushing groups once the sum of a field exceeds a threshold.
+
+			The values of the field are summed across the events of each group, and a group is flushed
+			with the event that brings the sum above `threshold`. This allows batching events by volume,
+			such as by the number of bytes they describe.
+			"""
+		required: false
+		type: object: options: {
+			field: {
+				description: """
+					The numeric field to sum.
+
+					Events without the field, or with a non-numeric value, do not affect the sum.
+					"""
+				required: true
+				type: string: examples: ["bytes"]
+			}
+			threshold: {
+				description: "The sum above which a group is flushed."
+				required:    true
+				type: float: examples: [1048576.0]
+			}
+		}
+	}
 	flush_period_ms: {
 		description: "The interval to check for and flush any expired events, in milliseconds."
 		required:    false
@@ -30,6 +621,44 @@ base: components: transforms: reduce: configuration: {
 			unit:    "milliseconds"
 		}
 	}
+	flush_priority_field: {
+		description: """
+			A field to order the combined events flushed together by, from the highest value to the
+			lowest.
+
+			When several groups expire in the same flush, their combined events are emitted in
+			descending order of this field, so downstream components receive the highest priority
+			aggregates first. Events without the field are emitted last, in no particular order.
+			"""
+		required: false
+		type: string: examples: ["severity"]
+	}
+	flush_under_pressure_bytes: {
+		description: """
+			The approximate size, in bytes, of all groups held in memory above which the oldest
+			groups are flushed early.
+
+			This is the same limit as `max_total_bytes`, but it is only checked once every
+			`flush_period_ms` rather than after each event, so the size of the groups can exceed it
+			between checks. When it is exceeded, the oldest groups are flushed until the size is back
+			within it.
+			"""
+		required: false
+		type: uint: unit: "bytes"
+	}
+	flush_vrl: {
+		description: """
+			A [Vector Remap Language][vrl] (VRL) program to run on each combined event before it is
+			emitted.
+
+			This can be used to compute derived fields from the combined event or to clean it up. If
+			the program fails, a warning is logged and the combined event is emitted unchanged.
+
+			[vrl]: https://vector.dev/docs/reference/vrl
+			"""
+		required: false
+		type: string: examples: [".average = .total / .count ?? 0"]
+	}
 	group_by: {
 		description: """
 			An ordered list of fields by which to group events.
@@ -47,46 +676,641 @@ base: components: transforms: reduce: configuration: {
 			items: type: string: examples: ["request_id", "user_id", "transaction_id"]
 		}
 	}
+	group_by_coerce_to_string: {
+		description: """
+			Whether or not to convert the values of the `group_by` fields to strings before grouping.
+
+			By default, values of different types are grouped separately, so an event where `host` is
+			the number `1` is not grouped with an event where `host` is the string `"1"`. When enabled,
+			both are grouped together.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	group_object_field: {
+		description: """
+			The field to store the values of the `group_by` fields in, as a single object.
+
+			When set, each combined event carries an object mapping each of the `group_by` fields to
+			the value it had for that group. Fields missing from the events of a group are omitted.
+			"""
+		required: false
+		type: string: examples: ["group"]
+	}
 	max_events: {
 		description: "The maximum number of events to group together."
 		required:    false
 		type: uint: {}
 	}
+	max_fields_per_group: {
+		description: """
+			The maximum number of distinct fields to keep in each group.
+
+			Once a group has this many fields, fields that are not yet part of the group are dropped
+			from subsequent events. Values of fields already in the group are still merged. This
+			bounds the size of groups when malformed events contain many distinct keys.
+			"""
+		required: false
+		type: uint: examples: [100]
+	}
+	max_group_bytes: {
+		description: """
+			The maximum approximate size, in bytes, of the merged fields of each group.
+
+			A group is flushed with the event that brings its size above this limit, and the events
+			that follow start a new group. This bounds the memory held by groups that accumulate large
+			fields, such as ones merged with the `array` strategy.
+			"""
+		required: false
+		type: uint: unit: "bytes"
+	}
+	max_lifetime_ms: {
+		description: """
+			The maximum duration, in milliseconds, that a group can stay open, measured from when it
+			was created.
+
+			Unlike `expire_after_ms`, this does not depend on when the last event was received, so a
+			group that keeps receiving events is still flushed once it reaches this age. Groups are
+			checked for every `flush_period_ms`.
+			"""
+		required: false
+		type: uint: {
+			examples: [60000]
+			unit:     "milliseconds"
+		}
+	}
+	max_total_bytes: {
+		description: """
+			The maximum approximate size, in bytes, of all groups held in memory.
+
+			When this limit is exceeded, the oldest groups are flushed until the total size is back
+			under the limit.
+			"""
+		required: false
+		type: uint: unit: "bytes"
+	}
 	merge_strategies: {
 		description: """
 			A map of field names to custom merge strategies.
 
 			For each field specified, the given strategy is used for combining events rather than
-			the default behavior.
+			the default behavior. Field names are interpreted as paths, so nested fields such as
+			`payload.counter` can be given their own strategy.
 
 			The default behavior is as follows:
 
 			- The first value of a string field is kept and subsequent values are discarded.
 			- For timestamp fields the first is kept and a new field `[field-name]_end` is added with
-			  the last received timestamp value.
+			  the last received timestamp value. This can be changed with `timestamp_strategy`.
 			- Numeric values are summed.
 			"""
 		required: false
 		type: object: options: "*": {
 			description: "An individual merge strategy."
 			required:    true
-			type: string: enum: {
-				array:          "Append each value to an array."
-				concat:         "Concatenate each string value, delimited with a space."
-				concat_newline: "Concatenate each string value, delimited with a newline."
-				concat_raw:     "Concatenate each string, without a delimiter."
-				discard:        "Discard all but the first value found."
-				flat_unique:    "Create a flattened array of all unique values."
-				longest_array:  "Keep the longest array seen."
-				max:            "Keep the maximum numeric value seen."
-				min:            "Keep the minimum numeric value seen."
-				retain: """
-					Discard all but the last value found.
-
-					Works as a way to coalesce by not retaining `null`.
+			type: object: options: {
+				alpha: {
+					description: """
+						The smoothing factor applied to each new value.
+
+						Must be greater than `0` and at most `1`. Higher values discount older values faster.
+						"""
+					relevant_when: "strategy = \"ema\""
+					required:      true
+					type: float: {}
+				}
+				context_fields: {
+					description: """
+						The top-level fields to take from the event holding the kept value.
+
+						When set, these fields are not merged on their own. Instead, the combined event carries
+						the values they had in the event holding the kept value, with `keep` choosing between
+						events holding equal values. Fields missing from that event are left out.
+						"""
+					relevant_when: "strategy = \"max\" or strategy = \"min\""
+					required:      false
+					type: array: {
+						default: []
+						items: type: string: examples: ["host"]
+					}
+				}
+				decay: {
+					description: """
+						The factor that the weights of earlier values are multiplied by for each new value.
+
+						Must be greater than `0` and at most `1`. Lower values discount older values faster, and
+						`1` weights all values equally.
+						"""
+					relevant_when: "strategy = \"avg\""
+					required:      false
+					type: float: {
+						default: 1.0
+						examples: [0.9]
+					}
+				}
+				end: {
+					description:   "The field containing the end timestamp of each event."
+					relevant_when: "strategy = \"sum_duration\""
+					required:      true
+					type: string: examples: ["ended_at"]
+				}
+				k: {
+					description: """
+						The number of values to keep.
+
+						Must be greater than `0`.
+						"""
+					relevant_when: "strategy = \"top_k\""
+					required:      true
+					type: uint: examples: [5]
+				}
+				keep: {
+					description:   "Which of several events holding equal values to keep the value and context fields of."
+					relevant_when: "strategy = \"max\" or strategy = \"min\""
+					required:      false
+					type: string: {
+						default: "first"
+						enum: {
+							first: "Keep the first of equal values seen."
+							last:  "Keep the last of equal values seen."
+						}
+					}
+				}
+				keep_last_as: {
+					description: """
+						The field to store the last numeric value seen in, alongside the sum.
+
+						When set, the sum is written to the original field and the last value is written to
+						this top-level field.
+						"""
+					relevant_when: "strategy = \"sum\""
+					required:      false
+					type: string: examples: ["value_last"]
+				}
+				key: {
+					description:   "The field of each object to group by."
+					relevant_when: "strategy = \"group_sum\""
+					required:      true
+					type: string: examples: ["sku"]
+				}
+				max: {
+					description: """
+						The maximum number of values to keep.
+
+						Must be greater than `0`.
+						"""
+					relevant_when: "strategy = \"sorted_unique\""
+					required:      true
+					type: uint: examples: [100]
+				}
+				max_len: {
+					description: """
+						The maximum number of elements to keep in the array.
+
+						What happens once the array is full is set by `on_overflow`. Must be greater than `0`.
+						"""
+					relevant_when: "strategy = \"array\""
+					required:      false
+					type: uint: examples: [100]
+				}
+				on_overflow: {
+					description:   "What to do once an array reaches its `max_len`."
+					relevant_when: "strategy = \"array\""
+					required:      false
+					type: string: {
+						default: "drop_new"
+						enum: {
+							drop_new:    "Discard new elements, keeping the oldest ones."
+							drop_oldest: "Discard the oldest elements to make room for new ones, keeping the newest ones."
+							flush:       "Flush the group with the event that fills the array."
+						}
+					}
+				}
+				sigma: {
+					description: """
+						The number of standard deviations from the mean above which a value is an anomaly.
+
+						Must be greater than `0`.
+						"""
+					relevant_when: "strategy = \"zscore_flag\""
+					required:      true
+					type: float: examples: [3.0]
+				}
+				sort_by: {
+					description: """
+						A field to sort object elements of the array by.
+
+						Elements are sorted in ascending order when the group is flushed. Elements that are not
+						objects, or that are missing the field, are placed last. Elements with equal values
+						keep the order they were received in.
+						"""
+					relevant_when: "strategy = \"array\""
+					required:      false
+					type: string: examples: ["timestamp"]
+				}
+				start: {
+					description:   "The field containing the start timestamp of each event."
+					relevant_when: "strategy = \"sum_duration\""
+					required:      true
+					type: string: examples: ["started_at"]
+				}
+				strategy: {
+					description: "The merge strategy to apply."
+					required:    true
+					type: string: enum: {
+						all: """
+							Keep `true` only if every boolean value seen is `true`.
+
+							Values that are not booleans are skipped with a warning.
+							"""
+						any: """
+							Keep `true` if any boolean value seen is `true`.
+
+							Values that are not booleans are skipped with a warning.
+							"""
+						array: "Append each value to an array."
+						array_diff: """
+							Remove the elements of each later array from the first array seen.
+
+							The first array is the baseline, and each element of a later array removes one matching
+							element from it, as a multiset difference. Elements that are not present are ignored.
+							"""
+						avg: """
+							Compute the average of all numeric values, optionally weighted towards the most recent
+							ones.
+
+							Each time a value is received, the weights of the values before it are multiplied by
+							`decay`, so the last value has a weight of `1`, the one before it a weight of `decay`, and
+							so on.
+							"""
+						bbox: """
+							Compute the bounding box of coordinates given as objects with `lat` and `lon` fields.
+
+							The bounding box is emitted as an object with `min_lat`, `min_lon`, `max_lat`, and
+							`max_lon` fields. Values that are not coordinates are skipped.
+							"""
+						coalesce: """
+							Keep the first value found that is not `null` or an empty string.
+
+							If no such value is found, the first value is kept.
+							"""
+						concat:         "Concatenate each string value, delimited with a space."
+						concat_newline: "Concatenate each string value, delimited with a newline."
+						concat_raw:     "Concatenate each string, without a delimiter."
+						count:          "Count the number of non-null values seen."
+						discard:        "Discard all but the first value found."
+						distinct_count: """
+							Count the number of non-null values seen, along with how many of them are distinct.
+
+							The number of values is written to the field, and the number of distinct values to a
+							`[field-name]_distinct_count` field.
+							"""
+						ema: """
+							Compute an exponential moving average of all numeric values, in the order they are
+							received.
+							"""
+						flat_unique: "Create a flattened array of all unique values."
+						group_sum: """
+							Sum a numeric field of objects, grouped by another field of the objects.
+
+							Values are arrays of objects, or single objects. The sums are emitted as an array of
+							objects, in the order their keys were first seen, each containing the `key` field and the
+							sum in a field named after `value` with a `_total` suffix. Objects missing either field, or
+							with a non-numeric value, are skipped.
+							"""
+						last_by_timestamp: """
+							Keep the value from the event with the latest timestamp, regardless of the order events are
+							received in.
+
+							A value only replaces the current one if its event's timestamp is later than the timestamp
+							of the event that set the current value. Events missing the timestamp are skipped.
+							"""
+						longest_array: "Keep the longest array seen."
+						max:           "Keep the maximum numeric value seen."
+						min:           "Keep the minimum numeric value seen."
+						retain: """
+							Discard all but the last value found.
+
+							Works as a way to coalesce by not retaining `null`.
+							"""
+						shortest_array: "Keep the shortest array seen."
+						sorted_unique: """
+							Create a sorted array of the unique values seen, keeping at most `max` of them.
+
+							Arrays are flattened into their elements, and `null` values are skipped. The array is
+							sorted in ascending order, and once it is full, only the `max` smallest values are kept.
+							"""
+						sum:            "Sum all numeric values."
+						sum_duration: """
+							Sum the durations, in milliseconds, between a start and an end timestamp of each event.
+
+							The merged field itself is not read from events. Events missing either timestamp are
+							skipped.
+							"""
+						toggle_count: """
+							Count the number of times a boolean value changed from one event to the next.
+
+							Values that are not booleans are skipped with a warning.
+							"""
+						top_k: """
+							Keep the most frequent values seen, along with how many times each was seen.
+
+							The values are emitted as an array of objects with `value` and `count` fields, ordered
+							from most to least frequent. Values seen equally often are ordered by when they were first
+							seen.
+							"""
+						zscore_flag: """
+							Keep the last numeric value seen, and flag whether it is an anomaly.
+
+							The last value is compared to the mean and standard deviation of the values before it. A
+							`[field-name]_anomaly` field is added, set to `true` if the last value deviates from the
+							mean by more than `sigma` standard deviations. With fewer than three values before the last
+							one, there is too little data to judge, so it is set to `false`.
+							"""
+					}
+				}
+				timestamp_field: {
+					description:   "The field containing the timestamp of each event."
+					relevant_when: "strategy = \"last_by_timestamp\""
+					required:      true
+					type: string: examples: ["updated_at"]
+				}
+				unit_field: {
+					description: """
+						The field holding the unit of each value, such as `MB`.
+
+						When set, only values with the same unit as the first value of the group are summed,
+						with a missing unit treated as a unit of its own. Values with a different unit are skipped
+						with a warning. The unit is written to this field alongside the sum.
+						"""
+					relevant_when: "strategy = \"sum\""
+					required:      false
+					type: string: examples: ["unit"]
+				}
+				value: {
+					description:   "The numeric field of each object to sum."
+					relevant_when: "strategy = \"group_sum\""
+					required:      true
+					type: string: examples: ["qty"]
+				}
+			}
+		}
+	}
+	output_label_keys: {
+		description: """
+			The fields whose values label each group, in place of `group_by`.
+
+			These are the fields stored in `group_object_field` and carried by lifecycle events.
+			Defaults to the `group_by` fields.
+			"""
+		required: false
+		type: array: items: type: string: examples: ["service"]
+	}
+	pause_when: {
+		description: """
+			A condition used to pause the aggregation of a group.
+
+			The event matching this condition is added to its group, and the following events of the
+			group are handled according to `paused_events` until an event matches `resume_when`, which
+			must be set as well. While paused, the group does not expire, but is still flushed once it
+			reaches `max_lifetime_ms` or quarantined once it reaches `quarantine_after_ms`.
+			"""
+		required: false
+		type: condition: {}
+	}
+	paused_events: {
+		description: "What to do with the events of a group received while it is paused."
+		required:    false
+		type: string: {
+			default: "drop"
+			enum: {
+				drop:        "Drop the events."
+				passthrough: "Forward the events, unchanged, to the `passthrough` output."
+			}
+		}
+	}
+	profiling: {
+		description: """
+			Whether or not to record the time spent finding the group of each event.
+
+			When enabled, the time spent computing the key of each event's group and looking the group
+			up is recorded in the `group_lookup_duration_seconds` histogram. This helps diagnose slow
+			grouping with many distinct groups, at the cost of timing every event.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	quarantine_after_ms: {
+		description: """
+			The duration, in milliseconds, after which a group that has not completed is considered
+			stuck, measured from when it was created.
+
+			Stuck groups are removed and their combined event, marked with `_incomplete` set to `true`,
+			is sent to the `quarantine` output instead of the default one, so that they can be
+			inspected separately. This is meant to be longer than `expire_after_ms` and
+			`max_lifetime_ms`. Groups are checked for every `flush_period_ms`.
+			"""
+		required: false
+		type: uint: {
+			examples: [3600000]
+			unit:     "milliseconds"
+		}
+	}
+	reorder_by: {
+		description: """
+			A timestamp field to order the events of each group by before they are merged.
+
+			Events are held back and merged in the order of this field, so that strategies such as
+			`concat` and `array` reflect the order in which events occurred rather than the order in
+			which they arrived. Events without a timestamp in this field are merged as they arrive.
+			"""
+		required: false
+		type: string: examples: ["timestamp"]
+	}
+	reorder_window_ms: {
+		description: """
+			How far behind the latest event of a group an event can be and still be reordered, in
+			milliseconds, when `reorder_by` is set.
+
+			Held events older than this, relative to the latest timestamp seen by the group, are
+			merged, and the rest are merged when the group is flushed. Events that arrive later than
+			this are merged as they arrive.
+			"""
+		required: false
+		type: uint: {
+			default: 1000
+			unit:    "milliseconds"
+		}
+	}
+	require_schema_field: {
+		description: """
+			A field whose value identifies the schema of each event.
+
+			Each group is locked to the value of this field in its first event, and later events with
+			a different value, or without the field when the first event had it, are sent to the
+			`rejected` output instead of being merged. This prevents merging heterogeneous events into
+			a single group.
+			"""
+		required: false
+		type: string: examples: ["schema_version"]
+	}
+	resume_when: {
+		description: """
+			A condition used to resume the aggregation of a paused group.
+
+			The event matching this condition is added to its group, along with the following events.
+			"""
+		required: false
+		type: condition: {}
+	}
+	route_when: {
+		description: """
+			A table of output names to conditions, used to route each combined event to an output.
+
+			The conditions are evaluated in order against each combined event when its group is
+			flushed, and the event is sent to the output of the first matching condition, which can be
+			referenced as an input by other components with the name `<transform_name>.<output_name>`.
+			Combined events that match no condition are sent to the default output. The names of the
+			other outputs of this transform, such as `passthrough`, are reserved.
+			"""
+		required: false
+		type: object: options: "*": {
+			description: "The condition for routing combined events to the output."
+			required:    true
+			type: condition: {}
+		}
+	}
+	sample_rate: {
+		description: """
+			The fraction of the events of each group to merge, to reduce the cost of very busy groups.
+
+			Each event is merged with this probability, and the fields of the others are discarded.
+			When a group is flushed, the summed and counted fields, including numeric fields summed by
+			default, are scaled by the inverse of this rate, so that they estimate the values over all
+			events. Events left out still count towards `max_events`, and events that end a group are
+			always merged. Must be greater than `0` and at most `1`.
+			"""
+		required: false
+		type: float: examples: [0.1]
+	}
+	sample_seed: {
+		description: """
+			A seed for the random sampling of events, when `sample_rate` is set.
+
+			By default, sampling is randomly seeded each time the transform starts. Setting a seed
+			makes the sampled events reproducible across runs with the same input.
+			"""
+		required: false
+		type: uint: {}
+	}
+	slow_threshold_ms: {
+		description: """
+			The duration, in milliseconds, above which a transaction is considered slow.
+
+			When a group completed by `ends_when` or `starts_when` was open for longer than this, the
+			combined event is marked with a `_slow` field set to `true`. Groups flushed for other
+			reasons, such as expiring, are not marked.
+			"""
+		required: false
+		type: uint: {
+			examples: [5000]
+			unit:     "milliseconds"
+		}
+	}
+	snapshot_interval_ms: {
+		description: """
+			The interval at which to emit a snapshot of each open group, in milliseconds.
+
+			A snapshot is the combined event of a group so far. The group stays open and keeps
+			accumulating events. Each snapshot carries a `_snapshot_seq` field, counting up from zero for
+			each group, so downstream components can order the snapshots of a group and detect gaps.
+			Snapshots are checked for every `flush_period_ms`.
+			"""
+		required: false
+		type: uint: {
+			examples: [10000]
+			unit:     "milliseconds"
+		}
+	}
+	span_fields: {
+		description: """
+			Adds span fields to each combined event.
+
+			When set, the timestamps of the first and last events of a transaction, along with the
+			duration between them, are added to the combined event. This is most useful when
+			transactions are delimited with `starts_when` or `ends_when`.
+			"""
+		required: false
+		type: object: options: {
+			duration_ms: {
+				description: """
+					The field to store the duration between the first and last event of the transaction in,
+					in milliseconds.
 					"""
-				shortest_array: "Keep the shortest array seen."
-				sum:            "Sum all numeric values."
+				required: false
+				type: string: default: "duration_ms"
+			}
+			end_time: {
+				description: "The field to store the timestamp of the last event of the transaction in."
+				required:    false
+				type: string: default: "end_time"
+			}
+			start_time: {
+				description: "The field to store the timestamp of the first event of the transaction in."
+				required:    false
+				type: string: default: "start_time"
+			}
+		}
+	}
+	spill: {
+		description: """
+			Configuration for writing a large field of combined events to files.
+
+			When the field of a combined event is larger than `threshold_bytes`, its value is written to a
+			new file, and the field is replaced with the path of the file, so that downstream components
+			only handle a reference to it. Snapshots and checkpoints of open groups are never written to
+			files. Unless `remove_after_secs` is set, Vector does not remove the files; whatever reads them
+			is responsible for deleting them once they are no longer needed.
+			"""
+		required: false
+		type: object: options: {
+			dir: {
+				description: """
+					The directory to write the files to.
+
+					If not set, the temporary directory of the system is used.
+					"""
+				required: false
+				type: string: examples: ["/var/lib/vector/reduce"]
+			}
+			field: {
+				description: """
+					The field to write to a file.
+
+					String values are written as they are, and other values are written as JSON.
+					"""
+				required: true
+				type: string: examples: ["body"]
+			}
+			remove_after_secs: {
+				description: """
+					The number of seconds after which Vector removes the files it wrote.
+
+					Files that are still waiting to be removed when Vector stops are left in place.
+					"""
+				required: false
+				type: uint: {
+					examples: [3600]
+					unit:     "seconds"
+				}
+			}
+			threshold_bytes: {
+				description: "The size, in bytes, above which the field is written to a file."
+				required:    true
+				type: uint: {
+					examples: [1048576]
+					unit:     "bytes"
+				}
 			}
 		}
 	}
@@ -100,4 +1324,103 @@ base: components: transforms: reduce: configuration: {
 		required: false
 		type: condition: {}
 	}
+	sticky_by: {
+		description: """
+			A field identifying the same entity across events whose `group_by` fields change.
+
+			The first event with a given value of this field decides the group of all later events
+			with the same value, even if their `group_by` fields differ. The mapping is forgotten once
+			the group is no longer open.
+			"""
+		required: false
+		type: string: examples: ["session_id"]
+	}
+	summary_template: {
+		description: """
+			A template for a short, human-readable summary of each combined event.
+
+			The template is rendered against the combined event, and the result is stored in a
+			`_summary` field, so the event can be consumed both in full and as a single line. If the
+			template fails to render, the field is not set.
+			"""
+		required: false
+		type: string: {
+			examples: ["{{ request_id }}: {{ count }} requests"]
+			syntax: "template"
+		}
+	}
+	synthesize_message_from_group: {
+		description: """
+			Whether or not to give combined events without a message one made of their group.
+
+			When enabled, a combined event that has no `message` field is given one listing each of
+			the `group_by` fields and the value it had for that group, such as `host=a region=x`.
+			Fields missing from the events of a group are omitted.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	tee: {
+		description: """
+			Whether or not to also forward each event, unchanged, to the `passthrough` output.
+
+			Events are still reduced and sent to the default output as usual. The forwarded events
+			share their finalizers with the events being reduced, so when end-to-end acknowledgements
+			are enabled, an event is only acknowledged once both its forwarded copy and the combined
+			event it is part of have been delivered.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	timestamp_strategy: {
+		description: "How to merge timestamp fields that have no merge strategy."
+		required:    false
+		type: string: {
+			default: "keep_first_with_end"
+			enum: {
+				keep_first:          "Keep the first timestamp."
+				keep_first_with_end: "Keep the first timestamp, and add a `[field-name]_end` field with the last one."
+				keep_last:           "Keep the last timestamp."
+				range: """
+					Replace the field with an object holding the first timestamp in `start` and the last one
+					in `end`.
+					"""
+			}
+		}
+	}
+	track_timestamp_bounds: {
+		description: """
+			Whether or not to add the earliest and latest timestamps of each group.
+
+			When enabled, the earliest and latest values of all top-level timestamp fields across the
+			events of a group are added to the combined event as `_min_timestamp` and
+			`_max_timestamp`.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	treat_empty_as_missing: {
+		description: """
+			Whether or not to treat `group_by` fields set to an empty string as missing.
+
+			When enabled, events where a `group_by` field is an empty string are grouped together with
+			events where the field is missing.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	warmup_ms: {
+		description: """
+			The time after startup during which expired groups are not flushed, in milliseconds.
+
+			This gives groups time to accumulate events after a cold start, rather than flushing small
+			partial groups. Groups are still flushed by `ends_when`, `starts_when`, and `max_events`
+			during this time.
+			"""
+		required: false
+		type: uint: {
+			default: 0
+			unit:    "milliseconds"
+		}
+	}
 }
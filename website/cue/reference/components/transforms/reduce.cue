@@ -144,7 +144,85 @@ components: transforms: reduce: {
 		},
 	]
 
+	outputs: [
+		components._default_output,
+		{
+			name: "passthrough"
+			description: """
+				When `tee` is set to `true`, every input event is also forwarded, unchanged, to the
+				`passthrough` output while still being reduced for the default output. When
+				`paused_events` is set to `passthrough`, events received while their group is paused
+				are forwarded to it instead of being dropped. For a transform component named `foo`,
+				this output can be accessed by specifying `foo.passthrough` as the input to another
+				component.
+				"""
+		},
+		{
+			name: "metrics"
+			description: """
+				When `emit_metric` is set, a counter metric is sent to the `metrics` output each time
+				a group is flushed. For a transform component named `foo`, this output can be accessed
+				by specifying `foo.metrics` as the input to another component.
+				"""
+		},
+		{
+			name: "lifecycle"
+			description: """
+				When `emit_lifecycle` is set to `true`, an event with a `_lifecycle` field set to
+				`start` is sent to the `lifecycle` output when a group is opened, and one set to `end`
+				after the group is flushed. For a transform component named `foo`, this output can be
+				accessed by specifying `foo.lifecycle` as the input to another component.
+				"""
+		},
+		{
+			name: "checkpoint"
+			description: """
+				When `checkpoint_every` is set, the combined event of a group so far is sent to the
+				`checkpoint` output every time the group has received a multiple of that many events.
+				For a transform component named `foo`, this output can be accessed by specifying
+				`foo.checkpoint` as the input to another component.
+				"""
+		},
+		{
+			name: "rejected"
+			description: """
+				When `require_schema_field` is set, events whose value of that field differs from the
+				one their group is locked to are sent, unchanged, to the `rejected` output instead of
+				being merged. For a transform component named `foo`, this output can be accessed by
+				specifying `foo.rejected` as the input to another component.
+				"""
+		},
+		{
+			name: "first"
+			description: """
+				When `emit_first_immediately` is set, the first event of each group is sent, unchanged,
+				to the `first` output as soon as the group is opened. For a transform component named
+				`foo`, this output can be accessed by specifying `foo.first` as the input to another
+				component.
+				"""
+		},
+		{
+			name: "quarantine"
+			description: """
+				When `quarantine_after_ms` is set, groups that have not completed within that duration
+				are removed and their combined event, marked with `_incomplete`, is sent to the
+				`quarantine` output. For a transform component named `foo`, this output can be accessed
+				by specifying `foo.quarantine` as the input to another component.
+				"""
+		},
+		{
+			name:        "<route_when_output>"
+			description: "Each output of `route_when` can be referenced as an input by other components with the name `<transform_name>.<output_name>`."
+		},
+	]
+
 	telemetry: metrics: {
-		stale_events_flushed_total: components.sources.internal_metrics.output.metrics.stale_events_flushed_total
+		stale_events_flushed_total:    components.sources.internal_metrics.output.metrics.stale_events_flushed_total
+		groups_evicted_total:          components.sources.internal_metrics.output.metrics.groups_evicted_total
+		fields_dropped_total:          components.sources.internal_metrics.output.metrics.fields_dropped_total
+		slow_transactions_total:       components.sources.internal_metrics.output.metrics.slow_transactions_total
+		group_lookup_duration_seconds: components.sources.internal_metrics.output.metrics.group_lookup_duration_seconds
+		open_group_age_seconds:        components.sources.internal_metrics.output.metrics.open_group_age_seconds
+		groups_flushed_per_tick:       components.sources.internal_metrics.output.metrics.groups_flushed_per_tick
 	}
 }